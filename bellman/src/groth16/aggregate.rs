@@ -0,0 +1,1040 @@
+//! Aggregation of Groth16 proofs, following SnarkPack (Gailly, Maller and
+//! Nitulescu, 2021).
+//!
+//! `aggregate_proofs` compresses `n` proofs under the same verifying key
+//! into an `AggregateProof` of `O(log n)` group elements, which
+//! `verify_aggregate_proof` checks in `O(log n)` pairings plus a pass over
+//! the public inputs.
+//!
+//! The prover commits to the `A`, `B` and `C` points of all proofs, derives a
+//! random `r` from the commitments and shows with inner pairing product
+//! arguments (TIPP for `A` and `B`, MIPP for `C`) that
+//!
+//! ```text
+//! ip_ab = prod e(A_i, B_i)^(r^i)   and   agg_c = sum r^i * C_i
+//! ```
+//!
+//! for the committed points. The verifier then checks the random linear
+//! combination of the verification equations of all proofs, as
+//! `verify_proofs_batch` does with the proofs themselves:
+//!
+//! ```text
+//! ip_ab = e(alpha, beta)^(sum r^i) * e(sum r^i * inputs_i, gamma) * e(agg_c, delta)
+//! ```
+//!
+//! The commitments need an `SRS` of powers of two secrets `a` and `b` in G1
+//! and G2, independent of the circuit. `SRS::setup` samples them for tests;
+//! in production the powers come from two powers-of-tau ceremonies, see
+//! `SRS::from_powers`. Both inner product arguments run in one GIPA loop with
+//! shared challenges and end with KZG openings of the folded commitment keys.
+//!
+//! The number of proofs is padded to a power of two by repeating the last
+//! proof; the verifier pads the public inputs the same way.
+
+use pairing::{
+    Engine,
+    CurveProjective,
+    CurveAffine,
+    EncodedPoint
+};
+
+use ff::{
+    Field,
+    PrimeField,
+    PrimeFieldRepr
+};
+
+use blake2_rfc::blake2b::Blake2b;
+use byteorder::{BigEndian, ByteOrder, ReadBytesExt, WriteBytesExt};
+use rand::{ChaChaRng, Rng, SeedableRng};
+
+use std::io::{self, Read, Write};
+use std::mem;
+
+use super::{
+    Proof,
+    PreparedVerifyingKey
+};
+
+use ::{
+    SynthesisError
+};
+
+/// Engines whose target group elements can be written and read, which
+/// aggregate proofs and their transcript need.
+pub trait AggregateEngine: Engine {
+    fn write_gt<W: Write>(gt: &Self::Fqk, writer: W) -> io::Result<()>;
+
+    /// Reads an element of the extension field; `read_gt` checks it is in
+    /// the target group.
+    fn read_fqk<R: Read>(reader: R) -> io::Result<Self::Fqk>;
+}
+
+macro_rules! impl_aggregate_engine {
+    ($curve:ident, $engine:ident) => {
+        impl AggregateEngine for ::pairing::$curve::$engine {
+            fn write_gt<W: Write>(gt: &Self::Fqk, mut writer: W) -> io::Result<()> {
+                for c6 in &[gt.c0, gt.c1] {
+                    for c2 in &[c6.c0, c6.c1, c6.c2] {
+                        c2.c0.into_repr().write_be(&mut writer)?;
+                        c2.c1.into_repr().write_be(&mut writer)?;
+                    }
+                }
+
+                Ok(())
+            }
+
+            fn read_fqk<R: Read>(mut reader: R) -> io::Result<Self::Fqk> {
+                use pairing::$curve::{Fq, FqRepr, Fq2, Fq6, Fq12};
+
+                let mut fq = || -> io::Result<Fq> {
+                    let mut repr = FqRepr::default();
+                    repr.read_be(&mut reader)?;
+                    Fq::from_repr(repr).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))
+                };
+                let mut fq2 = || -> io::Result<Fq2> {
+                    Ok(Fq2 { c0: fq()?, c1: fq()? })
+                };
+                let mut fq6 = || -> io::Result<Fq6> {
+                    Ok(Fq6 { c0: fq2()?, c1: fq2()?, c2: fq2()? })
+                };
+
+                Ok(Fq12 { c0: fq6()?, c1: fq6()? })
+            }
+        }
+    }
+}
+
+impl_aggregate_engine!(bn256, Bn256);
+impl_aggregate_engine!(bls12_381, Bls12);
+
+fn read_gt<E: AggregateEngine, R: Read>(reader: R) -> io::Result<E::Fqk> {
+    let gt = E::read_fqk(reader)?;
+    if gt.pow(E::Fr::char()) != E::Fqk::one() {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "not an element of the target group"));
+    }
+
+    Ok(gt)
+}
+
+fn write_point<W: Write, G: CurveAffine>(mut writer: W, point: &G) -> io::Result<()> {
+    writer.write_all(point.into_uncompressed().as_ref())
+}
+
+fn read_point<R: Read, G: CurveAffine>(mut reader: R) -> io::Result<G> {
+    let mut repr = G::Uncompressed::empty();
+    reader.read_exact(repr.as_mut())?;
+
+    repr.into_affine().map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+fn write_points<W: Write, G: CurveAffine>(mut writer: W, points: &[G]) -> io::Result<()> {
+    writer.write_u32::<BigEndian>(points.len() as u32)?;
+    for p in points {
+        write_point(&mut writer, p)?;
+    }
+
+    Ok(())
+}
+
+fn read_points<R: Read, G: CurveAffine>(mut reader: R) -> io::Result<Vec<G>> {
+    let len = reader.read_u32::<BigEndian>()? as usize;
+    let mut points = vec![];
+    for _ in 0..len {
+        points.push(read_point(&mut reader)?);
+    }
+
+    Ok(points)
+}
+
+/// Powers of the secrets `a` and `b` the commitments of an aggregate proof
+/// are computed with.
+#[derive(Clone)]
+pub struct SRS<E: Engine> {
+    /// `g^(a^i)` for `i` below `2n`
+    g_alpha_powers: Vec<E::G1Affine>,
+    /// `g^(b^i)` for `i` below `2n`
+    g_beta_powers: Vec<E::G1Affine>,
+    /// `h^(a^i)` for `i` below `n`
+    h_alpha_powers: Vec<E::G2Affine>,
+    /// `h^(b^i)` for `i` below `n`
+    h_beta_powers: Vec<E::G2Affine>
+}
+
+/// The part of the `SRS` verifiers need.
+#[derive(Clone)]
+pub struct VerifierSRS<E: Engine> {
+    g: E::G1Affine,
+    h: E::G2Affine,
+    g_alpha: E::G1Affine,
+    g_beta: E::G1Affine,
+    h_alpha: E::G2Affine,
+    h_beta: E::G2Affine
+}
+
+impl<E: Engine> PartialEq for SRS<E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.g_alpha_powers == other.g_alpha_powers &&
+        self.g_beta_powers == other.g_beta_powers &&
+        self.h_alpha_powers == other.h_alpha_powers &&
+        self.h_beta_powers == other.h_beta_powers
+    }
+}
+
+impl<E: Engine> PartialEq for VerifierSRS<E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.g == other.g &&
+        self.h == other.h &&
+        self.g_alpha == other.g_alpha &&
+        self.g_beta == other.g_beta &&
+        self.h_alpha == other.h_alpha &&
+        self.h_beta == other.h_beta
+    }
+}
+
+fn powers<G: CurveAffine>(base: G, secret: G::Scalar, count: usize) -> Vec<G> {
+    let mut power = G::Scalar::one();
+    let mut points = Vec::with_capacity(count);
+    for _ in 0..count {
+        points.push(base.mul(power));
+        power.mul_assign(&secret);
+    }
+    G::Projective::batch_normalization(&mut points);
+
+    points.into_iter().map(|p| p.into_affine()).collect()
+}
+
+impl<E: Engine> SRS<E> {
+    /// Samples `a` and `b` and computes their powers for up to `n` proofs.
+    /// Whoever knows `a` or `b` can forge aggregate proofs, so this is only
+    /// meant for tests.
+    pub fn setup<R: Rng>(rng: &mut R, n: usize) -> SRS<E> {
+        let n = n.next_power_of_two();
+        let a: E::Fr = rng.gen();
+        let b: E::Fr = rng.gen();
+        let g = E::G1Affine::one();
+        let h = E::G2Affine::one();
+
+        SRS {
+            g_alpha_powers: powers(g, a, 2 * n),
+            g_beta_powers: powers(g, b, 2 * n),
+            h_alpha_powers: powers(h, a, n),
+            h_beta_powers: powers(h, b, n)
+        }
+    }
+
+    /// The SRS from the powers of `a` and `b` in G1 and G2, such as the
+    /// `tau` powers of two independent powers-of-tau ceremonies. It serves
+    /// the largest power of two `n` for which there are `2n` powers in G1
+    /// and `n` in G2; further powers are dropped.
+    pub fn from_powers(
+        g_alpha_powers: Vec<E::G1Affine>,
+        h_alpha_powers: Vec<E::G2Affine>,
+        g_beta_powers: Vec<E::G1Affine>,
+        h_beta_powers: Vec<E::G2Affine>
+    ) -> io::Result<SRS<E>>
+    {
+        let available = *[
+            g_alpha_powers.len() / 2,
+            g_beta_powers.len() / 2,
+            h_alpha_powers.len(),
+            h_beta_powers.len()
+        ].iter().min().unwrap();
+        if available == 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "not enough powers"));
+        }
+        let n = if available.is_power_of_two() { available } else { available.next_power_of_two() / 2 };
+
+        if g_alpha_powers[0] != g_beta_powers[0] || h_alpha_powers[0] != h_beta_powers[0] {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "the powers start at different generators"));
+        }
+        if g_alpha_powers[0].is_zero() || h_alpha_powers[0].is_zero() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "the generator is the point at infinity"));
+        }
+
+        let mut srs = SRS { g_alpha_powers, g_beta_powers, h_alpha_powers, h_beta_powers };
+        srs.g_alpha_powers.truncate(2 * n);
+        srs.g_beta_powers.truncate(2 * n);
+        srs.h_alpha_powers.truncate(n);
+        srs.h_beta_powers.truncate(n);
+
+        Ok(srs)
+    }
+
+    /// The largest number of proofs the SRS can aggregate.
+    pub fn max_proofs(&self) -> usize {
+        self.h_alpha_powers.len()
+    }
+
+    pub fn verifier_srs(&self) -> VerifierSRS<E> {
+        VerifierSRS {
+            g: self.g_alpha_powers[0],
+            h: self.h_alpha_powers[0],
+            g_alpha: self.g_alpha_powers[1],
+            g_beta: self.g_beta_powers[1],
+            h_alpha: self.h_alpha_powers[1],
+            h_beta: self.h_beta_powers[1]
+        }
+    }
+
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        write_points(&mut writer, &self.g_alpha_powers)?;
+        write_points(&mut writer, &self.h_alpha_powers)?;
+        write_points(&mut writer, &self.g_beta_powers)?;
+        write_points(&mut writer, &self.h_beta_powers)
+    }
+
+    pub fn read<R: Read>(mut reader: R) -> io::Result<SRS<E>> {
+        let g_alpha_powers = read_points(&mut reader)?;
+        let h_alpha_powers = read_points(&mut reader)?;
+        let g_beta_powers = read_points(&mut reader)?;
+        let h_beta_powers = read_points(&mut reader)?;
+
+        SRS::from_powers(g_alpha_powers, h_alpha_powers, g_beta_powers, h_beta_powers)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+impl<E: Engine> VerifierSRS<E> {
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        write_point(&mut writer, &self.g)?;
+        write_point(&mut writer, &self.h)?;
+        write_point(&mut writer, &self.g_alpha)?;
+        write_point(&mut writer, &self.g_beta)?;
+        write_point(&mut writer, &self.h_alpha)?;
+        write_point(&mut writer, &self.h_beta)
+    }
+
+    pub fn read<R: Read>(mut reader: R) -> io::Result<VerifierSRS<E>> {
+        Ok(VerifierSRS {
+            g: read_point(&mut reader)?,
+            h: read_point(&mut reader)?,
+            g_alpha: read_point(&mut reader)?,
+            g_beta: read_point(&mut reader)?,
+            h_alpha: read_point(&mut reader)?,
+            h_beta: read_point(&mut reader)?
+        })
+    }
+}
+
+/// Commitments sent for one half of the vectors in a GIPA round.
+#[derive(Clone)]
+struct Message<E: Engine> {
+    /// Commitment to the cross terms of `A` and `B`
+    com_ab: (E::Fqk, E::Fqk),
+    /// Inner pairing product of the cross terms of `A` and `B`
+    z_ab: E::Fqk,
+    /// Commitment to the cross terms of `C`
+    com_c: (E::Fqk, E::Fqk),
+    /// Inner product of the cross terms of `C` and the scalars
+    z_c: E::G1Affine
+}
+
+impl<E: AggregateEngine> Message<E> {
+    fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        E::write_gt(&self.com_ab.0, &mut writer)?;
+        E::write_gt(&self.com_ab.1, &mut writer)?;
+        E::write_gt(&self.z_ab, &mut writer)?;
+        E::write_gt(&self.com_c.0, &mut writer)?;
+        E::write_gt(&self.com_c.1, &mut writer)?;
+        write_point(&mut writer, &self.z_c)
+    }
+
+    fn read<R: Read>(mut reader: R) -> io::Result<Message<E>> {
+        Ok(Message {
+            com_ab: (read_gt::<E, _>(&mut reader)?, read_gt::<E, _>(&mut reader)?),
+            z_ab: read_gt::<E, _>(&mut reader)?,
+            com_c: (read_gt::<E, _>(&mut reader)?, read_gt::<E, _>(&mut reader)?),
+            z_c: read_point(&mut reader)?
+        })
+    }
+}
+
+/// `n` Groth16 proofs compressed into `O(log n)` group elements.
+#[derive(Clone)]
+pub struct AggregateProof<E: Engine> {
+    /// Commitment to the `A` and `B` points
+    com_ab: (E::Fqk, E::Fqk),
+    /// Commitment to the `C` points
+    com_c: (E::Fqk, E::Fqk),
+    /// `prod e(A_i, B_i)^(r^i)`
+    ip_ab: E::Fqk,
+    /// `sum r^i * C_i`
+    agg_c: E::G1Affine,
+    /// Left and right messages of every GIPA round
+    rounds: Vec<(Message<E>, Message<E>)>,
+    /// `A`, `B` and `C` folded to a single point
+    final_a: E::G1Affine,
+    final_b: E::G2Affine,
+    final_c: E::G1Affine,
+    /// Folded commitment keys, in `a` and in `b`
+    final_v: (E::G2Affine, E::G2Affine),
+    final_w: (E::G1Affine, E::G1Affine),
+    /// KZG openings of the folded keys
+    opening_v: (E::G2Affine, E::G2Affine),
+    opening_w: (E::G1Affine, E::G1Affine)
+}
+
+impl<E: AggregateEngine> AggregateProof<E> {
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        E::write_gt(&self.com_ab.0, &mut writer)?;
+        E::write_gt(&self.com_ab.1, &mut writer)?;
+        E::write_gt(&self.com_c.0, &mut writer)?;
+        E::write_gt(&self.com_c.1, &mut writer)?;
+        E::write_gt(&self.ip_ab, &mut writer)?;
+        write_point(&mut writer, &self.agg_c)?;
+        writer.write_u32::<BigEndian>(self.rounds.len() as u32)?;
+        for &(ref left, ref right) in &self.rounds {
+            left.write(&mut writer)?;
+            right.write(&mut writer)?;
+        }
+        write_point(&mut writer, &self.final_a)?;
+        write_point(&mut writer, &self.final_b)?;
+        write_point(&mut writer, &self.final_c)?;
+        write_point(&mut writer, &self.final_v.0)?;
+        write_point(&mut writer, &self.final_v.1)?;
+        write_point(&mut writer, &self.final_w.0)?;
+        write_point(&mut writer, &self.final_w.1)?;
+        write_point(&mut writer, &self.opening_v.0)?;
+        write_point(&mut writer, &self.opening_v.1)?;
+        write_point(&mut writer, &self.opening_w.0)?;
+        write_point(&mut writer, &self.opening_w.1)
+    }
+
+    /// Reads an aggregate proof, checking that every target group element
+    /// is one.
+    pub fn read<R: Read>(mut reader: R) -> io::Result<AggregateProof<E>> {
+        let com_ab = (read_gt::<E, _>(&mut reader)?, read_gt::<E, _>(&mut reader)?);
+        let com_c = (read_gt::<E, _>(&mut reader)?, read_gt::<E, _>(&mut reader)?);
+        let ip_ab = read_gt::<E, _>(&mut reader)?;
+        let agg_c = read_point(&mut reader)?;
+
+        // more rounds would mean more than 2^32 proofs
+        let num_rounds = reader.read_u32::<BigEndian>()?;
+        if num_rounds > 32 {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "too many rounds"));
+        }
+        let mut rounds = vec![];
+        for _ in 0..num_rounds {
+            rounds.push((Message::read(&mut reader)?, Message::read(&mut reader)?));
+        }
+
+        Ok(AggregateProof {
+            com_ab,
+            com_c,
+            ip_ab,
+            agg_c,
+            rounds,
+            final_a: read_point(&mut reader)?,
+            final_b: read_point(&mut reader)?,
+            final_c: read_point(&mut reader)?,
+            final_v: (read_point(&mut reader)?, read_point(&mut reader)?),
+            final_w: (read_point(&mut reader)?, read_point(&mut reader)?),
+            opening_v: (read_point(&mut reader)?, read_point(&mut reader)?),
+            opening_w: (read_point(&mut reader)?, read_point(&mut reader)?)
+        })
+    }
+}
+
+/// Fiat-Shamir transcript: challenges are derived from a BLAKE2b hash of
+/// everything written so far.
+struct Transcript {
+    hasher: Blake2b
+}
+
+impl Transcript {
+    fn new(number_of_proofs: usize) -> Transcript {
+        let mut transcript = Transcript { hasher: Blake2b::new(64) };
+        transcript.hasher.update(b"snarkpack");
+        transcript.write_u64::<BigEndian>(number_of_proofs as u64).expect("hashing does not fail");
+
+        transcript
+    }
+
+    fn write_gt<E: AggregateEngine>(&mut self, gt: &E::Fqk) {
+        E::write_gt(gt, self).expect("hashing does not fail");
+    }
+
+    fn write_point<G: CurveAffine>(&mut self, point: &G) {
+        write_point(self, point).expect("hashing does not fail");
+    }
+
+    /// A non-zero scalar derived from the transcript, which is itself
+    /// written to the transcript.
+    fn challenge<F: PrimeField>(&mut self) -> F {
+        let hash = mem::replace(&mut self.hasher, Blake2b::new(64)).finalize();
+        self.hasher.update(hash.as_bytes());
+
+        let seed: Vec<u32> = hash.as_bytes()[..32].chunks(4).map(BigEndian::read_u32).collect();
+        let mut rng = ChaChaRng::from_seed(&seed);
+        loop {
+            let challenge: F = rng.gen();
+            if !challenge.is_zero() {
+                return challenge;
+            }
+        }
+    }
+}
+
+impl Write for Transcript {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.hasher.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Writes the public inputs and derives `r`.
+fn derive_r<E: AggregateEngine>(
+    transcript: &mut Transcript,
+    public_inputs: &[&[E::Fr]],
+    com_ab: &(E::Fqk, E::Fqk),
+    com_c: &(E::Fqk, E::Fqk)
+) -> E::Fr
+{
+    for inputs in public_inputs {
+        for input in inputs.iter() {
+            input.into_repr().write_be(&mut *transcript).expect("hashing does not fail");
+        }
+    }
+    transcript.write_gt::<E>(&com_ab.0);
+    transcript.write_gt::<E>(&com_ab.1);
+    transcript.write_gt::<E>(&com_c.0);
+    transcript.write_gt::<E>(&com_c.1);
+
+    transcript.challenge()
+}
+
+/// Writes the messages of a round and derives its challenge.
+fn derive_x<E: AggregateEngine>(transcript: &mut Transcript, left: &Message<E>, right: &Message<E>) -> E::Fr {
+    left.write(&mut *transcript).expect("hashing does not fail");
+    right.write(&mut *transcript).expect("hashing does not fail");
+
+    transcript.challenge()
+}
+
+/// `prod e(g1_i, g2_i)`
+fn pairing_product<E: Engine>(g1: &[E::G1Affine], g2: &[E::G2Affine]) -> E::Fqk {
+    let g1: Vec<_> = g1.iter().map(|p| p.prepare()).collect();
+    let g2: Vec<_> = g2.iter().map(|p| p.prepare()).collect();
+    let pairs: Vec<_> = g1.iter().zip(g2.iter()).collect();
+
+    E::final_exponentiation(&E::miller_loop(pairs.iter())).unwrap()
+}
+
+/// Commitment to `a` and `b` under the keys `v` (paired with `a`) and `w`
+/// (paired with `b`), one output per secret.
+fn commit_pair<E: Engine>(
+    v: (&[E::G2Affine], &[E::G2Affine]),
+    w: (&[E::G1Affine], &[E::G1Affine]),
+    a: &[E::G1Affine],
+    b: &[E::G2Affine]
+) -> (E::Fqk, E::Fqk)
+{
+    let commit = |v: &[E::G2Affine], w: &[E::G1Affine]| {
+        let g1: Vec<_> = a.iter().chain(w.iter()).cloned().collect();
+        let g2: Vec<_> = v.iter().chain(b.iter()).cloned().collect();
+        pairing_product::<E>(&g1, &g2)
+    };
+
+    (commit(v.0, w.0), commit(v.1, w.1))
+}
+
+/// Commitment to `c` under the key `v`.
+fn commit_single<E: Engine>(v: (&[E::G2Affine], &[E::G2Affine]), c: &[E::G1Affine]) -> (E::Fqk, E::Fqk) {
+    (pairing_product::<E>(c, v.0), pairing_product::<E>(c, v.1))
+}
+
+/// `sum s_i * c_i`
+fn multiexp<G: CurveAffine>(bases: &[G], scalars: &[G::Scalar]) -> G::Projective {
+    let mut acc = G::Projective::zero();
+    for (base, scalar) in bases.iter().zip(scalars.iter()) {
+        acc.add_assign(&base.mul(*scalar));
+    }
+
+    acc
+}
+
+/// `v_L + x * v_R` for the halves of `v`.
+fn fold_points<G: CurveAffine>(v: &[G], x: G::Scalar) -> Vec<G> {
+    let (left, right) = v.split_at(v.len() / 2);
+    let mut folded: Vec<G::Projective> = left.iter().zip(right.iter()).map(|(l, r)| {
+        let mut p = r.mul(x);
+        p.add_assign_mixed(l);
+        p
+    }).collect();
+    G::Projective::batch_normalization(&mut folded);
+
+    folded.into_iter().map(|p| p.into_affine()).collect()
+}
+
+fn fold_scalars<F: Field>(v: &[F], x: F) -> Vec<F> {
+    let (left, right) = v.split_at(v.len() / 2);
+    left.iter().zip(right.iter()).map(|(l, r)| {
+        let mut s = *r;
+        s.mul_assign(&x);
+        s.add_assign(l);
+        s
+    }).collect()
+}
+
+/// `points[i] * scalars[i]`
+fn scale_points<G: CurveAffine>(points: &[G], scalars: &[G::Scalar]) -> Vec<G> {
+    let mut scaled: Vec<G::Projective> = points.iter().zip(scalars.iter()).map(|(p, s)| p.mul(*s)).collect();
+    G::Projective::batch_normalization(&mut scaled);
+
+    scaled.into_iter().map(|p| p.into_affine()).collect()
+}
+
+/// `1, x, x^2, ...`
+fn scalar_powers<F: Field>(x: F, count: usize) -> Vec<F> {
+    let mut powers = Vec::with_capacity(count);
+    let mut power = F::one();
+    for _ in 0..count {
+        powers.push(power);
+        power.mul_assign(&x);
+    }
+
+    powers
+}
+
+/// Coefficients of `prod_t (1 + c_t * X^(2^(k - 1 - t)))` for the
+/// challenges `c` of the `k` rounds: the factor a commitment key point is
+/// multiplied by when it gets folded.
+fn challenge_poly<F: Field>(challenges: &[F]) -> Vec<F> {
+    let mut coeffs = vec![F::one()];
+    for c in challenges.iter().rev() {
+        for i in 0..coeffs.len() {
+            let mut coeff = coeffs[i];
+            coeff.mul_assign(c);
+            coeffs.push(coeff);
+        }
+    }
+
+    coeffs
+}
+
+/// `challenge_poly(challenges)` evaluated at `z`.
+fn eval_challenge_poly<F: Field>(challenges: &[F], z: F) -> F {
+    let mut result = F::one();
+    let mut power = z;
+    for c in challenges.iter().rev() {
+        let mut term = power;
+        term.mul_assign(c);
+        term.add_assign(&F::one());
+        result.mul_assign(&term);
+        power.square();
+    }
+
+    result
+}
+
+/// Coefficients of `(f(X) - f(z)) / (X - z)`.
+fn kzg_quotient<F: Field>(coeffs: &[F], z: F) -> Vec<F> {
+    let mut quotient = vec![F::zero(); coeffs.len() - 1];
+    let mut acc = F::zero();
+    for i in (1..coeffs.len()).rev() {
+        acc.mul_assign(&z);
+        acc.add_assign(&coeffs[i]);
+        quotient[i - 1] = acc;
+    }
+
+    quotient
+}
+
+fn invalid_input(message: &str) -> SynthesisError {
+    SynthesisError::IoError(io::Error::new(io::ErrorKind::InvalidInput, message))
+}
+
+/// `v` repeated up to `n` entries with its last one.
+fn pad<T: Clone>(v: &[T], n: usize) -> Vec<T> {
+    let mut padded = v.to_vec();
+    let last = v[v.len() - 1].clone();
+    padded.resize(n, last);
+
+    padded
+}
+
+/// Aggregates proofs, each with its public inputs, that were made with the
+/// same parameters.
+pub fn aggregate_proofs<E: AggregateEngine>(
+    srs: &SRS<E>,
+    proofs: &[(&Proof<E>, &[E::Fr])]
+) -> Result<AggregateProof<E>, SynthesisError>
+{
+    if proofs.is_empty() {
+        return Err(invalid_input("there are no proofs to aggregate"));
+    }
+    let n = proofs.len().next_power_of_two();
+    if n > srs.max_proofs() {
+        return Err(SynthesisError::PolynomialDegreeTooLarge);
+    }
+
+    let proofs = pad(proofs, n);
+    let a: Vec<_> = proofs.iter().map(|&(p, _)| p.a).collect();
+    let b: Vec<_> = proofs.iter().map(|&(p, _)| p.b).collect();
+    let c: Vec<_> = proofs.iter().map(|&(p, _)| p.c).collect();
+    let public_inputs: Vec<_> = proofs.iter().map(|&(_, inputs)| inputs).collect();
+
+    // v is paired with A and C, w with B
+    let v = (&srs.h_alpha_powers[..n], &srs.h_beta_powers[..n]);
+    let w = (&srs.g_alpha_powers[n..2 * n], &srs.g_beta_powers[n..2 * n]);
+    let com_ab = commit_pair::<E>(v, w, &a, &b);
+    let com_c = commit_single::<E>(v, &c);
+
+    let mut transcript = Transcript::new(n);
+    let r = derive_r::<E>(&mut transcript, &public_inputs, &com_ab, &com_c);
+    let r_powers = scalar_powers(r, n);
+    let r_inv_powers = scalar_powers(r.inverse().unwrap(), n);
+
+    // A_i * r^i and C_i * r^i keep their commitments under v_i * r^-i
+    let mut a = scale_points(&a, &r_powers);
+    let mut b = b;
+    let mut c = scale_points(&c, &r_powers);
+    let mut v = (scale_points(v.0, &r_inv_powers), scale_points(v.1, &r_inv_powers));
+    let mut w = (w.0.to_vec(), w.1.to_vec());
+    let mut s = vec![E::Fr::one(); n];
+
+    let ip_ab = pairing_product::<E>(&a, &b);
+    let agg_c = multiexp(&c, &s).into_affine();
+    transcript.write_gt::<E>(&ip_ab);
+    transcript.write_point(&agg_c);
+
+    let mut rounds = vec![];
+    let mut challenges = vec![];
+    while a.len() > 1 {
+        let half = a.len() / 2;
+        let (a_left, a_right) = a.split_at(half);
+        let (b_left, b_right) = b.split_at(half);
+        let (c_left, c_right) = c.split_at(half);
+        let (s_left, s_right) = s.split_at(half);
+        let (v0_left, v0_right) = v.0.split_at(half);
+        let (v1_left, v1_right) = v.1.split_at(half);
+        let (w0_left, w0_right) = w.0.split_at(half);
+        let (w1_left, w1_right) = w.1.split_at(half);
+
+        let left = Message {
+            com_ab: commit_pair::<E>((v0_left, v1_left), (w0_right, w1_right), a_right, b_left),
+            z_ab: pairing_product::<E>(a_right, b_left),
+            com_c: commit_single::<E>((v0_left, v1_left), c_right),
+            z_c: multiexp(c_right, s_left).into_affine()
+        };
+        let right = Message {
+            com_ab: commit_pair::<E>((v0_right, v1_right), (w0_left, w1_left), a_left, b_right),
+            z_ab: pairing_product::<E>(a_left, b_right),
+            com_c: commit_single::<E>((v0_right, v1_right), c_left),
+            z_c: multiexp(c_left, s_right).into_affine()
+        };
+
+        let x = derive_x::<E>(&mut transcript, &left, &right);
+        let x_inv = x.inverse().unwrap();
+        rounds.push((left, right));
+        challenges.push(x);
+
+        a = fold_points(&a, x);
+        b = fold_points(&b, x_inv);
+        c = fold_points(&c, x);
+        s = fold_scalars(&s, x_inv);
+        v = (fold_points(&v.0, x_inv), fold_points(&v.1, x_inv));
+        w = (fold_points(&w.0, x), fold_points(&w.1, x));
+    }
+
+    let mut aggregate = AggregateProof {
+        com_ab,
+        com_c,
+        ip_ab,
+        agg_c,
+        rounds,
+        final_a: a[0],
+        final_b: b[0],
+        final_c: c[0],
+        final_v: (v.0[0], v.1[0]),
+        final_w: (w.0[0], w.1[0]),
+        opening_v: (E::G2Affine::zero(), E::G2Affine::zero()),
+        opening_w: (E::G1Affine::zero(), E::G1Affine::zero())
+    };
+    let z: E::Fr = derive_z(&mut transcript, &aggregate);
+
+    // the folded v is v_0 * f_v(a / r), the folded w is w_0 * a^n f_w(a)
+    let challenges_inv: Vec<_> = challenges.iter().map(|x| x.inverse().unwrap()).collect();
+    let mut f_v = challenge_poly(&challenges_inv);
+    for (coeff, r_inv) in f_v.iter_mut().zip(r_inv_powers.iter()) {
+        coeff.mul_assign(r_inv);
+    }
+    let mut f_w = vec![E::Fr::zero(); n];
+    f_w.extend(challenge_poly(&challenges));
+
+    let q_v = kzg_quotient(&f_v, z);
+    let q_w = kzg_quotient(&f_w, z);
+    aggregate.opening_v = (
+        multiexp(&srs.h_alpha_powers, &q_v).into_affine(),
+        multiexp(&srs.h_beta_powers, &q_v).into_affine()
+    );
+    aggregate.opening_w = (
+        multiexp(&srs.g_alpha_powers, &q_w).into_affine(),
+        multiexp(&srs.g_beta_powers, &q_w).into_affine()
+    );
+
+    Ok(aggregate)
+}
+
+/// Writes the folded values and derives the KZG evaluation point.
+fn derive_z<E: AggregateEngine>(transcript: &mut Transcript, aggregate: &AggregateProof<E>) -> E::Fr {
+    transcript.write_point(&aggregate.final_a);
+    transcript.write_point(&aggregate.final_b);
+    transcript.write_point(&aggregate.final_c);
+    transcript.write_point(&aggregate.final_v.0);
+    transcript.write_point(&aggregate.final_v.1);
+    transcript.write_point(&aggregate.final_w.0);
+    transcript.write_point(&aggregate.final_w.1);
+
+    transcript.challenge()
+}
+
+/// Whether `commitment` opens to `value` at `z`, given `g^secret`:
+/// `e(g^secret - g^z, opening) = e(g, commitment - h^value)`.
+fn kzg_check_g2<E: Engine>(
+    srs: &VerifierSRS<E>,
+    g_secret: E::G1Affine,
+    commitment: E::G2Affine,
+    opening: E::G2Affine,
+    z: E::Fr,
+    value: E::Fr
+) -> bool
+{
+    let mut lhs = g_secret.into_projective();
+    lhs.sub_assign(&srs.g.mul(z));
+    let mut rhs = commitment.into_projective();
+    rhs.sub_assign(&srs.h.mul(value));
+
+    E::pairing(lhs, opening) == E::pairing(srs.g, rhs)
+}
+
+/// Whether `commitment` opens to `value` at `z`, given `h^secret`:
+/// `e(commitment - g^value, h) = e(opening, h^secret - h^z)`.
+fn kzg_check_g1<E: Engine>(
+    srs: &VerifierSRS<E>,
+    h_secret: E::G2Affine,
+    commitment: E::G1Affine,
+    opening: E::G1Affine,
+    z: E::Fr,
+    value: E::Fr
+) -> bool
+{
+    let mut lhs = commitment.into_projective();
+    lhs.sub_assign(&srs.g.mul(value));
+    let mut rhs = h_secret.into_projective();
+    rhs.sub_assign(&srs.h.mul(z));
+
+    E::pairing(lhs, srs.h) == E::pairing(opening, rhs)
+}
+
+/// `gt * left^x * right^(x^-1)`
+fn fold_gt<E: Engine>(gt: &mut E::Fqk, left: &E::Fqk, right: &E::Fqk, x: &E::Fr, x_inv: &E::Fr) {
+    gt.mul_assign(&left.pow(x.into_repr()));
+    gt.mul_assign(&right.pow(x_inv.into_repr()));
+}
+
+/// Checks an aggregate of proofs for `public_inputs`, in the order they
+/// were aggregated.
+pub fn verify_aggregate_proof<E: AggregateEngine>(
+    srs: &VerifierSRS<E>,
+    pvk: &PreparedVerifyingKey<E>,
+    public_inputs: &[&[E::Fr]],
+    aggregate: &AggregateProof<E>
+) -> Result<bool, SynthesisError>
+{
+    if public_inputs.is_empty() {
+        return Err(invalid_input("there are no proofs to verify"));
+    }
+    for inputs in public_inputs {
+        if inputs.len() + 1 != pvk.ic.len() {
+            return Err(SynthesisError::MalformedVerifyingKey);
+        }
+    }
+    let n = public_inputs.len().next_power_of_two();
+    if aggregate.rounds.len() != n.trailing_zeros() as usize {
+        return Ok(false);
+    }
+    let public_inputs = pad(public_inputs, n);
+
+    let mut transcript = Transcript::new(n);
+    let r: E::Fr = derive_r::<E>(&mut transcript, &public_inputs, &aggregate.com_ab, &aggregate.com_c);
+    transcript.write_gt::<E>(&aggregate.ip_ab);
+    transcript.write_point(&aggregate.agg_c);
+
+    let mut com_ab = aggregate.com_ab;
+    let mut z_ab = aggregate.ip_ab;
+    let mut com_c = aggregate.com_c;
+    let mut z_c = aggregate.agg_c.into_projective();
+    let mut challenges = vec![];
+    for &(ref left, ref right) in &aggregate.rounds {
+        let x = derive_x(&mut transcript, left, right);
+        let x_inv = x.inverse().unwrap();
+        challenges.push(x);
+
+        fold_gt::<E>(&mut com_ab.0, &left.com_ab.0, &right.com_ab.0, &x, &x_inv);
+        fold_gt::<E>(&mut com_ab.1, &left.com_ab.1, &right.com_ab.1, &x, &x_inv);
+        fold_gt::<E>(&mut z_ab, &left.z_ab, &right.z_ab, &x, &x_inv);
+        fold_gt::<E>(&mut com_c.0, &left.com_c.0, &right.com_c.0, &x, &x_inv);
+        fold_gt::<E>(&mut com_c.1, &left.com_c.1, &right.com_c.1, &x, &x_inv);
+        z_c.add_assign(&left.z_c.mul(x));
+        z_c.add_assign(&right.z_c.mul(x_inv));
+    }
+    let z: E::Fr = derive_z(&mut transcript, aggregate);
+    let challenges_inv: Vec<_> = challenges.iter().map(|x| x.inverse().unwrap()).collect();
+
+    // the folded vectors satisfy the folded relations
+    let a = aggregate.final_a;
+    let b = aggregate.final_b;
+    let c = aggregate.final_c;
+    let (v0, v1) = aggregate.final_v;
+    let (w0, w1) = aggregate.final_w;
+    if com_ab != commit_pair::<E>((&[v0], &[v1]), (&[w0], &[w1]), &[a], &[b]) ||
+        z_ab != E::pairing(a, b) ||
+        com_c != commit_single::<E>((&[v0], &[v1]), &[c]) ||
+        z_c != c.mul(eval_challenge_poly(&challenges_inv, E::Fr::one()))
+    {
+        return Ok(false);
+    }
+
+    // the folded keys were folded from the SRS
+    let mut z_over_r = z;
+    z_over_r.mul_assign(&r.inverse().unwrap());
+    let f_v = eval_challenge_poly(&challenges_inv, z_over_r);
+    let mut f_w = z.pow([n as u64]);
+    f_w.mul_assign(&eval_challenge_poly(&challenges, z));
+    if !kzg_check_g2(srs, srs.g_alpha, v0, aggregate.opening_v.0, z, f_v) ||
+        !kzg_check_g2(srs, srs.g_beta, v1, aggregate.opening_v.1, z, f_v) ||
+        !kzg_check_g1(srs, srs.h_alpha, w0, aggregate.opening_w.0, z, f_w) ||
+        !kzg_check_g1(srs, srs.h_beta, w1, aggregate.opening_w.1, z, f_w)
+    {
+        return Ok(false);
+    }
+
+    // the random linear combination of the Groth16 equations:
+    // ip_ab * e(inputs, -gamma) * e(agg_c, -delta) = e(alpha, beta)^(sum r^i)
+    let r_powers = scalar_powers(r, n);
+    let mut r_sum = E::Fr::zero();
+    for power in &r_powers {
+        r_sum.add_assign(power);
+    }
+    let mut input_scalars = vec![E::Fr::zero(); pvk.ic.len() - 1];
+    for (inputs, power) in public_inputs.iter().zip(r_powers.iter()) {
+        for (scalar, input) in input_scalars.iter_mut().zip(inputs.iter()) {
+            let mut term = *input;
+            term.mul_assign(power);
+            scalar.add_assign(&term);
+        }
+    }
+    let mut acc = pvk.ic[0].mul(r_sum);
+    acc.add_assign(&multiexp(&pvk.ic[1..], &input_scalars));
+
+    let mut lhs = E::final_exponentiation(&E::miller_loop([
+        (&acc.into_affine().prepare(), &pvk.neg_gamma_g2),
+        (&aggregate.agg_c.prepare(), &pvk.neg_delta_g2)
+    ].into_iter())).unwrap();
+    lhs.mul_assign(&aggregate.ip_ab);
+
+    Ok(lhs == pvk.alpha_g1_beta_g2.pow(r_sum.into_repr()))
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod tests {
+    use super::*;
+    use super::super::{create_random_proof, generate_random_parameters, prepare_verifying_key};
+    use {Circuit, ConstraintSystem};
+
+    use pairing::bn256::{Bn256, Fr};
+    use rand::{XorShiftRng, SeedableRng};
+
+    struct Square {
+        a: Option<Fr>
+    }
+
+    impl Circuit<Bn256> for Square {
+        fn synthesize<CS: ConstraintSystem<Bn256>>(
+            self,
+            cs: &mut CS
+        ) -> Result<(), SynthesisError>
+        {
+            let a = cs.alloc(|| "a", || self.a.ok_or(SynthesisError::AssignmentMissing))?;
+            let c = cs.alloc_input(|| "c", || {
+                let mut a = self.a.ok_or(SynthesisError::AssignmentMissing)?;
+                a.square();
+                Ok(a)
+            })?;
+
+            cs.enforce(
+                || "a*a=c",
+                |lc| lc + a,
+                |lc| lc + a,
+                |lc| lc + c
+            );
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn aggregation() {
+        let rng = &mut XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+        let params = generate_random_parameters::<Bn256, _, _>(Square { a: None }, rng).unwrap();
+        let pvk = prepare_verifying_key(&params.vk);
+        let srs = SRS::<Bn256>::setup(rng, 8);
+        let vsrs = srs.verifier_srs();
+
+        let mut proofs = vec![];
+        let mut inputs = vec![];
+        for _ in 0..7 {
+            let a: Fr = rng.gen();
+            let mut c = a;
+            c.square();
+            proofs.push(create_random_proof(Square { a: Some(a) }, &params, rng).unwrap());
+            inputs.push(vec![c]);
+        }
+        let batch: Vec<_> = proofs.iter().zip(inputs.iter()).map(|(p, i)| (p, &i[..])).collect();
+        let inputs: Vec<_> = inputs.iter().map(|i| &i[..]).collect();
+
+        // seven proofs are padded to eight
+        let aggregate = aggregate_proofs(&srs, &batch).unwrap();
+        assert_eq!(aggregate.rounds.len(), 3);
+        assert!(verify_aggregate_proof(&vsrs, &pvk, &inputs, &aggregate).unwrap());
+
+        let mut v = vec![];
+        aggregate.write(&mut v).unwrap();
+        let aggregate = AggregateProof::<Bn256>::read(&v[..]).unwrap();
+        assert!(verify_aggregate_proof(&vsrs, &pvk, &inputs, &aggregate).unwrap());
+
+        let mut v = vec![];
+        vsrs.write(&mut v).unwrap();
+        assert!(VerifierSRS::<Bn256>::read(&v[..]).unwrap() == vsrs);
+        let mut v = vec![];
+        srs.write(&mut v).unwrap();
+        assert!(SRS::<Bn256>::read(&v[..]).unwrap() == srs);
+
+        // other inputs, fewer proofs or another order are rejected
+        let mut wrong = inputs.clone();
+        wrong.swap(0, 1);
+        assert!(!verify_aggregate_proof(&vsrs, &pvk, &wrong, &aggregate).unwrap());
+        assert!(!verify_aggregate_proof(&vsrs, &pvk, &inputs[..6], &aggregate).unwrap());
+        assert!(!verify_aggregate_proof(&vsrs, &pvk, &inputs[..3], &aggregate).unwrap());
+
+        // an invalid proof spoils the aggregate
+        let other = [rng.gen::<Fr>()];
+        let mut batch = batch;
+        batch[4].1 = &other;
+        let aggregate = aggregate_proofs(&srs, &batch).unwrap();
+        let inputs: Vec<_> = batch.iter().map(|&(_, i)| i).collect();
+        assert!(!verify_aggregate_proof(&vsrs, &pvk, &inputs, &aggregate).unwrap());
+
+        // a single proof, and too many for the SRS
+        let aggregate = aggregate_proofs(&srs, &batch[..1]).unwrap();
+        assert!(verify_aggregate_proof(&vsrs, &pvk, &inputs[..1], &aggregate).unwrap());
+        let many: Vec<_> = (0..9).map(|_| batch[0]).collect();
+        assert!(aggregate_proofs(&srs, &many).is_err());
+    }
+}
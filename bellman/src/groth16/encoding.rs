@@ -0,0 +1,297 @@
+//! Point encodings of verifying keys and parameters.
+//!
+//! `VerifyingKey::write` and `Parameters::write` store affine points
+//! uncompressed and without any header, which is the layout every existing
+//! file has. `write_encoded` puts a versioned header in front instead, which
+//! records whether the points that follow are uncompressed or compressed.
+//! Compressed points take half the space, but cost a square root each to
+//! decode; on load they are always checked to be in the prime order
+//! subgroup, as the curve arithmetic alone does not guarantee it.
+//!
+//! The `read` functions look for the header and fall back to the headerless
+//! uncompressed layout. The header starts with the point at infinity flag
+//! followed by non-zero bits, which is not a valid uncompressed point, so the
+//! two cannot be confused.
+
+use pairing::{
+    CurveAffine,
+    CurveProjective,
+    EncodedPoint
+};
+use ff::PrimeField;
+
+use byteorder::{BigEndian, ByteOrder};
+use std::cmp;
+use std::io::{self, Read, Write};
+use std::vec::Vec;
+
+#[cfg(feature = "prover")]
+use multicore::Worker;
+
+const MAGIC: &'static [u8; 7] = b"GROTH16";
+const VERSION: u8 = 1;
+const HEADER_SIZE: usize = 9;
+
+/// How the points of a verifying key or of parameters are written.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Encoding {
+    /// Both affine coordinates.
+    Uncompressed,
+    /// The x coordinate and the sign of y.
+    Compressed
+}
+
+/// Size in bytes of a point of `G` written with `encoding`.
+pub(crate) fn size<G: CurveAffine>(encoding: Encoding) -> usize {
+    match encoding {
+        Encoding::Uncompressed => G::Uncompressed::size(),
+        Encoding::Compressed => G::Compressed::size()
+    }
+}
+
+pub(crate) fn write_header<W: Write>(mut writer: W, encoding: Encoding) -> io::Result<()> {
+    writer.write_all(&MAGIC[..])?;
+    writer.write_all(&[VERSION, match encoding {
+        Encoding::Uncompressed => 0,
+        Encoding::Compressed => 1
+    }])
+}
+
+/// A reader that first replays the bytes taken to look for a header.
+pub(crate) struct Prefixed<R> {
+    prefix: [u8; HEADER_SIZE],
+    pos: usize,
+    inner: R
+}
+
+impl<R: Read> Read for Prefixed<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.pos == HEADER_SIZE {
+            return self.inner.read(buf);
+        }
+
+        let n = cmp::min(buf.len(), HEADER_SIZE - self.pos);
+        buf[..n].copy_from_slice(&self.prefix[self.pos..self.pos + n]);
+        self.pos += n;
+
+        Ok(n)
+    }
+}
+
+/// Reads the header written by `write_header`, if there is one, and returns
+/// the encoding of the points together with a reader positioned at them.
+pub(crate) fn read_header<R: Read>(mut reader: R) -> io::Result<(Encoding, Prefixed<R>)> {
+    let mut prefix = [0u8; HEADER_SIZE];
+    reader.read_exact(&mut prefix)?;
+
+    if prefix[..MAGIC.len()] != MAGIC[..] {
+        return Ok((Encoding::Uncompressed, Prefixed { prefix: prefix, pos: 0, inner: reader }));
+    }
+
+    if prefix[7] != VERSION {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "unsupported encoding version"));
+    }
+    let encoding = match prefix[8] {
+        0 => Encoding::Uncompressed,
+        1 => Encoding::Compressed,
+        _ => return Err(io::Error::new(io::ErrorKind::InvalidData, "unknown point encoding"))
+    };
+
+    Ok((encoding, Prefixed { prefix: prefix, pos: HEADER_SIZE, inner: reader }))
+}
+
+pub(crate) fn write_point<G: CurveAffine, W: Write>(mut writer: W, p: &G, encoding: Encoding) -> io::Result<()> {
+    match encoding {
+        Encoding::Uncompressed => writer.write_all(p.into_uncompressed().as_ref()),
+        Encoding::Compressed => writer.write_all(p.into_compressed().as_ref())
+    }
+}
+
+/// Decodes a point from exactly `size::<G>(encoding)` bytes. With `checked`
+/// the point is checked like `EncodedPoint::into_affine` does and, if it was
+/// compressed, to be in the prime order subgroup.
+pub(crate) fn decode<G: CurveAffine>(bytes: &[u8], encoding: Encoding, checked: bool) -> io::Result<G> {
+    let p = match encoding {
+        Encoding::Uncompressed => {
+            let mut repr = G::Uncompressed::empty();
+            repr.as_mut().copy_from_slice(bytes);
+            if checked { repr.into_affine() } else { repr.into_affine_unchecked() }
+        },
+        Encoding::Compressed => {
+            let mut repr = G::Compressed::empty();
+            repr.as_mut().copy_from_slice(bytes);
+            repr.into_affine()
+        }
+    }.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+    if checked && encoding == Encoding::Compressed && !p.mul(G::Scalar::char()).is_zero() {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "point is not in the prime order subgroup"));
+    }
+
+    Ok(p)
+}
+
+pub(crate) fn read_point<G: CurveAffine, R: Read>(mut reader: R, encoding: Encoding, checked: bool) -> io::Result<G> {
+    let mut bytes = [0u8; 192];
+    let bytes = &mut bytes[..size::<G>(encoding)];
+    reader.read_exact(bytes)?;
+
+    decode(bytes, encoding, checked)
+}
+
+fn non_zero<G: CurveAffine>(p: G) -> io::Result<G> {
+    if p.is_zero() {
+        Err(io::Error::new(io::ErrorKind::InvalidData, "point at infinity"))
+    } else {
+        Ok(p)
+    }
+}
+
+pub(crate) fn write_points<G: CurveAffine, W: Write>(mut writer: W, points: &[G], encoding: Encoding) -> io::Result<()> {
+    let mut len = [0u8; 4];
+    BigEndian::write_u32(&mut len, points.len() as u32);
+    writer.write_all(&len)?;
+    for p in points {
+        write_point(&mut writer, p, encoding)?;
+    }
+
+    Ok(())
+}
+
+fn read_len<R: Read>(mut reader: R) -> io::Result<usize> {
+    let mut len = [0u8; 4];
+    reader.read_exact(&mut len)?;
+
+    Ok(BigEndian::read_u32(&len) as usize)
+}
+
+/// Reads a vector written by `write_points`, none of whose points may be
+/// the point at infinity.
+pub(crate) fn read_points<G: CurveAffine, R: Read>(mut reader: R, encoding: Encoding, checked: bool) -> io::Result<Vec<G>> {
+    let len = read_len(&mut reader)?;
+
+    let mut points = vec![];
+    for _ in 0..len {
+        points.push(non_zero(read_point(&mut reader, encoding, checked)?)?);
+    }
+
+    Ok(points)
+}
+
+/// Like `read_points`, but decodes the points on all threads of `worker`
+/// once they have been read.
+#[cfg(feature = "prover")]
+pub(crate) fn read_points_parallel<G: CurveAffine, R: Read>(
+    mut reader: R,
+    encoding: Encoding,
+    checked: bool,
+    worker: &Worker
+) -> io::Result<Vec<G>>
+{
+    let len = read_len(&mut reader)?;
+    let size = size::<G>(encoding);
+
+    // Read in steps so a corrupt length cannot allocate more than the
+    // input holds.
+    let mut bytes = vec![];
+    (&mut reader).take((len * size) as u64).read_to_end(&mut bytes)?;
+    if bytes.len() != len * size {
+        return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "failed to fill whole buffer"));
+    }
+
+    let mut points: Vec<io::Result<G>> = (0..len).map(|_| Ok(G::zero())).collect();
+    worker.scope(len, |scope, chunk| {
+        for (points, bytes) in points.chunks_mut(chunk).zip(bytes.chunks(chunk * size)) {
+            scope.spawn(move || {
+                for (p, bytes) in points.iter_mut().zip(bytes.chunks(size)) {
+                    *p = decode(bytes, encoding, checked).and_then(non_zero);
+                }
+            });
+        }
+    });
+
+    points.into_iter().collect()
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod tests {
+    use super::*;
+    use super::super::{Parameters, VerifyingKey, generate_random_parameters};
+    use {Circuit, ConstraintSystem, SynthesisError};
+
+    use ff::Field;
+    use pairing::bn256::{Bn256, Fr, G2Affine};
+    use rand::{SeedableRng, XorShiftRng};
+
+    struct Square {
+        x: Option<Fr>
+    }
+
+    impl Circuit<Bn256> for Square {
+        fn synthesize<CS: ConstraintSystem<Bn256>>(
+            self,
+            cs: &mut CS
+        ) -> Result<(), SynthesisError>
+        {
+            let x_value = self.x;
+            let x2_value = x_value.map(|mut x| { x.square(); x });
+
+            let x = cs.alloc(|| "x", || x_value.ok_or(SynthesisError::AssignmentMissing))?;
+            let x2 = cs.alloc_input(|| "x2", || x2_value.ok_or(SynthesisError::AssignmentMissing))?;
+
+            cs.enforce(|| "x2", |lc| lc + x, |lc| lc + x, |lc| lc + x2);
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn encodings() {
+        let rng = &mut XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+        let params = generate_random_parameters::<Bn256, _, _>(Square { x: None }, rng).unwrap();
+
+        let mut legacy = vec![];
+        params.write(&mut legacy).unwrap();
+        let mut uncompressed = vec![];
+        params.write_encoded(&mut uncompressed, Encoding::Uncompressed).unwrap();
+        let mut compressed = vec![];
+        params.write_encoded(&mut compressed, Encoding::Compressed).unwrap();
+
+        assert_eq!(uncompressed.len(), legacy.len() + HEADER_SIZE);
+        assert!(compressed.len() * 10 < legacy.len() * 6);
+
+        for v in &[&legacy, &uncompressed, &compressed] {
+            assert!(Parameters::<Bn256>::read(&v[..], false).unwrap() == params);
+            assert!(Parameters::<Bn256>::read_parallel(&v[..], true).unwrap() == params);
+            assert!(VerifyingKey::<Bn256>::read(&v[..]).unwrap() == params.vk);
+            assert!(Parameters::<Bn256>::read(&v[..v.len() - 1], false).is_err());
+        }
+
+        let mut vk = vec![];
+        params.vk.write_encoded(&mut vk, Encoding::Compressed).unwrap();
+        assert!(VerifyingKey::<Bn256>::read(&vk[..]).unwrap() == params.vk);
+
+        // unknown versions and encodings are refused
+        let mut v = compressed.clone();
+        v[7] = VERSION + 1;
+        assert!(Parameters::<Bn256>::read(&v[..], false).is_err());
+        v[7] = VERSION;
+        v[8] = 2;
+        assert!(Parameters::<Bn256>::read(&v[..], false).is_err());
+    }
+
+    #[test]
+    fn compressed_points_outside_the_subgroup() {
+        // G2 of BN256 has a cofactor, so most x coordinates on the twist
+        // decompress to points outside the prime order subgroup.
+        let mut bytes = [0u8; 64];
+        loop {
+            bytes[63] += 1;
+            if decode::<G2Affine>(&bytes, Encoding::Compressed, false).is_ok() {
+                break;
+            }
+        }
+
+        assert!(decode::<G2Affine>(&bytes, Encoding::Compressed, true).is_err());
+    }
+}
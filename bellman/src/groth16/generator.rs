@@ -172,17 +172,10 @@ impl<E: Engine> ConstraintSystem<E> for KeypairAssembly<E> {
     }
 }
 
-/// Create parameters for a circuit, given some toxic waste.
-pub fn generate_parameters<E, C>(
-    circuit: C,
-    g1: E::G1,
-    g2: E::G2,
-    alpha: E::Fr,
-    beta: E::Fr,
-    gamma: E::Fr,
-    delta: E::Fr,
-    tau: E::Fr
-) -> Result<Parameters<E>, SynthesisError>
+/// Synthesizes `circuit` into a QAP.
+fn synthesize<E, C>(
+    circuit: C
+) -> Result<KeypairAssembly<E>, SynthesisError>
     where E: Engine, C: Circuit<E>
 {
     let mut assembly = KeypairAssembly {
@@ -213,6 +206,24 @@ pub fn generate_parameters<E, C>(
         );
     }
 
+    Ok(assembly)
+}
+
+/// Create parameters for a circuit, given some toxic waste.
+pub fn generate_parameters<E, C>(
+    circuit: C,
+    g1: E::G1,
+    g2: E::G2,
+    alpha: E::Fr,
+    beta: E::Fr,
+    gamma: E::Fr,
+    delta: E::Fr,
+    tau: E::Fr
+) -> Result<Parameters<E>, SynthesisError>
+    where E: Engine, C: Circuit<E>
+{
+    let assembly = synthesize(circuit)?;
+
     // Create bases for blind evaluation of polynomials at tau
     let powers_of_tau = vec![Scalar::<E>(E::Fr::zero()); assembly.num_constraints];
     let mut powers_of_tau = EvaluationDomain::from_coeffs(powers_of_tau)?;
@@ -483,3 +494,161 @@ pub fn generate_parameters<E, C>(
         b_g2: Arc::new(b_g2.into_iter().filter(|e| !e.is_zero()).map(|e| e.into_affine()).collect())
     })
 }
+
+/// The output of a powers-of-tau ceremony, evaluated in the Lagrange basis
+/// of an evaluation domain of `2^exp` points. Nobody knows `tau`, `alpha`
+/// or `beta`, and the generator is the one of each group.
+pub struct Phase1<E: Engine> {
+    /// `L_i(tau)` for every Lagrange polynomial `L_i` of the domain
+    pub coeffs_g1: Vec<E::G1Affine>,
+    pub coeffs_g2: Vec<E::G2Affine>,
+    /// `alpha * L_i(tau)` and `beta * L_i(tau)`
+    pub alpha_coeffs_g1: Vec<E::G1Affine>,
+    pub beta_coeffs_g1: Vec<E::G1Affine>,
+    /// `tau^i * t(tau)` for `i` between 0 and `2^exp - 2` inclusive
+    pub h: Vec<E::G1Affine>,
+    pub alpha_g1: E::G1Affine,
+    pub beta_g1: E::G1Affine,
+    pub beta_g2: E::G2Affine,
+}
+
+/// Base 2 logarithm of the size of the evaluation domain of `circuit`,
+/// which is the size of the `Phase1` it needs.
+pub fn domain_exp<E, C>(
+    circuit: C
+) -> Result<u32, SynthesisError>
+    where E: Engine, C: Circuit<E>
+{
+    let assembly = synthesize::<E, C>(circuit)?;
+    let domain = EvaluationDomain::<E, Scalar<E>>::from_coeffs(vec![Scalar(E::Fr::zero()); assembly.num_constraints])?;
+
+    Ok(domain.as_ref().len().trailing_zeros())
+}
+
+/// Create parameters for a circuit from the output of a powers-of-tau
+/// ceremony, with `gamma` and `delta` one. `delta` is then left to a
+/// phase 2 ceremony, which must divide `h` and `l` by it.
+///
+/// Fails with `PolynomialDegreeTooLarge` unless `phase1` is over the
+/// domain of `circuit`, see `domain_exp`.
+pub fn generate_parameters_from_phase1<E, C>(
+    circuit: C,
+    phase1: &Phase1<E>
+) -> Result<Parameters<E>, SynthesisError>
+    where E: Engine, C: Circuit<E>
+{
+    let assembly = synthesize(circuit)?;
+    let domain = EvaluationDomain::<E, Scalar<E>>::from_coeffs(vec![Scalar(E::Fr::zero()); assembly.num_constraints])?;
+    let m = domain.as_ref().len();
+    if phase1.coeffs_g1.len() != m ||
+        phase1.coeffs_g2.len() != m ||
+        phase1.alpha_coeffs_g1.len() != m ||
+        phase1.beta_coeffs_g1.len() != m ||
+        phase1.h.len() != m - 1
+    {
+        return Err(SynthesisError::PolynomialDegreeTooLarge);
+    }
+
+    fn eval<E: Engine>(
+        phase1: &Phase1<E>,
+
+        // QAP polynomials
+        at: &[Vec<(E::Fr, usize)>],
+        bt: &[Vec<(E::Fr, usize)>],
+        ct: &[Vec<(E::Fr, usize)>],
+
+        // Resulting evaluated QAP polynomials
+        a: &mut [E::G1],
+        b_g1: &mut [E::G1],
+        b_g2: &mut [E::G2],
+        ext: &mut [E::G1]
+    )
+    {
+        for ((((((a, b_g1), b_g2), ext), at), bt), ct) in a.iter_mut()
+                                                           .zip(b_g1.iter_mut())
+                                                           .zip(b_g2.iter_mut())
+                                                           .zip(ext.iter_mut())
+                                                           .zip(at.iter())
+                                                           .zip(bt.iter())
+                                                           .zip(ct.iter())
+        {
+            // beta * A(tau) + alpha * B(tau) + C(tau)
+            for &(coeff, index) in at {
+                a.add_assign(&phase1.coeffs_g1[index].mul(coeff));
+                ext.add_assign(&phase1.beta_coeffs_g1[index].mul(coeff));
+            }
+            for &(coeff, index) in bt {
+                b_g1.add_assign(&phase1.coeffs_g1[index].mul(coeff));
+                b_g2.add_assign(&phase1.coeffs_g2[index].mul(coeff));
+                ext.add_assign(&phase1.alpha_coeffs_g1[index].mul(coeff));
+            }
+            for &(coeff, index) in ct {
+                ext.add_assign(&phase1.coeffs_g1[index].mul(coeff));
+            }
+        }
+
+        E::G1::batch_normalization(a);
+        E::G1::batch_normalization(b_g1);
+        E::G2::batch_normalization(b_g2);
+        E::G1::batch_normalization(ext);
+    }
+
+    let mut a = vec![E::G1::zero(); assembly.num_inputs + assembly.num_aux];
+    let mut b_g1 = vec![E::G1::zero(); assembly.num_inputs + assembly.num_aux];
+    let mut b_g2 = vec![E::G2::zero(); assembly.num_inputs + assembly.num_aux];
+    let mut ic = vec![E::G1::zero(); assembly.num_inputs];
+    let mut l = vec![E::G1::zero(); assembly.num_aux];
+
+    // Evaluate for inputs.
+    eval(
+        phase1,
+        &assembly.at_inputs,
+        &assembly.bt_inputs,
+        &assembly.ct_inputs,
+        &mut a[0..assembly.num_inputs],
+        &mut b_g1[0..assembly.num_inputs],
+        &mut b_g2[0..assembly.num_inputs],
+        &mut ic
+    );
+
+    // Evaluate for auxillary variables.
+    eval(
+        phase1,
+        &assembly.at_aux,
+        &assembly.bt_aux,
+        &assembly.ct_aux,
+        &mut a[assembly.num_inputs..],
+        &mut b_g1[assembly.num_inputs..],
+        &mut b_g2[assembly.num_inputs..],
+        &mut l
+    );
+
+    // Don't allow any elements be unconstrained, so that
+    // the L query is always fully dense.
+    for e in l.iter() {
+        if e.is_zero() {
+            return Err(SynthesisError::UnconstrainedVariable);
+        }
+    }
+
+    let vk = VerifyingKey::<E> {
+        alpha_g1: phase1.alpha_g1,
+        beta_g1: phase1.beta_g1,
+        beta_g2: phase1.beta_g2,
+        gamma_g2: E::G2Affine::one(),
+        delta_g1: E::G1Affine::one(),
+        delta_g2: E::G2Affine::one(),
+        ic: ic.into_iter().map(|e| e.into_affine()).collect()
+    };
+
+    Ok(Parameters {
+        vk: vk,
+        h: Arc::new(phase1.h.clone()),
+        l: Arc::new(l.into_iter().map(|e| e.into_affine()).collect()),
+
+        // Filter points at infinity away from A/B queries
+        a: Arc::new(a.into_iter().filter(|e| !e.is_zero()).map(|e| e.into_affine()).collect()),
+        b_g1: Arc::new(b_g1.into_iter().filter(|e| !e.is_zero()).map(|e| e.into_affine()).collect()),
+        b_g2: Arc::new(b_g2.into_iter().filter(|e| !e.is_zero()).map(|e| e.into_affine()).collect())
+    })
+}
@@ -0,0 +1,346 @@
+//! Proving parameters read on demand from a memory-mapped file.
+//!
+//! `Parameters::read` keeps every point of the proving key in memory, which
+//! for deep circuits is more than small machines have. `MappedParameters`
+//! maps a file written by `Parameters::write` or `write_encoded` instead and
+//! only parses the verifying key and the positions of the `h`, `l`, `a`,
+//! `b_g1` and `b_g2` vectors up front. The multiexponentiations of the
+//! prover decode the points they query straight from the mapping, so the
+//! operating system pages in the ranges in use and can drop them again
+//! under memory pressure.
+
+use pairing::{
+    Engine,
+    CurveProjective,
+    CurveAffine
+};
+
+use byteorder::{BigEndian, ReadBytesExt};
+use memmap::{Mmap, MmapOptions};
+
+use std::fs::File;
+use std::io::{self, Cursor};
+use std::marker::PhantomData;
+use std::path::Path;
+use std::sync::Arc;
+
+use super::{
+    Encoding,
+    ParameterSource,
+    VerifyingKey
+};
+use super::encoding;
+
+use multiexp::{Source, SourceBuilder};
+
+use ::{
+    SynthesisError
+};
+
+/// Position of a vector of points in the mapping.
+#[derive(Clone, Copy)]
+struct Range {
+    /// Byte offset of the first point
+    start: usize,
+    /// Number of points
+    len: usize,
+    /// Encoding of the points and their size in bytes
+    encoding: Encoding,
+    size: usize
+}
+
+/// Groth16 parameters whose proving key stays in a memory-mapped file.
+pub struct MappedParameters<E: Engine> {
+    map: Arc<Mmap>,
+    vk: VerifyingKey<E>,
+    h: Range,
+    l: Range,
+    a: Range,
+    b_g1: Range,
+    b_g2: Range
+}
+
+/// Reads the length of the vector at `offset` and checks that its points
+/// fit in the mapping.
+fn range<G: CurveAffine>(map: &[u8], offset: usize, encoding: Encoding) -> io::Result<Range> {
+    let len = Cursor::new(map.get(offset..).unwrap_or(&[])).read_u32::<BigEndian>()? as usize;
+    let range = Range { start: offset + 4, len, encoding, size: encoding::size::<G>(encoding) };
+    if map.len() < range.end() {
+        return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "the parameter file is truncated"));
+    }
+
+    Ok(range)
+}
+
+impl Range {
+    fn end(&self) -> usize {
+        self.start + self.len * self.size
+    }
+
+    fn bytes<'a>(&self, map: &'a [u8], i: usize) -> &'a [u8] {
+        let start = self.start + i * self.size;
+        &map[start..start + self.size]
+    }
+}
+
+/// Decodes the `i`th point of `range` without checking it is in the
+/// subgroup.
+fn point<G: CurveAffine>(map: &[u8], range: Range, i: usize) -> Result<G, SynthesisError> {
+    if i >= range.len {
+        return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "expected more bases from source").into());
+    }
+
+    encoding::decode(range.bytes(map, i), range.encoding, false).map_err(|e| e.into())
+}
+
+impl<E: Engine> MappedParameters<E> {
+    /// Maps a file written by `Parameters::write` or `write_encoded`. With
+    /// `checked`, every point is decoded once and checked to be in its
+    /// subgroup and not the point at infinity, like `Parameters::read` does;
+    /// points are not checked again when the prover reads them. Compressed
+    /// points are decompressed on every read, which makes proving slower
+    /// than from uncompressed parameters.
+    ///
+    /// The file must not be modified while it is mapped.
+    pub fn open<P: AsRef<Path>>(path: P, checked: bool) -> io::Result<MappedParameters<E>> {
+        Self::open_at(path, 0, checked)
+    }
+
+    /// Like `open`, for parameters that start `offset` bytes into the file,
+    /// e.g. after a header of their own.
+    pub fn open_at<P: AsRef<Path>>(path: P, offset: u64, checked: bool) -> io::Result<MappedParameters<E>> {
+        let file = File::open(path)?;
+        let map = unsafe { MmapOptions::new().map(&file)? };
+
+        let mut cursor = Cursor::new(&map[..]);
+        cursor.set_position(offset);
+        let (encoding, vk) = {
+            let (encoding, mut reader) = encoding::read_header(&mut cursor)?;
+            (encoding, VerifyingKey::<E>::read_body(&mut reader, encoding)?)
+        };
+        let h = range::<E::G1Affine>(&map, cursor.position() as usize, encoding)?;
+        let l = range::<E::G1Affine>(&map, h.end(), encoding)?;
+        let a = range::<E::G1Affine>(&map, l.end(), encoding)?;
+        let b_g1 = range::<E::G1Affine>(&map, a.end(), encoding)?;
+        let b_g2 = range::<E::G2Affine>(&map, b_g1.end(), encoding)?;
+
+        let params = MappedParameters { map: Arc::new(map), vk, h, l, a, b_g1, b_g2 };
+        if checked {
+            params.check()?;
+        }
+
+        Ok(params)
+    }
+
+    pub fn vk(&self) -> &VerifyingKey<E> {
+        &self.vk
+    }
+
+    fn check(&self) -> io::Result<()> {
+        fn check_range<G: CurveAffine>(map: &[u8], range: Range) -> io::Result<()> {
+            for i in 0..range.len {
+                let p: G = encoding::decode(range.bytes(map, i), range.encoding, true)?;
+                if p.is_zero() {
+                    return Err(io::Error::new(io::ErrorKind::InvalidData, "point at infinity"));
+                }
+            }
+
+            Ok(())
+        }
+
+        check_range::<E::G1Affine>(&self.map, self.h)?;
+        check_range::<E::G1Affine>(&self.map, self.l)?;
+        check_range::<E::G1Affine>(&self.map, self.a)?;
+        check_range::<E::G1Affine>(&self.map, self.b_g1)?;
+        check_range::<E::G2Affine>(&self.map, self.b_g2)
+    }
+
+    fn source<G: CurveAffine>(&self, range: Range, skip: usize) -> (MappedSource<G>, usize) {
+        (MappedSource { map: self.map.clone(), range, _marker: PhantomData }, skip)
+    }
+}
+
+/// The points of one vector of `MappedParameters`.
+pub struct MappedSource<G: CurveAffine> {
+    map: Arc<Mmap>,
+    range: Range,
+    _marker: PhantomData<G>
+}
+
+impl<G: CurveAffine> Clone for MappedSource<G> {
+    fn clone(&self) -> Self {
+        MappedSource { map: self.map.clone(), range: self.range, _marker: PhantomData }
+    }
+}
+
+impl<G: CurveAffine> SourceBuilder<G> for (MappedSource<G>, usize) {
+    type Source = (MappedSource<G>, usize);
+
+    fn new(self) -> (MappedSource<G>, usize) {
+        self
+    }
+}
+
+impl<G: CurveAffine> Source<G> for (MappedSource<G>, usize) {
+    fn add_assign_mixed(&mut self, to: &mut <G as CurveAffine>::Projective) -> Result<(), SynthesisError> {
+        let p: G = point(&self.0.map, self.0.range, self.1)?;
+        if p.is_zero() {
+            return Err(SynthesisError::UnexpectedIdentity)
+        }
+
+        to.add_assign_mixed(&p);
+
+        self.1 += 1;
+
+        Ok(())
+    }
+
+    fn skip(&mut self, amt: usize) -> Result<(), SynthesisError> {
+        if self.0.range.len <= self.1 {
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "expected more bases from source").into());
+        }
+
+        self.1 += amt;
+
+        Ok(())
+    }
+}
+
+impl<'a, E: Engine> ParameterSource<E> for &'a MappedParameters<E> {
+    type G1Builder = (MappedSource<E::G1Affine>, usize);
+    type G2Builder = (MappedSource<E::G2Affine>, usize);
+
+    fn get_vk(
+        &mut self,
+        _: usize
+    ) -> Result<VerifyingKey<E>, SynthesisError>
+    {
+        Ok(self.vk.clone())
+    }
+
+    fn get_h(
+        &mut self,
+        _: usize
+    ) -> Result<Self::G1Builder, SynthesisError>
+    {
+        Ok(self.source(self.h, 0))
+    }
+
+    fn get_l(
+        &mut self,
+        _: usize
+    ) -> Result<Self::G1Builder, SynthesisError>
+    {
+        Ok(self.source(self.l, 0))
+    }
+
+    fn get_a(
+        &mut self,
+        num_inputs: usize,
+        _: usize
+    ) -> Result<(Self::G1Builder, Self::G1Builder), SynthesisError>
+    {
+        Ok((self.source(self.a, 0), self.source(self.a, num_inputs)))
+    }
+
+    fn get_b_g1(
+        &mut self,
+        num_inputs: usize,
+        _: usize
+    ) -> Result<(Self::G1Builder, Self::G1Builder), SynthesisError>
+    {
+        Ok((self.source(self.b_g1, 0), self.source(self.b_g1, num_inputs)))
+    }
+
+    fn get_b_g2(
+        &mut self,
+        num_inputs: usize,
+        _: usize
+    ) -> Result<(Self::G2Builder, Self::G2Builder), SynthesisError>
+    {
+        Ok((self.source(self.b_g2, 0), self.source(self.b_g2, num_inputs)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::{Parameters, create_proof, generate_random_parameters, prepare_verifying_key, verify_proof};
+    use {Circuit, ConstraintSystem};
+
+    use ff::Field;
+    use pairing::bn256::{Bn256, Fr};
+    use rand::{Rng, SeedableRng, XorShiftRng};
+    use std::env;
+    use std::fs;
+
+    struct Cube {
+        x: Option<Fr>
+    }
+
+    impl Circuit<Bn256> for Cube {
+        fn synthesize<CS: ConstraintSystem<Bn256>>(
+            self,
+            cs: &mut CS
+        ) -> Result<(), SynthesisError>
+        {
+            let x_value = self.x;
+            let x2_value = x_value.map(|mut x| { x.square(); x });
+            let x3_value = x_value.and_then(|x| x2_value.map(|mut x2| { x2.mul_assign(&x); x2 }));
+
+            let x = cs.alloc(|| "x", || x_value.ok_or(SynthesisError::AssignmentMissing))?;
+            let x2 = cs.alloc(|| "x2", || x2_value.ok_or(SynthesisError::AssignmentMissing))?;
+            let x3 = cs.alloc_input(|| "x3", || x3_value.ok_or(SynthesisError::AssignmentMissing))?;
+
+            cs.enforce(|| "x2", |lc| lc + x, |lc| lc + x, |lc| lc + x2);
+            cs.enforce(|| "x3", |lc| lc + x2, |lc| lc + x, |lc| lc + x3);
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn mapped_parameters() {
+        let rng = &mut XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+        let params = generate_random_parameters::<Bn256, _, _>(Cube { x: None }, rng).unwrap();
+
+        let path = env::temp_dir().join(format!("bellman-mapped-{}.params", rng.gen::<u64>()));
+        let mut v = vec![];
+        params.write(&mut v).unwrap();
+        fs::write(&path, &v).unwrap();
+
+        let mapped = MappedParameters::<Bn256>::open(&path, true).unwrap();
+        assert!(mapped.vk() == &params.vk);
+
+        let x: Fr = rng.gen();
+        let mut x3 = x;
+        x3.square();
+        x3.mul_assign(&x);
+        let r: Fr = rng.gen();
+        let s: Fr = rng.gen();
+
+        // the same proof as from the parameters in memory
+        let proof = create_proof(Cube { x: Some(x) }, &mapped, r, s).unwrap();
+        assert!(proof == create_proof(Cube { x: Some(x) }, &params, r, s).unwrap());
+        assert!(verify_proof(&prepare_verifying_key(mapped.vk()), &proof, &[x3]).unwrap());
+
+        // rewriting a mapped file is undefined behaviour, so unmap it first
+        drop(mapped);
+
+        // compressed parameters map as well
+        let mut compressed = vec![];
+        params.write_encoded(&mut compressed, Encoding::Compressed).unwrap();
+        fs::write(&path, &compressed).unwrap();
+        let mapped = MappedParameters::<Bn256>::open(&path, true).unwrap();
+        assert!(proof == create_proof(Cube { x: Some(x) }, &mapped, r, s).unwrap());
+
+        drop(mapped);
+
+        // truncated files are rejected when mapped
+        fs::write(&path, &v[..v.len() - 1]).unwrap();
+        assert!(MappedParameters::<Bn256>::open(&path, false).is_err());
+        assert!(Parameters::<Bn256>::read(&v[..v.len() - 1], false).is_err());
+
+        fs::remove_file(&path).unwrap();
+    }
+}
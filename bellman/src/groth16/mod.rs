@@ -4,25 +4,53 @@ use pairing::{
     EncodedPoint
 };
 
+#[cfg(feature = "prover")]
 use ::{
     SynthesisError
 };
 
+#[cfg(feature = "prover")]
 use multiexp::SourceBuilder;
+#[cfg(feature = "prover")]
+use multicore::Worker;
 use std::io::{self, Read, Write};
+#[cfg(feature = "prover")]
 use std::sync::Arc;
-use byteorder::{BigEndian, WriteBytesExt, ReadBytesExt};
+use std::vec::Vec;
+use byteorder::{BigEndian, ByteOrder};
 
-#[cfg(test)]
+#[cfg(all(test, feature = "prover"))]
 mod tests;
 
+#[cfg(feature = "prover")]
 mod generator;
+#[cfg(feature = "prover")]
 mod prover;
+#[cfg(feature = "prover")]
+mod mapped;
+#[cfg(feature = "prover")]
+mod witness;
+#[cfg(feature = "prover")]
+mod shape;
 mod verifier;
+mod encoding;
+#[cfg(feature = "std")]
+mod aggregate;
 
+#[cfg(feature = "prover")]
 pub use self::generator::*;
+#[cfg(feature = "prover")]
 pub use self::prover::*;
+#[cfg(feature = "prover")]
+pub use self::mapped::*;
+#[cfg(feature = "prover")]
+pub use self::witness::*;
+#[cfg(feature = "prover")]
+pub use self::shape::*;
 pub use self::verifier::*;
+pub use self::encoding::Encoding;
+#[cfg(feature = "std")]
+pub use self::aggregate::*;
 
 #[derive(Clone)]
 pub struct Proof<E: Engine> {
@@ -138,80 +166,73 @@ impl<E: Engine> PartialEq for VerifyingKey<E> {
 }
 
 impl<E: Engine> VerifyingKey<E> {
+    /// Writes the key with uncompressed points and no header, the layout
+    /// `read` falls back to.
     pub fn write<W: Write>(
         &self,
-        mut writer: W
+        writer: W
     ) -> io::Result<()>
     {
-        writer.write_all(self.alpha_g1.into_uncompressed().as_ref())?;
-        writer.write_all(self.beta_g1.into_uncompressed().as_ref())?;
-        writer.write_all(self.beta_g2.into_uncompressed().as_ref())?;
-        writer.write_all(self.gamma_g2.into_uncompressed().as_ref())?;
-        writer.write_all(self.delta_g1.into_uncompressed().as_ref())?;
-        writer.write_all(self.delta_g2.into_uncompressed().as_ref())?;
-        writer.write_u32::<BigEndian>(self.ic.len() as u32)?;
-        for ic in &self.ic {
-            writer.write_all(ic.into_uncompressed().as_ref())?;
-        }
+        self.write_body(writer, Encoding::Uncompressed)
+    }
 
-        Ok(())
+    /// Writes the key behind a header recording `encoding`, which `read`
+    /// detects.
+    pub fn write_encoded<W: Write>(
+        &self,
+        mut writer: W,
+        encoding: Encoding
+    ) -> io::Result<()>
+    {
+        encoding::write_header(&mut writer, encoding)?;
+        self.write_body(writer, encoding)
     }
 
+    fn write_body<W: Write>(
+        &self,
+        mut writer: W,
+        encoding: Encoding
+    ) -> io::Result<()>
+    {
+        encoding::write_point(&mut writer, &self.alpha_g1, encoding)?;
+        encoding::write_point(&mut writer, &self.beta_g1, encoding)?;
+        encoding::write_point(&mut writer, &self.beta_g2, encoding)?;
+        encoding::write_point(&mut writer, &self.gamma_g2, encoding)?;
+        encoding::write_point(&mut writer, &self.delta_g1, encoding)?;
+        encoding::write_point(&mut writer, &self.delta_g2, encoding)?;
+        encoding::write_points(&mut writer, &self.ic, encoding)
+    }
+
+    /// Reads a key written by `write` or `write_encoded`, or the key at the
+    /// start of parameters written by `Parameters::write` or
+    /// `Parameters::write_encoded`.
     pub fn read<R: Read>(
-        mut reader: R
+        reader: R
     ) -> io::Result<Self>
     {
-        let mut g1_repr = <E::G1Affine as CurveAffine>::Uncompressed::empty();
-        let mut g2_repr = <E::G2Affine as CurveAffine>::Uncompressed::empty();
+        let (encoding, mut reader) = encoding::read_header(reader)?;
 
-        reader.read_exact(g1_repr.as_mut())?;
-        let alpha_g1 = g1_repr.into_affine().map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
-
-        reader.read_exact(g1_repr.as_mut())?;
-        let beta_g1 = g1_repr.into_affine().map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
-
-        reader.read_exact(g2_repr.as_mut())?;
-        let beta_g2 = g2_repr.into_affine().map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
-
-        reader.read_exact(g2_repr.as_mut())?;
-        let gamma_g2 = g2_repr.into_affine().map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
-
-        reader.read_exact(g1_repr.as_mut())?;
-        let delta_g1 = g1_repr.into_affine().map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
-
-        reader.read_exact(g2_repr.as_mut())?;
-        let delta_g2 = g2_repr.into_affine().map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
-
-        let ic_len = reader.read_u32::<BigEndian>()? as usize;
-
-        let mut ic = vec![];
-
-        for _ in 0..ic_len {
-            reader.read_exact(g1_repr.as_mut())?;
-            let g1 = g1_repr
-                     .into_affine()
-                     .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
-                     .and_then(|e| if e.is_zero() {
-                         Err(io::Error::new(io::ErrorKind::InvalidData, "point at infinity"))
-                     } else {
-                         Ok(e)
-                     })?;
-
-            ic.push(g1);
-        }
+        Self::read_body(&mut reader, encoding)
+    }
 
+    fn read_body<R: Read>(
+        mut reader: R,
+        encoding: Encoding
+    ) -> io::Result<Self>
+    {
         Ok(VerifyingKey {
-            alpha_g1: alpha_g1,
-            beta_g1: beta_g1,
-            beta_g2: beta_g2,
-            gamma_g2: gamma_g2,
-            delta_g1: delta_g1,
-            delta_g2: delta_g2,
-            ic: ic
+            alpha_g1: encoding::read_point(&mut reader, encoding, true)?,
+            beta_g1: encoding::read_point(&mut reader, encoding, true)?,
+            beta_g2: encoding::read_point(&mut reader, encoding, true)?,
+            gamma_g2: encoding::read_point(&mut reader, encoding, true)?,
+            delta_g1: encoding::read_point(&mut reader, encoding, true)?,
+            delta_g2: encoding::read_point(&mut reader, encoding, true)?,
+            ic: encoding::read_points(&mut reader, encoding, true)?
         })
     }
 }
 
+#[cfg(feature = "prover")]
 #[derive(Clone)]
 pub struct Parameters<E: Engine> {
     pub vk: VerifyingKey<E>,
@@ -237,6 +258,7 @@ pub struct Parameters<E: Engine> {
     pub b_g2: Arc<Vec<E::G2Affine>>
 }
 
+#[cfg(feature = "prover")]
 impl<E: Engine> PartialEq for Parameters<E> {
     fn eq(&self, other: &Self) -> bool {
         self.vk == other.vk &&
@@ -248,135 +270,97 @@ impl<E: Engine> PartialEq for Parameters<E> {
     }
 }
 
+#[cfg(feature = "prover")]
 impl<E: Engine> Parameters<E> {
+    /// Writes the parameters with uncompressed points and no header, the
+    /// layout `read` falls back to and `MappedParameters` reads fastest.
     pub fn write<W: Write>(
         &self,
-        mut writer: W
+        writer: W
     ) -> io::Result<()>
     {
-        self.vk.write(&mut writer)?;
-
-        writer.write_u32::<BigEndian>(self.h.len() as u32)?;
-        for g in &self.h[..] {
-            writer.write_all(g.into_uncompressed().as_ref())?;
-        }
-
-        writer.write_u32::<BigEndian>(self.l.len() as u32)?;
-        for g in &self.l[..] {
-            writer.write_all(g.into_uncompressed().as_ref())?;
-        }
-
-        writer.write_u32::<BigEndian>(self.a.len() as u32)?;
-        for g in &self.a[..] {
-            writer.write_all(g.into_uncompressed().as_ref())?;
-        }
-
-        writer.write_u32::<BigEndian>(self.b_g1.len() as u32)?;
-        for g in &self.b_g1[..] {
-            writer.write_all(g.into_uncompressed().as_ref())?;
-        }
+        self.write_body(writer, Encoding::Uncompressed)
+    }
 
-        writer.write_u32::<BigEndian>(self.b_g2.len() as u32)?;
-        for g in &self.b_g2[..] {
-            writer.write_all(g.into_uncompressed().as_ref())?;
-        }
+    /// Writes the parameters behind a header recording `encoding`, which
+    /// `read` detects. Compressed parameters are about half the size.
+    pub fn write_encoded<W: Write>(
+        &self,
+        mut writer: W,
+        encoding: Encoding
+    ) -> io::Result<()>
+    {
+        encoding::write_header(&mut writer, encoding)?;
+        self.write_body(writer, encoding)
+    }
 
-        Ok(())
+    fn write_body<W: Write>(
+        &self,
+        mut writer: W,
+        encoding: Encoding
+    ) -> io::Result<()>
+    {
+        self.vk.write_body(&mut writer, encoding)?;
+        encoding::write_points(&mut writer, &self.h, encoding)?;
+        encoding::write_points(&mut writer, &self.l, encoding)?;
+        encoding::write_points(&mut writer, &self.a, encoding)?;
+        encoding::write_points(&mut writer, &self.b_g1, encoding)?;
+        encoding::write_points(&mut writer, &self.b_g2, encoding)
     }
 
+    /// Reads parameters written by `write` or `write_encoded`. Without
+    /// `checked`, uncompressed points of the proving key are not checked to
+    /// be on the curve and in the subgroup; compressed points always are.
     pub fn read<R: Read>(
-        mut reader: R,
+        reader: R,
         checked: bool
     ) -> io::Result<Self>
     {
-        let read_g1 = |reader: &mut R| -> io::Result<E::G1Affine> {
-            let mut repr = <E::G1Affine as CurveAffine>::Uncompressed::empty();
-            reader.read_exact(repr.as_mut())?;
-
-            if checked {
-                repr
-                .into_affine()
-            } else {
-                repr
-                .into_affine_unchecked()
-            }
-            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
-            .and_then(|e| if e.is_zero() {
-                Err(io::Error::new(io::ErrorKind::InvalidData, "point at infinity"))
-            } else {
-                Ok(e)
-            })
-        };
-
-        let read_g2 = |reader: &mut R| -> io::Result<E::G2Affine> {
-            let mut repr = <E::G2Affine as CurveAffine>::Uncompressed::empty();
-            reader.read_exact(repr.as_mut())?;
-
-            if checked {
-                repr
-                .into_affine()
-            } else {
-                repr
-                .into_affine_unchecked()
-            }
-            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
-            .and_then(|e| if e.is_zero() {
-                Err(io::Error::new(io::ErrorKind::InvalidData, "point at infinity"))
-            } else {
-                Ok(e)
-            })
-        };
-
-        let vk = VerifyingKey::<E>::read(&mut reader)?;
-
-        let mut h = vec![];
-        let mut l = vec![];
-        let mut a = vec![];
-        let mut b_g1 = vec![];
-        let mut b_g2 = vec![];
-
-        {
-            let len = reader.read_u32::<BigEndian>()? as usize;
-            for _ in 0..len {
-                h.push(read_g1(&mut reader)?);
-            }
-        }
+        Self::read_with(reader, checked, None)
+    }
 
-        {
-            let len = reader.read_u32::<BigEndian>()? as usize;
-            for _ in 0..len {
-                l.push(read_g1(&mut reader)?);
-            }
-        }
+    /// Like `read`, but decodes the points of each vector on all cores,
+    /// which mostly pays off for compressed parameters. Without the
+    /// `multithread` feature it is the same as `read`.
+    pub fn read_parallel<R: Read>(
+        reader: R,
+        checked: bool
+    ) -> io::Result<Self>
+    {
+        Self::read_with(reader, checked, Some(&Worker::new()))
+    }
 
-        {
-            let len = reader.read_u32::<BigEndian>()? as usize;
-            for _ in 0..len {
-                a.push(read_g1(&mut reader)?);
-            }
-        }
+    fn read_with<R: Read>(
+        reader: R,
+        checked: bool,
+        worker: Option<&Worker>
+    ) -> io::Result<Self>
+    {
+        let (encoding, mut reader) = encoding::read_header(reader)?;
+        let checked = checked || encoding == Encoding::Compressed;
 
-        {
-            let len = reader.read_u32::<BigEndian>()? as usize;
-            for _ in 0..len {
-                b_g1.push(read_g1(&mut reader)?);
-            }
-        }
+        let vk = VerifyingKey::<E>::read_body(&mut reader, encoding)?;
 
+        fn points<G: CurveAffine, R: Read>(
+            reader: R,
+            encoding: Encoding,
+            checked: bool,
+            worker: Option<&Worker>
+        ) -> io::Result<Arc<Vec<G>>>
         {
-            let len = reader.read_u32::<BigEndian>()? as usize;
-            for _ in 0..len {
-                b_g2.push(read_g2(&mut reader)?);
-            }
+            match worker {
+                Some(worker) => encoding::read_points_parallel(reader, encoding, checked, worker),
+                None => encoding::read_points(reader, encoding, checked)
+            }.map(Arc::new)
         }
 
         Ok(Parameters {
             vk: vk,
-            h: Arc::new(h),
-            l: Arc::new(l),
-            a: Arc::new(a),
-            b_g1: Arc::new(b_g1),
-            b_g2: Arc::new(b_g2)
+            h: points(&mut reader, encoding, checked, worker)?,
+            l: points(&mut reader, encoding, checked, worker)?,
+            a: points(&mut reader, encoding, checked, worker)?,
+            b_g1: points(&mut reader, encoding, checked, worker)?,
+            b_g2: points(&mut reader, encoding, checked, worker)?
         })
     }
 }
@@ -389,9 +373,108 @@ pub struct PreparedVerifyingKey<E: Engine> {
     /// -delta in G2
     neg_delta_g2: <E::G2Affine as CurveAffine>::Prepared,
     /// Copy of IC from `VerifiyingKey`.
-    ic: Vec<E::G1Affine>
+    ic: Vec<E::G1Affine>,
+    /// The points the above were computed from, kept for `write`.
+    alpha_g1: E::G1Affine,
+    beta_g2: E::G2Affine,
+    gamma_g2: E::G2Affine,
+    delta_g2: E::G2Affine
 }
 
+impl<E: Engine> PreparedVerifyingKey<E> {
+    pub(crate) fn from_points(
+        alpha_g1: E::G1Affine,
+        beta_g2: E::G2Affine,
+        gamma_g2: E::G2Affine,
+        delta_g2: E::G2Affine,
+        ic: Vec<E::G1Affine>
+    ) -> Self
+    {
+        let mut neg_gamma = gamma_g2;
+        neg_gamma.negate();
+        let mut neg_delta = delta_g2;
+        neg_delta.negate();
+
+        PreparedVerifyingKey {
+            alpha_g1_beta_g2: E::pairing(alpha_g1, beta_g2),
+            neg_gamma_g2: neg_gamma.prepare(),
+            neg_delta_g2: neg_delta.prepare(),
+            ic: ic,
+            alpha_g1: alpha_g1,
+            beta_g2: beta_g2,
+            gamma_g2: gamma_g2,
+            delta_g2: delta_g2
+        }
+    }
+
+    /// Writes the part of the verifying key needed for verification:
+    /// alpha in G1, beta, gamma and delta in G2 and IC, leaving out the
+    /// elements only the prover uses.
+    pub fn write<W: Write>(
+        &self,
+        mut writer: W
+    ) -> io::Result<()>
+    {
+        writer.write_all(self.alpha_g1.into_uncompressed().as_ref())?;
+        writer.write_all(self.beta_g2.into_uncompressed().as_ref())?;
+        writer.write_all(self.gamma_g2.into_uncompressed().as_ref())?;
+        writer.write_all(self.delta_g2.into_uncompressed().as_ref())?;
+        let mut ic_len = [0u8; 4];
+        BigEndian::write_u32(&mut ic_len, self.ic.len() as u32);
+        writer.write_all(&ic_len)?;
+        for ic in &self.ic {
+            writer.write_all(ic.into_uncompressed().as_ref())?;
+        }
+
+        Ok(())
+    }
+
+    /// Reads a key written by `write` and prepares it again, which costs
+    /// one pairing.
+    pub fn read<R: Read>(
+        mut reader: R
+    ) -> io::Result<Self>
+    {
+        let mut g1_repr = <E::G1Affine as CurveAffine>::Uncompressed::empty();
+        let mut g2_repr = <E::G2Affine as CurveAffine>::Uncompressed::empty();
+
+        reader.read_exact(g1_repr.as_mut())?;
+        let alpha_g1 = g1_repr.into_affine().map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        reader.read_exact(g2_repr.as_mut())?;
+        let beta_g2 = g2_repr.into_affine().map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        reader.read_exact(g2_repr.as_mut())?;
+        let gamma_g2 = g2_repr.into_affine().map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        reader.read_exact(g2_repr.as_mut())?;
+        let delta_g2 = g2_repr.into_affine().map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        let mut ic_len = [0u8; 4];
+        reader.read_exact(&mut ic_len)?;
+        let ic_len = BigEndian::read_u32(&ic_len) as usize;
+
+        let mut ic = vec![];
+
+        for _ in 0..ic_len {
+            reader.read_exact(g1_repr.as_mut())?;
+            let g1 = g1_repr
+                     .into_affine()
+                     .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+                     .and_then(|e| if e.is_zero() {
+                         Err(io::Error::new(io::ErrorKind::InvalidData, "point at infinity"))
+                     } else {
+                         Ok(e)
+                     })?;
+
+            ic.push(g1);
+        }
+
+        Ok(PreparedVerifyingKey::from_points(alpha_g1, beta_g2, gamma_g2, delta_g2, ic))
+    }
+}
+
+#[cfg(feature = "prover")]
 pub trait ParameterSource<E: Engine> {
     type G1Builder: SourceBuilder<E::G1Affine>;
     type G2Builder: SourceBuilder<E::G2Affine>;
@@ -425,6 +508,7 @@ pub trait ParameterSource<E: Engine> {
     ) -> Result<(Self::G2Builder, Self::G2Builder), SynthesisError>;
 }
 
+#[cfg(feature = "prover")]
 impl<'a, E: Engine> ParameterSource<E> for &'a Parameters<E> {
     type G1Builder = (Arc<Vec<E::G1Affine>>, usize);
     type G2Builder = (Arc<Vec<E::G2Affine>>, usize);
@@ -481,13 +565,13 @@ impl<'a, E: Engine> ParameterSource<E> for &'a Parameters<E> {
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "prover"))]
 mod test_with_bls12_381 {
     use super::*;
     use {Circuit, SynthesisError, ConstraintSystem};
 
     use rand::{Rand, thread_rng};
-    use pairing::{Field};
+    use ff::Field;
     use pairing::bls12_381::{Bls12, Fr};
 
     #[test]
@@ -573,4 +657,73 @@ mod test_with_bls12_381 {
             assert!(!verify_proof(&pvk, &proof, &[a]).unwrap());
         }
     }
+
+    #[test]
+    fn batch_verification() {
+        struct Square<E: Engine> {
+            a: Option<E::Fr>
+        }
+
+        impl<E: Engine> Circuit<E> for Square<E> {
+            fn synthesize<CS: ConstraintSystem<E>>(
+                self,
+                cs: &mut CS
+            ) -> Result<(), SynthesisError>
+            {
+                let a = cs.alloc(|| "a", || self.a.ok_or(SynthesisError::AssignmentMissing))?;
+                let c = cs.alloc_input(|| "c", || {
+                    let mut a = self.a.ok_or(SynthesisError::AssignmentMissing)?;
+                    a.square();
+                    Ok(a)
+                })?;
+
+                cs.enforce(
+                    || "a*a=c",
+                    |lc| lc + a,
+                    |lc| lc + a,
+                    |lc| lc + c
+                );
+
+                Ok(())
+            }
+        }
+
+        let rng = &mut thread_rng();
+
+        let params = generate_random_parameters::<Bls12, _, _>(
+            Square { a: None },
+            rng
+        ).unwrap();
+        let pvk = prepare_verifying_key::<Bls12>(&params.vk);
+
+        let mut proofs = vec![];
+        let mut inputs = vec![];
+        for _ in 0..8 {
+            let a = Fr::rand(rng);
+            let mut c = a;
+            c.square();
+
+            proofs.push(create_random_proof(Square { a: Some(a) }, &params, rng).unwrap());
+            inputs.push(vec![c]);
+        }
+
+        {
+            let batch: Vec<_> = proofs.iter().zip(inputs.iter()).map(|(p, i)| (p, &i[..])).collect();
+            assert!(verify_proofs_batch(&pvk, rng, &batch).unwrap().is_empty());
+            assert!(verify_proofs_batch(&pvk, rng, &batch[..1]).unwrap().is_empty());
+            assert!(verify_proofs_batch::<Bls12, _>(&pvk, rng, &[]).unwrap().is_empty());
+        }
+
+        // a proof for another input, and two proofs swapped
+        inputs[2][0] = Fr::rand(rng);
+        proofs.swap(5, 6);
+        {
+            let batch: Vec<_> = proofs.iter().zip(inputs.iter()).map(|(p, i)| (p, &i[..])).collect();
+            assert_eq!(verify_proofs_batch(&pvk, rng, &batch).unwrap(), vec![2, 5, 6]);
+        }
+
+        // the wrong number of inputs
+        let batch = [(&proofs[0], &[][..])];
+        assert!(verify_proofs_batch(&pvk, rng, &batch).is_err());
+    }
 }
@@ -1,5 +1,6 @@
 use rand::Rng;
 
+use std::io;
 use std::sync::Arc;
 
 use futures::Future;
@@ -16,6 +17,7 @@ use ff::{
 };
 
 use super::{
+    Assignment,
     ParameterSource,
     Proof
 };
@@ -99,6 +101,21 @@ struct ProvingAssignment<E: Engine> {
     aux_assignment: Vec<E::Fr>
 }
 
+impl<E: Engine> ProvingAssignment<E> {
+    fn new() -> Self {
+        ProvingAssignment {
+            a_aux_density: DensityTracker::new(),
+            b_input_density: DensityTracker::new(),
+            b_aux_density: DensityTracker::new(),
+            a: vec![],
+            b: vec![],
+            c: vec![],
+            input_assignment: vec![],
+            aux_assignment: vec![]
+        }
+    }
+}
+
 impl<E: Engine> ConstraintSystem<E> for ProvingAssignment<E> {
     type Root = Self;
 
@@ -191,6 +208,74 @@ impl<E: Engine> ConstraintSystem<E> for ProvingAssignment<E> {
     }
 }
 
+/// Synthesizes a circuit with the variable values of an `Assignment`,
+/// without calling the closures that would compute them.
+struct ReplayAssignment<'a, E: Engine + 'a> {
+    prover: ProvingAssignment<E>,
+    assignment: &'a Assignment<E>
+}
+
+impl<'a, E: Engine> ConstraintSystem<E> for ReplayAssignment<'a, E> {
+    type Root = Self;
+
+    fn alloc<F, A, AR>(
+        &mut self,
+        annotation: A,
+        _: F
+    ) -> Result<Variable, SynthesisError>
+        where F: FnOnce() -> Result<E::Fr, SynthesisError>, A: FnOnce() -> AR, AR: Into<String>
+    {
+        let value = *self.assignment.aux.get(self.prover.aux_assignment.len())
+            .ok_or(SynthesisError::AssignmentMissing)?;
+
+        self.prover.alloc(annotation, || Ok(value))
+    }
+
+    fn alloc_input<F, A, AR>(
+        &mut self,
+        annotation: A,
+        _: F
+    ) -> Result<Variable, SynthesisError>
+        where F: FnOnce() -> Result<E::Fr, SynthesisError>, A: FnOnce() -> AR, AR: Into<String>
+    {
+        // The assignment leaves out the constant one at input 0.
+        let value = *self.assignment.inputs.get(self.prover.input_assignment.len() - 1)
+            .ok_or(SynthesisError::AssignmentMissing)?;
+
+        self.prover.alloc_input(annotation, || Ok(value))
+    }
+
+    fn enforce<A, AR, LA, LB, LC>(
+        &mut self,
+        annotation: A,
+        a: LA,
+        b: LB,
+        c: LC
+    )
+        where A: FnOnce() -> AR, AR: Into<String>,
+              LA: FnOnce(LinearCombination<E>) -> LinearCombination<E>,
+              LB: FnOnce(LinearCombination<E>) -> LinearCombination<E>,
+              LC: FnOnce(LinearCombination<E>) -> LinearCombination<E>
+    {
+        self.prover.enforce(annotation, a, b, c)
+    }
+
+    fn push_namespace<NR, N>(&mut self, _: N)
+        where NR: Into<String>, N: FnOnce() -> NR
+    {
+        // Do nothing; we don't care about namespaces in this context.
+    }
+
+    fn pop_namespace(&mut self)
+    {
+        // Do nothing; we don't care about namespaces in this context.
+    }
+
+    fn get_root(&mut self) -> &mut Self::Root {
+        self
+    }
+}
+
 pub fn create_random_proof<E, C, R, P: ParameterSource<E>>(
     circuit: C,
     params: P,
@@ -206,22 +291,13 @@ pub fn create_random_proof<E, C, R, P: ParameterSource<E>>(
 
 pub fn create_proof<E, C, P: ParameterSource<E>>(
     circuit: C,
-    mut params: P,
+    params: P,
     r: E::Fr,
     s: E::Fr
 ) -> Result<Proof<E>, SynthesisError>
     where E: Engine, C: Circuit<E>
 {
-    let mut prover = ProvingAssignment {
-        a_aux_density: DensityTracker::new(),
-        b_input_density: DensityTracker::new(),
-        b_aux_density: DensityTracker::new(),
-        a: vec![],
-        b: vec![],
-        c: vec![],
-        input_assignment: vec![],
-        aux_assignment: vec![]
-    };
+    let mut prover = ProvingAssignment::new();
 
     prover.alloc_input(|| "", || Ok(E::Fr::one()))?;
 
@@ -235,7 +311,109 @@ pub fn create_proof<E, C, P: ParameterSource<E>>(
         );
     }
 
-    let worker = Worker::new();
+    prove(prover.into(), params, r, s, &Worker::new())
+}
+
+pub fn create_random_proof_from_assignment<E, C, R, P: ParameterSource<E>>(
+    circuit: C,
+    params: P,
+    assignment: &Assignment<E>,
+    rng: &mut R
+) -> Result<Proof<E>, SynthesisError>
+    where E: Engine, C: Circuit<E>, R: Rng
+{
+    let r = rng.gen();
+    let s = rng.gen();
+
+    create_proof_from_assignment::<E, C, P>(circuit, params, assignment, r, s)
+}
+
+/// Like `create_proof`, with the values of the variables taken from
+/// `assignment` instead of the closures of `circuit`, which is synthesized
+/// only for its constraints and may be built without any witness.
+pub fn create_proof_from_assignment<E, C, P: ParameterSource<E>>(
+    circuit: C,
+    params: P,
+    assignment: &Assignment<E>,
+    r: E::Fr,
+    s: E::Fr
+) -> Result<Proof<E>, SynthesisError>
+    where E: Engine, C: Circuit<E>
+{
+    let mut replay = ReplayAssignment {
+        prover: ProvingAssignment::new(),
+        assignment: assignment
+    };
+
+    replay.prover.alloc_input(|| "", || Ok(E::Fr::one()))?;
+
+    circuit.synthesize(&mut replay)?;
+
+    let mut prover = replay.prover;
+
+    if prover.input_assignment.len() != assignment.inputs.len() + 1 ||
+       prover.aux_assignment.len() != assignment.aux.len()
+    {
+        return Err(SynthesisError::IoError(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "the assignment has more variables than the circuit"
+        )));
+    }
+
+    for i in 0..prover.input_assignment.len() {
+        prover.enforce(|| "",
+            |lc| lc + Variable(Index::Input(i)),
+            |lc| lc,
+            |lc| lc,
+        );
+    }
+
+    prove(prover.into(), params, r, s, &Worker::new())
+}
+
+/// A circuit evaluated at an assignment, ready for the FFTs and
+/// multiexponentiations.
+pub(crate) struct Synthesized<E: Engine> {
+    // Density of queries
+    pub(crate) a_aux_density: Arc<DensityTracker>,
+    pub(crate) b_input_density: Arc<DensityTracker>,
+    pub(crate) b_aux_density: Arc<DensityTracker>,
+
+    // Evaluations of A, B, C polynomials
+    pub(crate) a: Vec<Scalar<E>>,
+    pub(crate) b: Vec<Scalar<E>>,
+    pub(crate) c: Vec<Scalar<E>>,
+
+    // Assignments of variables
+    pub(crate) input_assignment: Vec<E::Fr>,
+    pub(crate) aux_assignment: Vec<E::Fr>
+}
+
+impl<E: Engine> From<ProvingAssignment<E>> for Synthesized<E> {
+    fn from(prover: ProvingAssignment<E>) -> Self {
+        Synthesized {
+            a_aux_density: Arc::new(prover.a_aux_density),
+            b_input_density: Arc::new(prover.b_input_density),
+            b_aux_density: Arc::new(prover.b_aux_density),
+            a: prover.a,
+            b: prover.b,
+            c: prover.c,
+            input_assignment: prover.input_assignment,
+            aux_assignment: prover.aux_assignment
+        }
+    }
+}
+
+/// Computes the proof from a synthesized circuit.
+pub(crate) fn prove<E, P: ParameterSource<E>>(
+    prover: Synthesized<E>,
+    mut params: P,
+    r: E::Fr,
+    s: E::Fr,
+    worker: &Worker
+) -> Result<Proof<E>, SynthesisError>
+    where E: Engine
+{
 
     let vk = params.get_vk(prover.input_assignment.len())?;
 
@@ -243,55 +421,55 @@ pub fn create_proof<E, C, P: ParameterSource<E>>(
         let mut a = EvaluationDomain::from_coeffs(prover.a)?;
         let mut b = EvaluationDomain::from_coeffs(prover.b)?;
         let mut c = EvaluationDomain::from_coeffs(prover.c)?;
-        a.ifft(&worker);
-        a.coset_fft(&worker);
-        b.ifft(&worker);
-        b.coset_fft(&worker);
-        c.ifft(&worker);
-        c.coset_fft(&worker);
-
-        a.mul_assign(&worker, &b);
+        a.ifft(worker);
+        a.coset_fft(worker);
+        b.ifft(worker);
+        b.coset_fft(worker);
+        c.ifft(worker);
+        c.coset_fft(worker);
+
+        a.mul_assign(worker, &b);
         drop(b);
-        a.sub_assign(&worker, &c);
+        a.sub_assign(worker, &c);
         drop(c);
-        a.divide_by_z_on_coset(&worker);
-        a.icoset_fft(&worker);
+        a.divide_by_z_on_coset(worker);
+        a.icoset_fft(worker);
         let mut a = a.into_coeffs();
         let a_len = a.len() - 1;
         a.truncate(a_len);
         // TODO: parallelize if it's even helpful
         let a = Arc::new(a.into_iter().map(|s| s.0.into_repr()).collect::<Vec<_>>());
 
-        multiexp(&worker, params.get_h(a.len())?, FullDensity, a)
+        multiexp(worker, params.get_h(a.len())?, FullDensity, a)
     };
 
     // TODO: parallelize if it's even helpful
     let input_assignment = Arc::new(prover.input_assignment.into_iter().map(|s| s.into_repr()).collect::<Vec<_>>());
     let aux_assignment = Arc::new(prover.aux_assignment.into_iter().map(|s| s.into_repr()).collect::<Vec<_>>());
 
-    let l = multiexp(&worker, params.get_l(aux_assignment.len())?, FullDensity, aux_assignment.clone());
+    let l = multiexp(worker, params.get_l(aux_assignment.len())?, FullDensity, aux_assignment.clone());
 
     let a_aux_density_total = prover.a_aux_density.get_total_density();
 
     let (a_inputs_source, a_aux_source) = params.get_a(input_assignment.len(), a_aux_density_total)?;
 
-    let a_inputs = multiexp(&worker, a_inputs_source, FullDensity, input_assignment.clone());
-    let a_aux = multiexp(&worker, a_aux_source, Arc::new(prover.a_aux_density), aux_assignment.clone());
+    let a_inputs = multiexp(worker, a_inputs_source, FullDensity, input_assignment.clone());
+    let a_aux = multiexp(worker, a_aux_source, prover.a_aux_density, aux_assignment.clone());
 
-    let b_input_density = Arc::new(prover.b_input_density);
+    let b_input_density = prover.b_input_density;
     let b_input_density_total = b_input_density.get_total_density();
-    let b_aux_density = Arc::new(prover.b_aux_density);
+    let b_aux_density = prover.b_aux_density;
     let b_aux_density_total = b_aux_density.get_total_density();
 
     let (b_g1_inputs_source, b_g1_aux_source) = params.get_b_g1(b_input_density_total, b_aux_density_total)?;
 
-    let b_g1_inputs = multiexp(&worker, b_g1_inputs_source, b_input_density.clone(), input_assignment.clone());
-    let b_g1_aux = multiexp(&worker, b_g1_aux_source, b_aux_density.clone(), aux_assignment.clone());
+    let b_g1_inputs = multiexp(worker, b_g1_inputs_source, b_input_density.clone(), input_assignment.clone());
+    let b_g1_aux = multiexp(worker, b_g1_aux_source, b_aux_density.clone(), aux_assignment.clone());
 
     let (b_g2_inputs_source, b_g2_aux_source) = params.get_b_g2(b_input_density_total, b_aux_density_total)?;
 
-    let b_g2_inputs = multiexp(&worker, b_g2_inputs_source, b_input_density, input_assignment);
-    let b_g2_aux = multiexp(&worker, b_g2_aux_source, b_aux_density, aux_assignment);
+    let b_g2_inputs = multiexp(worker, b_g2_inputs_source, b_input_density, input_assignment);
+    let b_g2_aux = multiexp(worker, b_g2_aux_source, b_aux_density, aux_assignment);
 
     if vk.delta_g1.is_zero() || vk.delta_g2.is_zero() {
         // If this element is zero, someone is trying to perform a
@@ -0,0 +1,442 @@
+//! Precompiled circuits for repeated proving.
+//!
+//! `create_proof` synthesizes the circuit for every proof: it builds the
+//! linear combinations of every constraint only to evaluate them once, and
+//! tracks the densities of the queries along the way. A `CircuitShape`
+//! does that part once. It holds the constraints as sparse A, B and C
+//! matrices, with the densities, which depend on the constraints alone. It
+//! also knows the size of the evaluation domain, so the buffers of the
+//! evaluations are allocated once at their final size.
+//!
+//! `create_proof_with_shape` then takes an `Assignment` from
+//! `synthesize_assignment`, which calls no `enforce` closure, and only has
+//! to evaluate the matrices at it before the FFTs and multiexponentiations.
+//! One shape serves every proof for the parameters of its circuit.
+
+use rand::Rng;
+
+use std::io;
+use std::sync::Arc;
+
+use pairing::Engine;
+
+use ff::{
+    Field,
+    PrimeField
+};
+
+use super::{
+    Assignment,
+    ParameterSource,
+    Proof
+};
+
+use super::prover::{Synthesized, prove};
+
+use ::{
+    SynthesisError,
+    Circuit,
+    ConstraintSystem,
+    LinearCombination,
+    Variable,
+    Index
+};
+
+use ::domain::Scalar;
+
+use ::multiexp::DensityTracker;
+
+use ::multicore::Worker;
+
+/// Sparse matrix with a row per constraint. Row `i` holds the terms
+/// `terms[starts[i]..starts[i + 1]]`.
+struct Matrix<E: Engine> {
+    starts: Vec<usize>,
+    terms: Vec<(Index, E::Fr)>
+}
+
+impl<E: Engine> Matrix<E> {
+    fn new() -> Self {
+        Matrix {
+            starts: vec![0],
+            terms: vec![]
+        }
+    }
+
+    fn push(&mut self, lc: LinearCombination<E>) {
+        self.terms.extend(lc.0.into_iter().map(|(Variable(index), coeff)| (index, coeff)));
+        self.starts.push(self.terms.len());
+    }
+
+    fn row(&self, i: usize) -> &[(Index, E::Fr)] {
+        &self.terms[self.starts[i]..self.starts[i + 1]]
+    }
+
+    fn eval(&self, i: usize, inputs: &[E::Fr], aux: &[E::Fr]) -> E::Fr {
+        let mut acc = E::Fr::zero();
+
+        for &(index, coeff) in self.row(i) {
+            let mut tmp = match index {
+                Index::Input(i) => inputs[i],
+                Index::Aux(i) => aux[i]
+            };
+
+            if coeff == E::Fr::one() {
+                acc.add_assign(&tmp);
+            } else {
+                tmp.mul_assign(&coeff);
+                acc.add_assign(&tmp);
+            }
+        }
+
+        acc
+    }
+}
+
+/// The constraints of a circuit, without any assignment.
+pub struct CircuitShape<E: Engine> {
+    num_inputs: usize,
+    num_aux: usize,
+    domain_size: usize,
+
+    a: Matrix<E>,
+    b: Matrix<E>,
+    c: Matrix<E>,
+
+    // Density of queries
+    a_aux_density: Arc<DensityTracker>,
+    b_input_density: Arc<DensityTracker>,
+    b_aux_density: Arc<DensityTracker>
+}
+
+struct ShapeAssembly<E: Engine> {
+    num_inputs: usize,
+    num_aux: usize,
+
+    a: Matrix<E>,
+    b: Matrix<E>,
+    c: Matrix<E>,
+
+    a_aux_density: DensityTracker,
+    b_input_density: DensityTracker,
+    b_aux_density: DensityTracker
+}
+
+impl<E: Engine> ConstraintSystem<E> for ShapeAssembly<E> {
+    type Root = Self;
+
+    fn alloc<F, A, AR>(
+        &mut self,
+        _: A,
+        _: F
+    ) -> Result<Variable, SynthesisError>
+        where F: FnOnce() -> Result<E::Fr, SynthesisError>, A: FnOnce() -> AR, AR: Into<String>
+    {
+        // There is no assignment to compute.
+        self.num_aux += 1;
+        self.a_aux_density.add_element();
+        self.b_aux_density.add_element();
+
+        Ok(Variable(Index::Aux(self.num_aux - 1)))
+    }
+
+    fn alloc_input<F, A, AR>(
+        &mut self,
+        _: A,
+        _: F
+    ) -> Result<Variable, SynthesisError>
+        where F: FnOnce() -> Result<E::Fr, SynthesisError>, A: FnOnce() -> AR, AR: Into<String>
+    {
+        self.num_inputs += 1;
+        self.b_input_density.add_element();
+
+        Ok(Variable(Index::Input(self.num_inputs - 1)))
+    }
+
+    fn enforce<A, AR, LA, LB, LC>(
+        &mut self,
+        _: A,
+        a: LA,
+        b: LB,
+        c: LC
+    )
+        where A: FnOnce() -> AR, AR: Into<String>,
+              LA: FnOnce(LinearCombination<E>) -> LinearCombination<E>,
+              LB: FnOnce(LinearCombination<E>) -> LinearCombination<E>,
+              LC: FnOnce(LinearCombination<E>) -> LinearCombination<E>
+    {
+        let a = a(LinearCombination::zero());
+        let b = b(LinearCombination::zero());
+        let c = c(LinearCombination::zero());
+
+        // The densities are tracked like `create_proof` does: inputs have
+        // full density in the A query and there is no C query.
+        for &(Variable(index), _) in a.0.iter() {
+            if let Index::Aux(i) = index {
+                self.a_aux_density.inc(i);
+            }
+        }
+        for &(Variable(index), _) in b.0.iter() {
+            match index {
+                Index::Input(i) => self.b_input_density.inc(i),
+                Index::Aux(i) => self.b_aux_density.inc(i)
+            }
+        }
+
+        self.a.push(a);
+        self.b.push(b);
+        self.c.push(c);
+    }
+
+    fn push_namespace<NR, N>(&mut self, _: N)
+        where NR: Into<String>, N: FnOnce() -> NR
+    {
+        // Do nothing; we don't care about namespaces in this context.
+    }
+
+    fn pop_namespace(&mut self)
+    {
+        // Do nothing; we don't care about namespaces in this context.
+    }
+
+    fn get_root(&mut self) -> &mut Self::Root {
+        self
+    }
+}
+
+impl<E: Engine> CircuitShape<E> {
+    /// Records the constraints of `circuit`, which is synthesized without
+    /// calling any of its value closures, so it may be built without a
+    /// witness like for `generate_random_parameters`.
+    pub fn new<C: Circuit<E>>(circuit: C) -> Result<Self, SynthesisError> {
+        let mut assembly = ShapeAssembly {
+            num_inputs: 0,
+            num_aux: 0,
+            a: Matrix::new(),
+            b: Matrix::new(),
+            c: Matrix::new(),
+            a_aux_density: DensityTracker::new(),
+            b_input_density: DensityTracker::new(),
+            b_aux_density: DensityTracker::new()
+        };
+
+        assembly.alloc_input(|| "", || Ok(E::Fr::one()))?;
+
+        circuit.synthesize(&mut assembly)?;
+
+        for i in 0..assembly.num_inputs {
+            assembly.enforce(|| "",
+                |lc| lc + Variable(Index::Input(i)),
+                |lc| lc,
+                |lc| lc,
+            );
+        }
+
+        let num_constraints = assembly.a.starts.len() - 1;
+        let mut domain_size = 1;
+        let mut exp = 0;
+        while domain_size < num_constraints {
+            domain_size *= 2;
+            exp += 1;
+
+            if exp >= E::Fr::S {
+                return Err(SynthesisError::PolynomialDegreeTooLarge)
+            }
+        }
+
+        Ok(CircuitShape {
+            num_inputs: assembly.num_inputs,
+            num_aux: assembly.num_aux,
+            domain_size: domain_size,
+            a: assembly.a,
+            b: assembly.b,
+            c: assembly.c,
+            a_aux_density: Arc::new(assembly.a_aux_density),
+            b_input_density: Arc::new(assembly.b_input_density),
+            b_aux_density: Arc::new(assembly.b_aux_density)
+        })
+    }
+
+    /// The number of public inputs, without the constant one.
+    pub fn num_inputs(&self) -> usize {
+        self.num_inputs - 1
+    }
+
+    pub fn num_aux(&self) -> usize {
+        self.num_aux
+    }
+
+    /// The number of constraints, including one for each input.
+    pub fn num_constraints(&self) -> usize {
+        self.a.starts.len() - 1
+    }
+
+    /// Evaluates the circuit at `assignment`.
+    fn evaluate(&self, assignment: &Assignment<E>, worker: &Worker) -> Result<Synthesized<E>, SynthesisError> {
+        if assignment.inputs.len() < self.num_inputs() || assignment.aux.len() < self.num_aux {
+            return Err(SynthesisError::AssignmentMissing);
+        }
+        if assignment.inputs.len() > self.num_inputs() || assignment.aux.len() > self.num_aux {
+            return Err(SynthesisError::IoError(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "the assignment has more variables than the circuit"
+            )));
+        }
+
+        let mut inputs = Vec::with_capacity(self.num_inputs);
+        inputs.push(E::Fr::one());
+        inputs.extend_from_slice(&assignment.inputs);
+        let aux = assignment.aux.clone();
+
+        // Room for the padding of the evaluation domain, so it does not
+        // have to grow the vectors.
+        let n = self.num_constraints();
+        let zeros = || {
+            let mut v = Vec::with_capacity(self.domain_size);
+            v.resize(n, Scalar(E::Fr::zero()));
+            v
+        };
+        let (mut a, mut b, mut c) = (zeros(), zeros(), zeros());
+
+        worker.scope(n, |scope, chunk| {
+            let rows = a.chunks_mut(chunk).zip(b.chunks_mut(chunk)).zip(c.chunks_mut(chunk));
+            for (i, ((a, b), c)) in rows.enumerate() {
+                let inputs = &inputs;
+                let aux = &aux;
+                scope.spawn(move || {
+                    for (j, ((a, b), c)) in a.iter_mut().zip(b.iter_mut()).zip(c.iter_mut()).enumerate() {
+                        let row = i * chunk + j;
+                        a.0 = self.a.eval(row, inputs, aux);
+                        b.0 = self.b.eval(row, inputs, aux);
+                        c.0 = self.c.eval(row, inputs, aux);
+                    }
+                });
+            }
+        });
+
+        Ok(Synthesized {
+            a_aux_density: self.a_aux_density.clone(),
+            b_input_density: self.b_input_density.clone(),
+            b_aux_density: self.b_aux_density.clone(),
+            a: a,
+            b: b,
+            c: c,
+            input_assignment: inputs,
+            aux_assignment: aux
+        })
+    }
+}
+
+pub fn create_random_proof_with_shape<E, R, P: ParameterSource<E>>(
+    shape: &CircuitShape<E>,
+    params: P,
+    assignment: &Assignment<E>,
+    rng: &mut R
+) -> Result<Proof<E>, SynthesisError>
+    where E: Engine, R: Rng
+{
+    let r = rng.gen();
+    let s = rng.gen();
+
+    create_proof_with_shape::<E, P>(shape, params, assignment, r, s)
+}
+
+/// Like `create_proof_from_assignment`, for the circuit whose constraints
+/// `shape` recorded.
+pub fn create_proof_with_shape<E, P: ParameterSource<E>>(
+    shape: &CircuitShape<E>,
+    params: P,
+    assignment: &Assignment<E>,
+    r: E::Fr,
+    s: E::Fr
+) -> Result<Proof<E>, SynthesisError>
+    where E: Engine
+{
+    let worker = Worker::new();
+    let prover = shape.evaluate(assignment, &worker)?;
+
+    prove(prover, params, r, s, &worker)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::{
+        create_proof,
+        generate_random_parameters,
+        prepare_verifying_key,
+        synthesize_assignment,
+        verify_proof
+    };
+
+    use pairing::bn256::{Bn256, Fr};
+    use rand::{SeedableRng, XorShiftRng};
+
+    /// Proves knowledge of `x` with `x^3 + 2x = y` for the public `y`.
+    struct Cubic {
+        x: Option<Fr>
+    }
+
+    impl Circuit<Bn256> for Cubic {
+        fn synthesize<CS: ConstraintSystem<Bn256>>(
+            self,
+            cs: &mut CS
+        ) -> Result<(), SynthesisError>
+        {
+            let two = Fr::from_str("2").unwrap();
+            let x_value = self.x;
+            let x2_value = x_value.map(|mut x| { x.square(); x });
+            let y_value = x_value.map(|x| {
+                let mut y = x;
+                y.square();
+                y.mul_assign(&x);
+                let mut x2 = x;
+                x2.mul_assign(&two);
+                y.add_assign(&x2);
+                y
+            });
+
+            let x = cs.alloc(|| "x", || x_value.ok_or(SynthesisError::AssignmentMissing))?;
+            let x2 = cs.alloc(|| "x2", || x2_value.ok_or(SynthesisError::AssignmentMissing))?;
+            let y = cs.alloc_input(|| "y", || y_value.ok_or(SynthesisError::AssignmentMissing))?;
+
+            cs.enforce(|| "x2", |lc| lc + x, |lc| lc + x, |lc| lc + x2);
+            cs.enforce(|| "y", |lc| lc + x2 + (two, CS::one()), |lc| lc + x, |lc| lc + y);
+            // an input in the B query
+            cs.enforce(|| "y again", |lc| lc + CS::one(), |lc| lc + y, |lc| lc + y);
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn proofs_with_shape() {
+        let rng = &mut XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+        let params = generate_random_parameters::<Bn256, _, _>(Cubic { x: None }, rng).unwrap();
+        let pvk = prepare_verifying_key(&params.vk);
+
+        let shape = CircuitShape::new(Cubic { x: None }).unwrap();
+        assert_eq!(shape.num_inputs(), 1);
+        assert_eq!(shape.num_aux(), 2);
+        assert_eq!(shape.num_constraints(), 5);
+
+        // one shape for several proofs, each the same as `create_proof`'s
+        for _ in 0..3 {
+            let x: Fr = rng.gen();
+            let assignment = synthesize_assignment(Cubic { x: Some(x) }).unwrap();
+            let r = rng.gen();
+            let s = rng.gen();
+
+            let proof = create_proof_with_shape(&shape, &params, &assignment, r, s).unwrap();
+            assert!(proof == create_proof(Cubic { x: Some(x) }, &params, r, s).unwrap());
+            assert!(verify_proof(&pvk, &proof, &assignment.inputs).unwrap());
+        }
+
+        let x: Fr = rng.gen();
+        let mut assignment = synthesize_assignment(Cubic { x: Some(x) }).unwrap();
+        assignment.aux.push(x);
+        assert!(create_random_proof_with_shape(&shape, &params, &assignment, rng).is_err());
+        assignment.aux.truncate(1);
+        assert!(create_random_proof_with_shape(&shape, &params, &assignment, rng).is_err());
+    }
+}
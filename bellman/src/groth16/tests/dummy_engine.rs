@@ -1,13 +1,16 @@
-use pairing::{
-    Engine,
+use ff::{
     PrimeField,
     PrimeFieldRepr,
     Field,
     SqrtField,
     LegendreSymbol,
+    PrimeFieldDecodingError,
+    ScalarEngine
+};
+use pairing::{
+    Engine,
     CurveProjective,
     CurveAffine,
-    PrimeFieldDecodingError,
     GroupDecodingError,
     EncodedPoint
 };
@@ -263,8 +266,11 @@ impl PrimeField for Fr {
 #[derive(Clone)]
 pub struct DummyEngine;
 
-impl Engine for DummyEngine {
+impl ScalarEngine for DummyEngine {
     type Fr = Fr;
+}
+
+impl Engine for DummyEngine {
     type G1 = Fr;
     type G1Affine = Fr;
     type G2 = Fr;
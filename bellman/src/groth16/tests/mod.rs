@@ -1,8 +1,8 @@
-use pairing::{
-    Engine,
+use ff::{
     Field,
     PrimeField
 };
+use pairing::Engine;
 
 mod dummy_engine;
 use self::dummy_engine::*;
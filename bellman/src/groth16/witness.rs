@@ -0,0 +1,256 @@
+//! Witness generation apart from proving.
+//!
+//! `create_proof` synthesizes the circuit and then runs the FFTs and
+//! multiexponentiations in one go. `synthesize_assignment` runs only the
+//! first half: it records the value of every variable the circuit
+//! allocates, without evaluating any constraint, into an `Assignment` that
+//! can be written out and proven elsewhere with
+//! `create_proof_from_assignment`. That side still walks the circuit for
+//! its constraints but calls none of the value closures, so it does not need
+//! the witness data the circuit was built from.
+//!
+//! The assignment is not checked against the constraints. An assignment
+//! that does not satisfy them yields a proof that fails to verify.
+
+use pairing::Engine;
+use ff::{PrimeField, PrimeFieldRepr};
+
+use byteorder::{BigEndian, ByteOrder};
+use std::io::{self, Read, Write};
+
+use ::{
+    SynthesisError,
+    Circuit,
+    ConstraintSystem,
+    LinearCombination,
+    Variable,
+    Index
+};
+
+/// The values of the variables of one circuit instance, in allocation order.
+pub struct Assignment<E: Engine> {
+    /// The public inputs, without the constant one every circuit starts
+    /// with, as `verify_proof` takes them.
+    pub inputs: Vec<E::Fr>,
+    /// The auxiliary (private) variables.
+    pub aux: Vec<E::Fr>
+}
+
+impl<E: Engine> PartialEq for Assignment<E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.inputs == other.inputs &&
+        self.aux == other.aux
+    }
+}
+
+impl<E: Engine> Assignment<E> {
+    /// Writes the inputs and then the aux values, each as a big endian `u32`
+    /// count followed by the big endian field elements.
+    pub fn write<W: Write>(
+        &self,
+        mut writer: W
+    ) -> io::Result<()>
+    {
+        write_values::<E, _>(&mut writer, &self.inputs)?;
+        write_values::<E, _>(&mut writer, &self.aux)?;
+
+        Ok(())
+    }
+
+    pub fn read<R: Read>(
+        mut reader: R
+    ) -> io::Result<Self>
+    {
+        let inputs = read_values::<E, _>(&mut reader)?;
+        let aux = read_values::<E, _>(&mut reader)?;
+
+        Ok(Assignment {
+            inputs: inputs,
+            aux: aux
+        })
+    }
+}
+
+fn write_values<E: Engine, W: Write>(mut writer: W, values: &[E::Fr]) -> io::Result<()> {
+    let mut len = [0u8; 4];
+    BigEndian::write_u32(&mut len, values.len() as u32);
+    writer.write_all(&len)?;
+    for v in values {
+        v.into_repr().write_be(&mut writer)?;
+    }
+
+    Ok(())
+}
+
+fn read_values<E: Engine, R: Read>(mut reader: R) -> io::Result<Vec<E::Fr>> {
+    let mut len = [0u8; 4];
+    reader.read_exact(&mut len)?;
+    let len = BigEndian::read_u32(&len) as usize;
+
+    let mut values = vec![];
+    for _ in 0..len {
+        let mut repr = <E::Fr as PrimeField>::Repr::default();
+        repr.read_be(&mut reader)?;
+        values.push(E::Fr::from_repr(repr).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?);
+    }
+
+    Ok(values)
+}
+
+struct WitnessAssembly<E: Engine> {
+    inputs: Vec<E::Fr>,
+    aux: Vec<E::Fr>
+}
+
+impl<E: Engine> ConstraintSystem<E> for WitnessAssembly<E> {
+    type Root = Self;
+
+    fn alloc<F, A, AR>(
+        &mut self,
+        _: A,
+        f: F
+    ) -> Result<Variable, SynthesisError>
+        where F: FnOnce() -> Result<E::Fr, SynthesisError>, A: FnOnce() -> AR, AR: Into<String>
+    {
+        self.aux.push(f()?);
+
+        Ok(Variable(Index::Aux(self.aux.len() - 1)))
+    }
+
+    fn alloc_input<F, A, AR>(
+        &mut self,
+        _: A,
+        f: F
+    ) -> Result<Variable, SynthesisError>
+        where F: FnOnce() -> Result<E::Fr, SynthesisError>, A: FnOnce() -> AR, AR: Into<String>
+    {
+        self.inputs.push(f()?);
+
+        // The constant one is input 0 during proving.
+        Ok(Variable(Index::Input(self.inputs.len())))
+    }
+
+    fn enforce<A, AR, LA, LB, LC>(
+        &mut self,
+        _: A,
+        _: LA,
+        _: LB,
+        _: LC
+    )
+        where A: FnOnce() -> AR, AR: Into<String>,
+              LA: FnOnce(LinearCombination<E>) -> LinearCombination<E>,
+              LB: FnOnce(LinearCombination<E>) -> LinearCombination<E>,
+              LC: FnOnce(LinearCombination<E>) -> LinearCombination<E>
+    {
+        // Constraints play no part in the witness.
+    }
+
+    fn push_namespace<NR, N>(&mut self, _: N)
+        where NR: Into<String>, N: FnOnce() -> NR
+    {
+        // Do nothing; we don't care about namespaces in this context.
+    }
+
+    fn pop_namespace(&mut self)
+    {
+        // Do nothing; we don't care about namespaces in this context.
+    }
+
+    fn get_root(&mut self) -> &mut Self::Root {
+        self
+    }
+}
+
+/// Synthesizes `circuit` for its variable assignment only, to be proven
+/// later with `create_proof_from_assignment`.
+pub fn synthesize_assignment<E, C>(
+    circuit: C
+) -> Result<Assignment<E>, SynthesisError>
+    where E: Engine, C: Circuit<E>
+{
+    let mut assembly = WitnessAssembly {
+        inputs: vec![],
+        aux: vec![]
+    };
+
+    circuit.synthesize(&mut assembly)?;
+
+    Ok(Assignment {
+        inputs: assembly.inputs,
+        aux: assembly.aux
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::{
+        create_proof,
+        create_proof_from_assignment,
+        generate_random_parameters,
+        prepare_verifying_key,
+        verify_proof
+    };
+
+    use ff::Field;
+    use pairing::bn256::{Bn256, Fr};
+    use rand::{Rng, SeedableRng, XorShiftRng};
+
+    struct Cube {
+        x: Option<Fr>
+    }
+
+    impl Circuit<Bn256> for Cube {
+        fn synthesize<CS: ConstraintSystem<Bn256>>(
+            self,
+            cs: &mut CS
+        ) -> Result<(), SynthesisError>
+        {
+            let x_value = self.x;
+            let x2_value = x_value.map(|mut x| { x.square(); x });
+            let x3_value = x_value.and_then(|x| x2_value.map(|mut x2| { x2.mul_assign(&x); x2 }));
+
+            let x = cs.alloc(|| "x", || x_value.ok_or(SynthesisError::AssignmentMissing))?;
+            let x2 = cs.alloc(|| "x2", || x2_value.ok_or(SynthesisError::AssignmentMissing))?;
+            let x3 = cs.alloc_input(|| "x3", || x3_value.ok_or(SynthesisError::AssignmentMissing))?;
+
+            cs.enforce(|| "x2", |lc| lc + x, |lc| lc + x, |lc| lc + x2);
+            cs.enforce(|| "x3", |lc| lc + x2, |lc| lc + x, |lc| lc + x3);
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn proofs_from_assignments() {
+        let rng = &mut XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+        let params = generate_random_parameters::<Bn256, _, _>(Cube { x: None }, rng).unwrap();
+        let pvk = prepare_verifying_key(&params.vk);
+
+        let x: Fr = rng.gen();
+        let assignment = synthesize_assignment(Cube { x: Some(x) }).unwrap();
+        assert_eq!(assignment.inputs.len(), 1);
+        assert_eq!(assignment.aux.len(), 2);
+
+        let mut v = vec![];
+        assignment.write(&mut v).unwrap();
+        assert_eq!(v.len(), 4 + 32 + 4 + 2 * 32);
+        let assignment = Assignment::<Bn256>::read(&v[..]).unwrap();
+        assert!(Assignment::<Bn256>::read(&v[..v.len() - 1]).is_err());
+
+        // the same randomness gives the same proof as proving directly
+        let r = rng.gen();
+        let s = rng.gen();
+        let proof = create_proof_from_assignment(Cube { x: None }, &params, &assignment, r, s).unwrap();
+        assert!(proof == create_proof(Cube { x: Some(x) }, &params, r, s).unwrap());
+        assert!(verify_proof(&pvk, &proof, &assignment.inputs).unwrap());
+
+        // assignments that do not fit the circuit are refused
+        let mut short = Assignment::<Bn256>::read(&v[..]).unwrap();
+        short.aux.pop();
+        assert!(create_proof_from_assignment(Cube { x: None }, &params, &short, r, s).is_err());
+        let mut long = Assignment::<Bn256>::read(&v[..]).unwrap();
+        long.inputs.push(Fr::one());
+        assert!(create_proof_from_assignment(Cube { x: None }, &params, &long, r, s).is_err());
+    }
+}
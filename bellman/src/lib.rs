@@ -1,9 +1,21 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(feature = "std"))]
+#[macro_use]
+extern crate alloc;
+
 extern crate pairing;
 extern crate rand;
+#[cfg(feature = "prover")]
 extern crate futures;
+#[cfg(feature = "prover")]
 extern crate bit_vec;
+#[cfg(feature = "prover")]
+extern crate memmap;
 extern crate byteorder;
 extern crate ff;
+#[cfg(feature = "std")]
+extern crate blake2_rfc;
 
 #[cfg(feature = "multithread")]
 extern crate futures_cpupool;
@@ -12,8 +24,20 @@ extern crate num_cpus;
 #[cfg(feature = "multithread")]
 extern crate crossbeam;
 
+// Without `std`, `::std` paths resolve to `core`, `alloc` and the minimal
+// `io` module of `ff`.
+#[cfg(not(feature = "std"))]
+mod std {
+    pub use core::{cmp, fmt, marker, ops};
+    pub use alloc::{string, vec};
+    pub use ff::io;
+}
+
+#[cfg(feature = "prover")]
 pub mod multicore;
+#[cfg(feature = "prover")]
 mod multiexp;
+#[cfg(feature = "prover")]
 pub mod domain;
 pub mod groth16;
 
@@ -22,9 +46,12 @@ use pairing::{Engine};
 
 use std::ops::{Add, Sub};
 use std::fmt;
+#[cfg(feature = "std")]
 use std::error::Error;
 use std::io;
 use std::marker::PhantomData;
+use std::string::String;
+use std::vec::Vec;
 
 /// Computations are expressed in terms of arithmetic circuits, in particular
 /// rank-1 quadratic constraint systems. The `Circuit` trait represents a
@@ -197,8 +224,8 @@ impl From<io::Error> for SynthesisError {
     }
 }
 
-impl Error for SynthesisError {
-    fn description(&self) -> &str {
+impl SynthesisError {
+    fn message(&self) -> &'static str {
         match *self {
             SynthesisError::AssignmentMissing => "an assignment for a variable could not be computed",
             SynthesisError::DivisionByZero => "division by zero",
@@ -212,13 +239,20 @@ impl Error for SynthesisError {
     }
 }
 
+#[cfg(feature = "std")]
+impl Error for SynthesisError {
+    fn description(&self) -> &str {
+        self.message()
+    }
+}
+
 impl fmt::Display for SynthesisError {
     fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
         if let &SynthesisError::IoError(ref e) = self {
             write!(f, "I/O error: ")?;
             e.fmt(f)
         } else {
-            write!(f, "{}", self.description())
+            write!(f, "{}", self.message())
         }
     }
 }
@@ -282,6 +282,7 @@ fn test_with_bls12() {
     }
 
     use rand::{self, Rand};
+    use pairing::Engine;
     use pairing::bls12_381::Bls12;
 
     const SAMPLES: usize = 1 << 14;
@@ -124,7 +124,8 @@ fn fetch_attr(name: &str, attrs: &[syn::Attribute]) -> Option<String> {
 fn prime_field_repr_impl(repr: &syn::Ident, limbs: usize) -> proc_macro2::TokenStream {
     quote! {
 
-        #[derive(Copy, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+        #[derive(Copy, Clone, PartialEq, Eq, Default)]
+        #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
         pub struct #repr(
             //#[serde(with = "SerHex::<StrictPfx>")]
             pub [u64; #limbs]
@@ -1036,7 +1037,7 @@ fn prime_field_impl(
             }
         }
 
-        impl std::default::Default for #name {
+        impl ::std::default::Default for #name {
             fn default() -> Self {
                 Self::zero()
             }
@@ -1074,12 +1075,14 @@ fn prime_field_impl(
                 self.reduce();
             }
 
+            #[cfg(feature = "std")]
             pub fn to_hex(&self) -> String {
                 let mut buf: Vec<u8> = vec![];
                 self.into_repr().write_be(&mut buf).unwrap();
                 hex::encode(&buf)
             }
 
+            #[cfg(feature = "std")]
             pub fn from_hex(value: &str) -> Result<#name, String> {
                 let value = if value.starts_with("0x") { &value[2..] } else { value };
                 if value.len() % 2 != 0 {return Err(format!("hex length must be even for full byte encoding: {}", value))}
@@ -1092,6 +1095,7 @@ fn prime_field_impl(
             }
         }
 
+        #[cfg(feature = "std")]
         impl serde::Serialize for #name {
             fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
             where
@@ -1101,12 +1105,16 @@ fn prime_field_impl(
             }
         }
 
+        #[cfg(feature = "std")]
         use std::fmt;
 
+        #[cfg(feature = "std")]
         use serde::de::{self, Visitor};
 
+        #[cfg(feature = "std")]
         struct FrVisitor;
 
+        #[cfg(feature = "std")]
         impl<'de> Visitor<'de> for FrVisitor {
             type Value = #name;
 
@@ -1122,6 +1130,7 @@ fn prime_field_impl(
             }
         }
 
+        #[cfg(feature = "std")]
         impl<'de> serde::Deserialize<'de> for #name {
             fn deserialize<D>(deserializer: D) -> Result<#name, D::Error>
             where
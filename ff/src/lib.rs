@@ -1,4 +1,9 @@
 #![allow(unused_imports)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(feature = "std"))]
+#[macro_use]
+extern crate alloc;
 
 extern crate byteorder;
 extern crate rand;
@@ -13,9 +18,24 @@ pub use ff_derive::*;
 // #[cfg(feature = "asm")]
 // pub use ff_asm::*;
 
+// Without `std`, the paths below resolve to `core` and `alloc`.
+#[cfg(not(feature = "std"))]
+mod std {
+    pub use core::fmt;
+    pub use alloc::string;
+}
+
+/// `std::io`, or a minimal replacement of it without `std`.
+#[cfg(feature = "std")]
+pub use std::io;
+#[cfg(not(feature = "std"))]
+pub mod io;
+
+#[cfg(feature = "std")]
 use std::error::Error;
 use std::fmt;
-use std::io::{self, Read, Write};
+use std::string::String;
+use io::{Read, Write};
 
 /// This trait represents an element of a field.
 pub trait Field:
@@ -143,10 +163,12 @@ pub trait PrimeFieldRepr:
 
     /// Writes this `PrimeFieldRepr` as a big endian integer.
     fn write_be<W: Write>(&self, mut writer: W) -> io::Result<()> {
-        use byteorder::{BigEndian, WriteBytesExt};
+        use byteorder::{BigEndian, ByteOrder};
 
+        let mut buf = [0u8; 8];
         for digit in self.as_ref().iter().rev() {
-            writer.write_u64::<BigEndian>(*digit)?;
+            BigEndian::write_u64(&mut buf, *digit);
+            writer.write_all(&buf)?;
         }
 
         Ok(())
@@ -154,10 +176,12 @@ pub trait PrimeFieldRepr:
 
     /// Reads a big endian integer into this representation.
     fn read_be<R: Read>(&mut self, mut reader: R) -> io::Result<()> {
-        use byteorder::{BigEndian, ReadBytesExt};
+        use byteorder::{BigEndian, ByteOrder};
 
+        let mut buf = [0u8; 8];
         for digit in self.as_mut().iter_mut().rev() {
-            *digit = reader.read_u64::<BigEndian>()?;
+            reader.read_exact(&mut buf)?;
+            *digit = BigEndian::read_u64(&buf);
         }
 
         Ok(())
@@ -165,10 +189,12 @@ pub trait PrimeFieldRepr:
 
     /// Writes this `PrimeFieldRepr` as a little endian integer.
     fn write_le<W: Write>(&self, mut writer: W) -> io::Result<()> {
-        use byteorder::{LittleEndian, WriteBytesExt};
+        use byteorder::{LittleEndian, ByteOrder};
 
+        let mut buf = [0u8; 8];
         for digit in self.as_ref().iter() {
-            writer.write_u64::<LittleEndian>(*digit)?;
+            LittleEndian::write_u64(&mut buf, *digit);
+            writer.write_all(&buf)?;
         }
 
         Ok(())
@@ -176,10 +202,12 @@ pub trait PrimeFieldRepr:
 
     /// Reads a little endian integer into this representation.
     fn read_le<R: Read>(&mut self, mut reader: R) -> io::Result<()> {
-        use byteorder::{LittleEndian, ReadBytesExt};
+        use byteorder::{LittleEndian, ByteOrder};
 
+        let mut buf = [0u8; 8];
         for digit in self.as_mut().iter_mut() {
-            *digit = reader.read_u64::<LittleEndian>()?;
+            reader.read_exact(&mut buf)?;
+            *digit = LittleEndian::read_u64(&buf);
         }
 
         Ok(())
@@ -201,6 +229,7 @@ pub enum PrimeFieldDecodingError {
     NotInField(String),
 }
 
+#[cfg(feature = "std")]
 impl Error for PrimeFieldDecodingError {
     fn description(&self) -> &str {
         match *self {
@@ -0,0 +1,349 @@
+#ifndef SPACEPROOF_H
+#define SPACEPROOF_H
+
+/* Generated with cbindgen from src/ffi.rs, do not edit. */
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+/**
+ * Size in bytes of a serialized proof.
+ */
+#define SPACEPROOF_PROOF_SIZE 128
+
+/**
+ * Size in bytes of a field element.
+ */
+#define SPACEPROOF_FIELD_SIZE 32
+
+/**
+ * Outcome of a call.
+ */
+typedef enum SpaceproofStatus {
+  SPACEPROOF_STATUS_OK = 0,
+  /**
+   * A pointer was null, a buffer malformed or a value out of range
+   */
+  SPACEPROOF_STATUS_INVALID_INPUT = 1,
+  /**
+   * Parameters, keys or proofs could not be decoded
+   */
+  SPACEPROOF_STATUS_IO = 2,
+  /**
+   * Parameter generation or proving failed
+   */
+  SPACEPROOF_STATUS_SYNTHESIS = 3,
+  /**
+   * The library panicked; this is a bug
+   */
+  SPACEPROOF_STATUS_PANIC = 4,
+} SpaceproofStatus;
+
+/**
+ * Groth16 parameters, of the space proof or of the Blake tree circuit.
+ */
+typedef struct SpaceproofParams SpaceproofParams;
+
+/**
+ * Groth16 proof.
+ */
+typedef struct SpaceproofProof SpaceproofProof;
+
+/**
+ * Pedersen Merkle tree committing to leaves.
+ */
+typedef struct SpaceproofTree SpaceproofTree;
+
+/**
+ * Prepared verifying key.
+ */
+typedef struct SpaceproofVerifyingKey SpaceproofVerifyingKey;
+
+/**
+ * Bytes owned by the library, released with `spaceproof_buffer_free`.
+ */
+typedef struct SpaceproofBuffer {
+  uint8_t *data;
+  size_t len;
+} SpaceproofBuffer;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Message of the last failed call on this thread, or null. The string is
+ * valid until the next failing call on the same thread.
+ */
+const char *spaceproof_last_error(void);
+
+/**
+ * Releases bytes the library returned in a `SpaceproofBuffer`.
+ *
+ * # Safety
+ *
+ * `buffer` must have been filled in by this library, with `data` and `len`
+ * unchanged, and not be freed again. A buffer with null `data` is ignored.
+ */
+void spaceproof_buffer_free(SpaceproofBuffer buffer);
+
+/**
+ * Generates parameters for space proofs in trees of height `depth`.
+ *
+ * # Safety
+ *
+ * `seed` must point to `seed_len` readable `u32`s, or be null if
+ * `seed_len` is zero. `out` must be valid for a write; the handle stored
+ * there belongs to the caller, who releases it with
+ * `spaceproof_params_free`.
+ */
+SpaceproofStatus spaceproof_params_generate(const uint32_t *seed,
+                                            size_t seed_len,
+                                            uint32_t depth,
+                                            SpaceproofParams **out);
+
+/**
+ * Generates parameters for Blake tree proofs of paths of `depth` nodes.
+ *
+ * # Safety
+ *
+ * As for `spaceproof_params_generate`.
+ */
+SpaceproofStatus spaceproof_blake_params_generate(const uint32_t *seed,
+                                                  size_t seed_len,
+                                                  uint32_t depth,
+                                                  SpaceproofParams **out);
+
+/**
+ * Reads parameters written by `spaceproof_params_write`.
+ *
+ * # Safety
+ *
+ * `data` must point to `len` readable bytes, or be null if `len` is zero;
+ * they are not used after the call. `out` must be valid for a write; the
+ * handle stored there belongs to the caller, who releases it with
+ * `spaceproof_params_free`.
+ */
+SpaceproofStatus spaceproof_params_read(const uint8_t *data, size_t len, SpaceproofParams **out);
+
+/**
+ * Writes `params` as `spaceproof_params_read` reads them.
+ *
+ * # Safety
+ *
+ * `params` must be a handle from this library that has not been freed.
+ * `out` must be valid for a write; the buffer stored there belongs to the
+ * caller, who releases it with `spaceproof_buffer_free`.
+ */
+SpaceproofStatus spaceproof_params_write(const SpaceproofParams *params, SpaceproofBuffer *out);
+
+/**
+ * Writes the verifying key of `params`, readable with
+ * `spaceproof_verifying_key_read`.
+ *
+ * # Safety
+ *
+ * As for `spaceproof_params_write`.
+ */
+SpaceproofStatus spaceproof_params_write_verifying_key(const SpaceproofParams *params,
+                                                       SpaceproofBuffer *out);
+
+/**
+ * Releases parameters.
+ *
+ * # Safety
+ *
+ * `params` must be null or a handle from this library, which must not be
+ * used or freed again afterwards.
+ */
+void spaceproof_params_free(SpaceproofParams *params);
+
+/**
+ * Reads a verifying key as written by `VerifyingKey::write`. Parameters
+ * start with their verifying key, so parameter files are accepted too.
+ *
+ * # Safety
+ *
+ * `data` must point to `len` readable bytes, or be null if `len` is zero;
+ * they are not used after the call. `out` must be valid for a write; the
+ * handle stored there belongs to the caller, who releases it with
+ * `spaceproof_verifying_key_free`.
+ */
+SpaceproofStatus spaceproof_verifying_key_read(const uint8_t *data,
+                                               size_t len,
+                                               SpaceproofVerifyingKey **out);
+
+/**
+ * Reads a verifying key as written by `PreparedVerifyingKey::write`.
+ *
+ * # Safety
+ *
+ * As for `spaceproof_verifying_key_read`.
+ */
+SpaceproofStatus spaceproof_prepared_verifying_key_read(const uint8_t *data,
+                                                        size_t len,
+                                                        SpaceproofVerifyingKey **out);
+
+/**
+ * Releases a verifying key.
+ *
+ * # Safety
+ *
+ * `vk` must be null or a handle from this library, which must not be used
+ * or freed again afterwards.
+ */
+void spaceproof_verifying_key_free(SpaceproofVerifyingKey *vk);
+
+/**
+ * Builds the tree committing to `count` leaves of `SPACEPROOF_FIELD_SIZE`
+ * bytes each.
+ *
+ * # Safety
+ *
+ * `leaves` must point to `count * SPACEPROOF_FIELD_SIZE` readable bytes, or
+ * be null if `count` is zero. `out` must be valid for a write; the handle
+ * stored there belongs to the caller, who releases it with
+ * `spaceproof_tree_free`.
+ */
+SpaceproofStatus spaceproof_tree_new(const uint8_t *leaves, size_t count, SpaceproofTree **out);
+
+/**
+ * Writes the root of `tree` to the `SPACEPROOF_FIELD_SIZE` bytes at `root`.
+ *
+ * # Safety
+ *
+ * `tree` must be a handle from `spaceproof_tree_new` that has not been
+ * freed, and `root` must be valid for writes of `SPACEPROOF_FIELD_SIZE`
+ * bytes.
+ */
+SpaceproofStatus spaceproof_tree_root(const SpaceproofTree *tree, uint8_t *root);
+
+/**
+ * Releases a tree.
+ *
+ * # Safety
+ *
+ * `tree` must be null or a handle from `spaceproof_tree_new`, which must
+ * not be used or freed again afterwards.
+ */
+void spaceproof_tree_free(SpaceproofTree *tree);
+
+/**
+ * Proves that the leaf at `position` is in `tree`.
+ *
+ * # Safety
+ *
+ * `seed` must point to `seed_len` readable `u32`s, or be null if
+ * `seed_len` is zero. `params` and `tree` must be handles from this library
+ * that have not been freed; they are only read. `out` must be valid for a
+ * write; the proof stored there belongs to the caller, who releases it with
+ * `spaceproof_proof_free`.
+ */
+SpaceproofStatus spaceproof_prove(const uint32_t *seed,
+                                  size_t seed_len,
+                                  const SpaceproofParams *params,
+                                  const SpaceproofTree *tree,
+                                  uint64_t position,
+                                  SpaceproofProof **out);
+
+/**
+ * Sets `valid` to whether `proof` shows that `leaf` sits at `position` in
+ * the tree with `root`.
+ *
+ * # Safety
+ *
+ * `vk` and `proof` must be handles from this library that have not been
+ * freed. `leaf` and `root` must each point to `SPACEPROOF_FIELD_SIZE`
+ * readable bytes, and `valid` must be valid for a write.
+ */
+SpaceproofStatus spaceproof_verify(const SpaceproofVerifyingKey *vk,
+                                   const SpaceproofProof *proof,
+                                   const uint8_t *leaf,
+                                   uint64_t position,
+                                   const uint8_t *root,
+                                   bool *valid);
+
+/**
+ * Proves knowledge of `secret` for `nullifier` in a Blake tree. The path
+ * has `depth` nodes from the leaf up: `sides[i]` is non-zero when the path
+ * so far is the left child and `hashes` holds the 32 byte siblings. The
+ * public inputs of the proof are written to `nullifier_out` and `root_out`,
+ * `SPACEPROOF_FIELD_SIZE` bytes each.
+ *
+ * # Safety
+ *
+ * `seed` must point to `seed_len` readable `u32`s, or be null if
+ * `seed_len` is zero, and `params` must be a handle from this library that
+ * has not been freed. `nullifier` and `secret` must each point to 32
+ * readable bytes, `sides` to `depth` and `hashes` to `depth * 32`.
+ * `nullifier_out` and `root_out` must each be valid for writes of
+ * `SPACEPROOF_FIELD_SIZE` bytes. `out` must be valid for a write; the proof
+ * stored there belongs to the caller, who releases it with
+ * `spaceproof_proof_free`.
+ */
+SpaceproofStatus spaceproof_blake_prove(const uint32_t *seed,
+                                        size_t seed_len,
+                                        const SpaceproofParams *params,
+                                        const uint8_t *nullifier,
+                                        const uint8_t *secret,
+                                        const uint8_t *sides,
+                                        const uint8_t *hashes,
+                                        size_t depth,
+                                        SpaceproofProof **out,
+                                        uint8_t *nullifier_out,
+                                        uint8_t *root_out);
+
+/**
+ * Sets `valid` to whether `proof` is a Blake tree proof for the public
+ * inputs `nullifier` and `root`, as written by `spaceproof_blake_prove`.
+ *
+ * # Safety
+ *
+ * `vk` and `proof` must be handles from this library that have not been
+ * freed. `nullifier` and `root` must each point to `SPACEPROOF_FIELD_SIZE`
+ * readable bytes, and `valid` must be valid for a write.
+ */
+SpaceproofStatus spaceproof_blake_verify(const SpaceproofVerifyingKey *vk,
+                                         const SpaceproofProof *proof,
+                                         const uint8_t *nullifier,
+                                         const uint8_t *root,
+                                         bool *valid);
+
+/**
+ * Reads a proof of `SPACEPROOF_PROOF_SIZE` bytes.
+ *
+ * # Safety
+ *
+ * `data` must point to `len` readable bytes, or be null if `len` is zero;
+ * they are not used after the call. `out` must be valid for a write; the
+ * proof stored there belongs to the caller, who releases it with
+ * `spaceproof_proof_free`.
+ */
+SpaceproofStatus spaceproof_proof_read(const uint8_t *data, size_t len, SpaceproofProof **out);
+
+/**
+ * Writes `proof` to the `SPACEPROOF_PROOF_SIZE` bytes at `out`.
+ *
+ * # Safety
+ *
+ * `proof` must be a handle from this library that has not been freed, and
+ * `out` must be valid for writes of `SPACEPROOF_PROOF_SIZE` bytes.
+ */
+SpaceproofStatus spaceproof_proof_write(const SpaceproofProof *proof, uint8_t *out);
+
+/**
+ * Releases a proof.
+ *
+ * # Safety
+ *
+ * `proof` must be null or a handle from this library, which must not be
+ * used or freed again afterwards.
+ */
+void spaceproof_proof_free(SpaceproofProof *proof);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif // __cplusplus
+
+#endif /* SPACEPROOF_H */
@@ -630,6 +630,7 @@ pub mod g1 {
     use ff::{BitIterator, Field, PrimeField, PrimeFieldRepr, SqrtField};
     use rand::{Rand, Rng};
     use std::fmt;
+    use std::vec::Vec;
     use {CurveAffine, CurveProjective, EncodedPoint, Engine, GroupDecodingError};
 
     curve_impl!(
@@ -1009,6 +1010,7 @@ pub mod g2 {
     use ff::{BitIterator, Field, PrimeField, PrimeFieldRepr, SqrtField};
     use rand::{Rand, Rng};
     use std::fmt;
+    use std::vec::Vec;
     use {CurveAffine, CurveProjective, EncodedPoint, Engine, GroupDecodingError};
 
     curve_impl!(
@@ -1023,21 +1025,43 @@ pub mod g2 {
         G1Affine
     );
 
+    impl G2Affine {
+        fn scale_by_cofactor(&self) -> G2 {
+            // G2 cofactor = 2p - r
+            // 0x30644e72e131a029b85045b68181585e06ceecda572a2489345f2299c0f9fa8d
+            let cofactor = BitIterator::new([
+                0x345f2299c0f9fa8d,
+                0x06ceecda572a2489,
+                0xb85045b68181585e,
+                0x30644e72e131a029,
+            ]);
+            self.mul_bits(cofactor)
+        }
+    }
+
+    // Points of the twist are not in the prime order subgroup in general, so
+    // a random x-coordinate is followed by clearing the cofactor. Unlike a
+    // random multiple of the generator, nobody learns the discrete logarithm.
     impl Rand for G2 {
         fn rand<R: Rng>(rng: &mut R) -> Self {
-            let mut r = G2::one();
-            let k = Fr::rand(rng);
-            r.mul_assign(k);
-            return r;
+            loop {
+                let x = rng.gen();
+                let greatest = rng.gen();
+
+                if let Some(p) = G2Affine::get_point_from_x(x, greatest) {
+                    let p = p.scale_by_cofactor();
+
+                    if !p.is_zero() {
+                        return p;
+                    }
+                }
+            }
         }
     }
 
     impl Rand for G2Affine {
         fn rand<R: Rng>(rng: &mut R) -> Self {
-            let mut r = G2::one();
-            let k = Fr::rand(rng);
-            r.mul_assign(k);
-            return r.into_affine();
+            G2::rand(rng).into_affine()
         }
     }
 
@@ -1411,6 +1435,18 @@ pub mod g2 {
         panic!();
     }
 
+    #[test]
+    fn g2_random_points_in_subgroup() {
+        let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        for _ in 0..10 {
+            let p = G2Affine::rand(&mut rng);
+            assert!(!p.is_zero());
+            assert!(p.is_on_curve());
+            assert!(p.mul(Fr::char()).is_zero());
+        }
+    }
+
     #[test]
     fn g2_curve_tests() {
         ::tests::curve::curve_tests::<G2>();
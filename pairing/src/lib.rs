@@ -10,16 +10,32 @@
 // #![cfg_attr(feature = "cargo-clippy", allow(write_literal))]
 // Force public structures to implement Debug
 #![deny(missing_debug_implementations)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(feature = "std"))]
+#[macro_use]
+extern crate alloc;
 
 extern crate byteorder;
 extern crate ff;
 extern crate rand;
 
+#[cfg(feature = "std")]
 extern crate hex;
+#[cfg(feature = "std")]
 extern crate serde;
+#[cfg(feature = "std")]
 #[macro_use]
 extern crate serde_derive;
 
+// Without `std`, `::std` paths, including those emitted by
+// `#[derive(PrimeField)]`, resolve to `core` and `alloc`.
+#[cfg(not(feature = "std"))]
+mod std {
+    pub use core::{clone, cmp, default, fmt, marker, mem};
+    pub use alloc::vec;
+}
+
 #[cfg(test)]
 pub mod tests;
 
@@ -30,6 +46,7 @@ mod wnaf;
 pub use self::wnaf::Wnaf;
 
 use ff::{Field, PrimeField, PrimeFieldDecodingError, PrimeFieldRepr, ScalarEngine, SqrtField};
+#[cfg(feature = "std")]
 use std::error::Error;
 use std::fmt;
 
@@ -280,8 +297,8 @@ pub enum GroupDecodingError {
     UnexpectedInformation,
 }
 
-impl Error for GroupDecodingError {
-    fn description(&self) -> &str {
+impl GroupDecodingError {
+    fn message(&self) -> &'static str {
         match *self {
             GroupDecodingError::NotOnCurve => "coordinate(s) do not lie on the curve",
             GroupDecodingError::NotInSubgroup => "the element is not part of an r-order subgroup",
@@ -294,13 +311,20 @@ impl Error for GroupDecodingError {
     }
 }
 
+#[cfg(feature = "std")]
+impl Error for GroupDecodingError {
+    fn description(&self) -> &str {
+        self.message()
+    }
+}
+
 impl fmt::Display for GroupDecodingError {
     fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
         match *self {
             GroupDecodingError::CoordinateDecodingError(description, ref err) => {
                 write!(f, "{} decoding error: {}", description, err)
             }
-            _ => write!(f, "{}", self.to_string()),
+            _ => write!(f, "{}", self.message()),
         }
     }
 }
@@ -0,0 +1,98 @@
+//! JSON-RPC proving service, see `rust_miximus::rpc`.
+//!
+//! ```text
+//! spaceproof-server --params PARAMS [--depth N] (--tcp ADDR | --unix PATH) [--workers N] [--plot-dir DIR]
+//! ```
+//!
+//! `PARAMS` are read once at start-up, as written by `spaceproof setup`, and
+//! only trees of their height are proven. The height is taken from the
+//! parameter file header; bare parameters need `--depth`.
+//! Requests may only name plots in `DIR`, and none without `--plot-dir`.
+//! Requests are JSON-RPC 2.0 objects, one per line. Built with the `server`
+//! feature: `cargo build --release --features server`.
+
+extern crate rust_miximus;
+
+use std::collections::HashMap;
+use std::env;
+use std::fs::File;
+use std::io::BufReader;
+use std::net::TcpListener;
+use std::process;
+use std::sync::Arc;
+
+use rust_miximus::params::read_parameters;
+use rust_miximus::rpc::server::Server;
+
+const USAGE: &'static str = "usage:
+    spaceproof-server --params PARAMS [--depth N] (--tcp ADDR | --unix PATH) [--workers N] [--plot-dir DIR]";
+
+fn parse(args: &[String]) -> Result<HashMap<String, String>, String> {
+    let mut options = HashMap::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if !arg.starts_with("--") {
+            return Err(format!("unexpected argument {}", arg));
+        }
+        let value = iter.next().ok_or(format!("missing value for {}", arg))?;
+        options.insert(arg[2..].to_string(), value.clone());
+    }
+    Ok(options)
+}
+
+fn run(options: &HashMap<String, String>) -> Result<(), String> {
+    let params_path = options.get("params").ok_or("missing --params".to_string())?;
+    let workers = match options.get("workers") {
+        Some(w) => w.parse().map_err(|_| "--workers must be a number".to_string())?,
+        None => 1,
+    };
+
+    let file = File::open(params_path).map_err(|e| format!("could not open {}: {}", params_path, e))?;
+    let (header, params) = read_parameters(BufReader::new(file), false)
+        .map_err(|e| format!("could not read parameters {}: {}", params_path, e))?;
+    let depth = match (header, options.get("depth")) {
+        (Some(header), None) => header.id.depth,
+        (Some(header), Some(depth)) => {
+            if depth.parse::<usize>() != Ok(header.id.depth) {
+                return Err(format!("{} are parameters for {}", params_path, header.id));
+            }
+            header.id.depth
+        }
+        (None, Some(depth)) => depth.parse().map_err(|_| "--depth must be a number".to_string())?,
+        (None, None) => return Err(format!("{} has no header, --depth is required", params_path)),
+    };
+    let mut server = Server::new(params, depth, workers).map_err(|e| e.to_string())?;
+    if let Some(dir) = options.get("plot-dir") {
+        server = server.with_plot_dir(dir).map_err(|e| format!("could not open {}: {}", dir, e))?;
+    }
+    let server = Arc::new(server);
+
+    match (options.get("tcp"), options.get("unix")) {
+        (Some(addr), None) => {
+            let listener = TcpListener::bind(addr).map_err(|e| format!("could not listen on {}: {}", addr, e))?;
+            eprintln!("listening on {}", addr);
+            server.serve(listener).map_err(|e| e.to_string())
+        }
+        #[cfg(unix)]
+        (None, Some(path)) => {
+            let listener = ::std::os::unix::net::UnixListener::bind(path)
+                .map_err(|e| format!("could not listen on {}: {}", path, e))?;
+            eprintln!("listening on {}", path);
+            server.serve_unix(listener).map_err(|e| e.to_string())
+        }
+        _ => Err(format!("exactly one of --tcp and --unix is required\n{}", USAGE)),
+    }
+}
+
+fn main() {
+    let argv: Vec<String> = env::args().skip(1).collect();
+    if argv.is_empty() || argv[0] == "--help" {
+        println!("{}", USAGE);
+        return;
+    }
+
+    if let Err(e) = parse(&argv).and_then(|options| run(&options)) {
+        eprintln!("error: {}", e);
+        process::exit(2);
+    }
+}
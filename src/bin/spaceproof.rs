@@ -0,0 +1,728 @@
+//! Command-line front end for Merkle space proofs.
+//!
+//! ```text
+//! spaceproof setup  --depth N --out PARAMS [--seed A,B,C,D] [--compressed true]
+//! spaceproof plot   --height N --out PLOT [--id HEX] [--checkpoint-interval N]
+//! spaceproof commit --plot PLOT
+//! spaceproof witness --plot PLOT --position N --out WITNESS
+//! spaceproof prove  (--params PARAMS | --bundle BUNDLE) (--plot PLOT --position N | --witness WITNESS) --out PROOF [--seed A,B,C,D]
+//! spaceproof export-vk --params PARAMS --out VK [--prepared true | --compressed true]
+//! spaceproof verify (--params PARAMS | --vk VK | --pvk PVK | --bundle BUNDLE --depth N) --proof PROOF --root HEX
+//! spaceproof tau-new --exp N --out TAU
+//! spaceproof tau-contribute --in TAU --out TAU
+//! spaceproof tau-beacon --in TAU --out TAU --beacon HEX --iterations-exp N
+//! spaceproof tau-verify --before TAU --after TAU [--beacon HEX --iterations-exp N]
+//! spaceproof ceremony-new --depth N --out CEREMONY [--phase1 TAU]
+//! spaceproof ceremony-contribute --in CEREMONY --out CEREMONY
+//! spaceproof ceremony-verify (--initial CEREMONY | --phase1 TAU) --in CEREMONY --depth N [--out PARAMS [--compressed true]]
+//! spaceproof bundle-new --params PARAMS,PARAMS,... --out BUNDLE
+//! spaceproof bundle-vk --in BUNDLE --out BUNDLE [--compressed true]
+//! spaceproof bundle-list --in BUNDLE
+//! ```
+//!
+//! `verify` checks the proof against the `--root` the verifier committed to,
+//! never against the root the proof file claims. It exits with status 1 when
+//! the proof is rejected; any other failure exits with status 2.
+//!
+//! ## File formats
+//!
+//! * `PARAMS` are Groth16 parameters of the `SpaceProofMerkleTreeCircuit` for
+//!   plots of height `depth` in a `params::ParameterFile`, whose header
+//!   records the circuit, the depth and the ceremony transcript, and whose
+//!   digest is checked by every command reading it. `prove` refuses
+//!   parameters of another depth than the plot's. The parameters inside
+//!   are written by `bellman::groth16::Parameters::write`, or with
+//!   `--compressed true` by `Parameters::write_encoded` with compressed
+//!   points, about half the size but slower to load and to prove from.
+//!   `verify` only reads the verifying key at the start of the parameters,
+//!   and `prove` maps them into memory instead of loading the proving key.
+//!   Bare parameters without the header are read as well.
+//! * `VK` is the verifying key alone, as written by `VerifyingKey::write`,
+//!   or `VerifyingKey::write_encoded` with `--compressed true`, and `PVK`
+//!   the part of it needed for verification, as written by
+//!   `PreparedVerifyingKey::write`. Either is all a verifier needs; neither
+//!   contains proving key material.
+//! * `CEREMONY` are parameters with the transcript of a parameter ceremony,
+//!   see `mpc`. `ceremony-contribute` and `ceremony-verify` print the
+//!   contribution hashes as hex. `ceremony-verify` checks the ceremony for
+//!   trees of height `--depth` and `--out` writes the checked parameters as
+//!   `PARAMS`. With `--phase1`, the initial parameters are derived from a
+//!   finished powers-of-tau ceremony instead of read from `--initial`.
+//! * `BUNDLE` holds the parameters of several depths, see `params::bundle`.
+//!   `bundle-new` collects `PARAMS` files into one, `bundle-vk` writes the
+//!   companion bundle with only their verifying keys, and `bundle-list`
+//!   prints the circuit, depth and ceremony transcript hash of every entry.
+//!   `prove --bundle` picks the parameters for the height of the plot;
+//!   `verify --bundle` takes either kind of bundle and needs the `--depth`.
+//! * `TAU` is a powers-of-tau accumulator for circuits of up to `2^exp`
+//!   constraints, see `powersoftau`. `tau-contribute` and `tau-beacon`
+//!   append the public key of the contribution, which `tau-verify` checks,
+//!   and print the hash of the new accumulator.
+//! * `PLOT` is a Merkle plot, see `plot::merkle` for its layout. While
+//!   plotting, progress is checkpointed to `PLOT.ckpt`; running the same
+//!   `plot` command again resumes from it.
+//! * `WITNESS` is the assignment of the circuit variables for one position of
+//!   a plot, so that `prove --witness` can run on a machine without the plot.
+//!   It starts with the magic `SPWITNS1` and the height of the plot as a
+//!   big endian `u32`, followed by the assignment as written by
+//!   `bellman::groth16::Assignment::write`, whose public inputs are the
+//!   leaf, the position and the root.
+//! * `PROOF` holds the public inputs followed by the proof. Integers are
+//!   big endian, field elements are 32 bytes little endian.
+//!
+//! ```text
+//! magic        8 bytes   "SPPROOF1"
+//! position     u64
+//! leaf         32 bytes
+//! root         32 bytes
+//! proof        128 bytes compressed A (G1), B (G2) and C (G1)
+//! ```
+//!
+//! Roots are printed and parsed as big endian hex, like `Fr::to_hex`.
+//!
+//! `setup` and `prove` without `--seed` draw their randomness from the
+//! operating system; a fixed seed makes parameters and proofs reproducible,
+//! and parameters forgeable, so only use one for testing.
+
+extern crate rust_miximus;
+extern crate bellman;
+extern crate pairing;
+extern crate ff;
+extern crate rand;
+extern crate byteorder;
+extern crate hex;
+
+use std::collections::HashMap;
+use std::env;
+use std::fs::File;
+use std::io::{self, Read, Write, BufReader, BufWriter, Seek, SeekFrom};
+use std::path::PathBuf;
+use std::process;
+
+use bellman::groth16::{Assignment, Encoding, Proof, MappedParameters, VerifyingKey, PreparedVerifyingKey, prepare_verifying_key};
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use ff::{PrimeField, PrimeFieldRepr};
+use pairing::bn256::{Bn256, Fr, FrRepr};
+use rand::{OsRng, Rng};
+
+use rust_miximus::mpc::MPCParameters;
+use rust_miximus::params::{self, CircuitId, Header, ParameterFile, MERKLE_TREE_CIRCUIT};
+use rust_miximus::params::bundle::{Bundle, BundleKind, BundleWriter};
+use rust_miximus::powersoftau::{Accumulator, PublicKey};
+use rust_miximus::plot::builder::PlotBuilder;
+use rust_miximus::plot::merkle::MerklePlot;
+
+const PROOF_MAGIC: &'static [u8; 8] = b"SPPROOF1";
+const WITNESS_MAGIC: &'static [u8; 8] = b"SPWITNS1";
+
+/// Units of plotting work between two checkpoints.
+const DEFAULT_CHECKPOINT_INTERVAL: usize = 4096;
+
+const USAGE: &'static str = "usage:
+    spaceproof setup  --depth N --out PARAMS [--seed A,B,C,D] [--compressed true]
+    spaceproof plot   --height N --out PLOT [--id HEX] [--checkpoint-interval N]
+    spaceproof commit --plot PLOT
+    spaceproof witness --plot PLOT --position N --out WITNESS
+    spaceproof prove  (--params PARAMS | --bundle BUNDLE) (--plot PLOT --position N | --witness WITNESS) --out PROOF [--seed A,B,C,D]
+    spaceproof export-vk --params PARAMS --out VK [--prepared true | --compressed true]
+    spaceproof verify (--params PARAMS | --vk VK | --pvk PVK | --bundle BUNDLE --depth N) --proof PROOF --root HEX
+    spaceproof tau-new --exp N --out TAU
+    spaceproof tau-contribute --in TAU --out TAU
+    spaceproof tau-beacon --in TAU --out TAU --beacon HEX --iterations-exp N
+    spaceproof tau-verify --before TAU --after TAU [--beacon HEX --iterations-exp N]
+    spaceproof ceremony-new --depth N --out CEREMONY [--phase1 TAU]
+    spaceproof ceremony-contribute --in CEREMONY --out CEREMONY
+    spaceproof ceremony-verify (--initial CEREMONY | --phase1 TAU) --in CEREMONY --depth N [--out PARAMS [--compressed true]]
+    spaceproof bundle-new --params PARAMS,PARAMS,... --out BUNDLE
+    spaceproof bundle-vk --in BUNDLE --out BUNDLE [--compressed true]
+    spaceproof bundle-list --in BUNDLE";
+
+type Result<T> = ::std::result::Result<T, String>;
+
+/// Public inputs and proof, as stored in a proof file.
+struct ProofFile {
+    position: u64,
+    leaf: Fr,
+    root: Fr,
+    proof: Proof<Bn256>,
+}
+
+impl ProofFile {
+    fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(PROOF_MAGIC)?;
+        writer.write_u64::<BigEndian>(self.position)?;
+        self.leaf.into_repr().write_le(&mut writer)?;
+        self.root.into_repr().write_le(&mut writer)?;
+        self.proof.write(&mut writer)
+    }
+
+    fn read<R: Read>(mut reader: R) -> io::Result<ProofFile> {
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic)?;
+        if &magic != PROOF_MAGIC {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "not a proof file"));
+        }
+        let position = reader.read_u64::<BigEndian>()?;
+        let leaf = read_fr(&mut reader)?;
+        let root = read_fr(&mut reader)?;
+        let proof = Proof::read(&mut reader)?;
+
+        Ok(ProofFile { position, leaf, root, proof })
+    }
+}
+
+/// The circuit assignment for a plot of height `depth`, as stored in a
+/// witness file.
+struct WitnessFile {
+    depth: usize,
+    assignment: Assignment<Bn256>,
+}
+
+impl WitnessFile {
+    fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(WITNESS_MAGIC)?;
+        writer.write_u32::<BigEndian>(self.depth as u32)?;
+        self.assignment.write(&mut writer)
+    }
+
+    fn read<R: Read>(mut reader: R) -> io::Result<WitnessFile> {
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic)?;
+        if &magic != WITNESS_MAGIC {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "not a witness file"));
+        }
+        let depth = reader.read_u32::<BigEndian>()? as usize;
+        let assignment = Assignment::read(&mut reader)?;
+        if assignment.inputs.len() != 3 {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "a witness has three public inputs"));
+        }
+
+        Ok(WitnessFile { depth, assignment })
+    }
+
+    /// The position of the leaf, the second public input.
+    fn position(&self) -> io::Result<u64> {
+        let repr = self.assignment.inputs[1].into_repr();
+        if repr.as_ref()[1..].iter().any(|&limb| limb != 0) {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "the position does not fit in 64 bits"));
+        }
+
+        Ok(repr.as_ref()[0])
+    }
+}
+
+fn read_fr<R: Read>(reader: R) -> io::Result<Fr> {
+    let mut repr = FrRepr::default();
+    repr.read_le(reader)?;
+    Fr::from_repr(repr).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))
+}
+
+/// `--name value` options following the subcommand.
+struct Args {
+    options: HashMap<String, String>,
+}
+
+impl Args {
+    fn parse(args: &[String]) -> Result<Args> {
+        let mut options = HashMap::new();
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            if !arg.starts_with("--") {
+                return Err(format!("unexpected argument {}", arg));
+            }
+            let value = iter.next().ok_or(format!("missing value for {}", arg))?;
+            options.insert(arg[2..].to_string(), value.clone());
+        }
+        Ok(Args { options })
+    }
+
+    fn get(&self, name: &str) -> Option<&str> {
+        self.options.get(name).map(|v| &v[..])
+    }
+
+    fn required(&self, name: &str) -> Result<&str> {
+        self.get(name).ok_or(format!("missing --{}", name))
+    }
+
+    fn number(&self, name: &str) -> Result<Option<u64>> {
+        match self.get(name) {
+            Some(v) => v.parse().map(Some).map_err(|_| format!("--{} must be a number", name)),
+            None => Ok(None),
+        }
+    }
+
+    fn required_number(&self, name: &str) -> Result<u64> {
+        self.number(name)?.ok_or(format!("missing --{}", name))
+    }
+}
+
+fn open(path: &str) -> Result<BufReader<File>> {
+    File::open(path).map(BufReader::new).map_err(|e| format!("could not open {}: {}", path, e))
+}
+
+/// Creates `path`, writes it with `write` and syncs it to disk, so a
+/// successful command leaves a complete file behind.
+fn write_file<F>(path: &str, write: F) -> Result<()>
+    where F: FnOnce(&mut BufWriter<File>) -> Result<()>
+{
+    let mut writer = File::create(path).map(BufWriter::new)
+        .map_err(|e| format!("could not create {}: {}", path, e))?;
+    write(&mut writer)?;
+    writer.flush()
+        .and_then(|_| writer.get_ref().sync_all())
+        .map_err(|e| format!("could not write {}: {}", path, e))
+}
+
+fn read_plot(path: &str) -> Result<MerklePlot> {
+    MerklePlot::read(open(path)?).map_err(|e| format!("could not read plot {}: {}", path, e))
+}
+
+fn os_rng() -> Result<OsRng> {
+    OsRng::new().map_err(|e| format!("no system randomness: {}", e))
+}
+
+fn seed(args: &Args) -> Result<Vec<u32>> {
+    match args.get("seed") {
+        Some(s) => s.split(',')
+            .map(|w| w.trim().parse().map_err(|_| "--seed must be comma separated numbers".to_string()))
+            .collect(),
+        None => {
+            let rng = &mut os_rng()?;
+            Ok((0..8).map(|_| rng.gen()).collect())
+        }
+    }
+}
+
+fn setup(args: &Args) -> Result<()> {
+    let depth = args.required_number("depth")? as usize;
+    let params = rust_miximus::generate(&seed(args)?, depth).map_err(|e| format!("could not generate parameters: {}", e))?;
+    let out = args.required("out")?;
+    let encoding = encoding(args)?;
+    write_file(out, |w| {
+        ParameterFile::new(CircuitId::merkle_tree(depth), params)
+            .write(w, encoding)
+            .map_err(|e| format!("could not write {}: {}", out, e))
+    })
+}
+
+/// Reads `--compressed`, which selects compressed points for written
+/// parameters and keys.
+fn compressed(args: &Args) -> Result<bool> {
+    match args.get("compressed") {
+        Some("true") => Ok(true),
+        Some("false") | None => Ok(false),
+        Some(_) => Err("--compressed must be true or false".to_string()),
+    }
+}
+
+fn encoding(args: &Args) -> Result<Encoding> {
+    Ok(if compressed(args)? { Encoding::Compressed } else { Encoding::Uncompressed })
+}
+
+/// Checks the header and digest of `PARAMS` and returns the header and the
+/// offset of the Groth16 parameters in the file. Bare parameters, as
+/// written before parameter files had headers, have neither.
+fn params_header(path: &str) -> Result<(Option<Header>, u64)> {
+    let mut magic = [0u8; 8];
+    if open(path)?.read_exact(&mut magic).is_err() || &magic != params::MAGIC {
+        return Ok((None, 0));
+    }
+
+    let (header, offset) = ParameterFile::check(path)
+        .map_err(|e| format!("could not read parameters {}: {}", path, e))?;
+    Ok((Some(header), offset))
+}
+
+/// Reads the verifying key at the start of the Groth16 parameters in `PARAMS`.
+fn read_params_vk(path: &str) -> Result<VerifyingKey<Bn256>> {
+    let (_, offset) = params_header(path)?;
+    let mut reader = open(path)?;
+    reader.seek(SeekFrom::Start(offset)).map_err(|e| format!("could not read {}: {}", path, e))?;
+    VerifyingKey::<Bn256>::read(reader)
+        .map_err(|e| format!("could not read verifying key {}: {}", path, e))
+}
+
+fn plot(args: &Args) -> Result<()> {
+    let height = args.required_number("height")? as usize;
+    let out = args.required("out")?;
+    let interval = args.number("checkpoint-interval")?.map(|i| i as usize).unwrap_or(DEFAULT_CHECKPOINT_INTERVAL);
+    let checkpoint = PathBuf::from(format!("{}.ckpt", out));
+
+    let mut builder = if checkpoint.exists() {
+        let builder = PlotBuilder::resume(&checkpoint, interval)
+            .map_err(|e| format!("could not resume from {}: {}", checkpoint.display(), e))?;
+        if builder.height() != height {
+            return Err(format!("{} belongs to a plot of another height", checkpoint.display()));
+        }
+        if let Some(id) = args.get("id") {
+            if hex::encode(&builder.plot_id()[..]) != id.trim_start_matches("0x").to_lowercase() {
+                return Err(format!("{} belongs to a plot with another id", checkpoint.display()));
+            }
+        }
+        eprintln!("resuming from {}", checkpoint.display());
+        builder
+    } else {
+        let mut plot_id = [0u8; 32];
+        match args.get("id") {
+            Some(id) => {
+                let bytes = hex::decode(id.trim_start_matches("0x")).map_err(|_| "--id must be hex".to_string())?;
+                if bytes.len() != plot_id.len() {
+                    return Err("--id must be 32 bytes".to_string());
+                }
+                plot_id.copy_from_slice(&bytes);
+            }
+            None => os_rng()?.fill_bytes(&mut plot_id),
+        }
+        PlotBuilder::new(plot_id, height, &checkpoint, interval)
+            .map_err(|e| format!("could not start plot: {}", e))?
+    };
+
+    let plot_id = *builder.plot_id();
+    while !builder.run(interval).map_err(|e| format!("could not checkpoint: {}", e))? {
+        let (done, total) = builder.progress();
+        eprintln!("{}/{}", done, total);
+    }
+    builder.finish_to(out).map_err(|e| format!("could not write {}: {}", out, e))?;
+
+    println!("{}", hex::encode(&plot_id[..]));
+    Ok(())
+}
+
+fn commit(args: &Args) -> Result<()> {
+    let plot = read_plot(args.required("plot")?)?;
+    println!("{}", plot.root().to_hex());
+    Ok(())
+}
+
+fn witness(args: &Args) -> Result<()> {
+    let plot = read_plot(args.required("plot")?)?;
+    let position = args.required_number("position")?;
+    let assignment = rust_miximus::witness(plot.tree(), position)
+        .map_err(|e| format!("could not compute witness: {}", e))?;
+
+    let file = WitnessFile { depth: plot.height(), assignment };
+    let out = args.required("out")?;
+    write_file(out, |w| file.write(w).map_err(|e| format!("could not write {}: {}", out, e)))
+}
+
+/// Reads the parameters for plots of height `depth` from `--params` or
+/// `--bundle`.
+fn read_proving_params(args: &Args, depth: usize) -> Result<MappedParameters<Bn256>> {
+    match args.get("bundle") {
+        Some(path) => read_bundle(path)?.map(MERKLE_TREE_CIRCUIT, depth)
+            .map_err(|e| format!("could not read parameters from {}: {}", path, e)),
+        None => {
+            let params_path = args.required("params")?;
+            let (header, offset) = params_header(params_path)?;
+            if let Some(header) = header {
+                header.check(&CircuitId::merkle_tree(depth))
+                    .map_err(|e| format!("{} does not fit the plot: {}", params_path, e))?;
+            }
+            MappedParameters::<Bn256>::open_at(params_path, offset, false)
+                .map_err(|e| format!("could not read parameters {}: {}", params_path, e))
+        }
+    }
+}
+
+fn prove(args: &Args) -> Result<()> {
+    let file = match args.get("witness") {
+        Some(path) => {
+            let witness = WitnessFile::read(open(path)?)
+                .map_err(|e| format!("could not read witness {}: {}", path, e))?;
+            let params = read_proving_params(args, witness.depth)?;
+            let position = witness.position().map_err(|e| format!("invalid witness {}: {}", path, e))?;
+            let proof = rust_miximus::prove_assignment(&seed(args)?, &params, witness.depth, &witness.assignment)
+                .map_err(|e| format!("could not create proof: {}", e))?;
+
+            let inputs = &witness.assignment.inputs;
+            ProofFile { position, leaf: inputs[0], root: inputs[2], proof }
+        }
+        None => {
+            let plot = read_plot(args.required("plot")?)?;
+            let params = read_proving_params(args, plot.height())?;
+            let position = args.required_number("position")?;
+
+            let leaf = plot.leaf(position).ok_or(format!("position {} is outside the plot", position))?;
+            let proof = rust_miximus::prove(&seed(args)?, &params, plot.tree(), position)
+                .map_err(|e| format!("could not create proof: {}", e))?;
+
+            ProofFile { position, leaf, root: plot.root(), proof }
+        }
+    };
+
+    let out = args.required("out")?;
+    write_file(out, |w| file.write(w).map_err(|e| format!("could not write {}: {}", out, e)))
+}
+
+fn export_vk(args: &Args) -> Result<()> {
+    let vk = read_params_vk(args.required("params")?)?;
+    let prepared = match args.get("prepared") {
+        Some("true") => true,
+        Some("false") | None => false,
+        Some(_) => return Err("--prepared must be true or false".to_string()),
+    };
+
+    let compressed = compressed(args)?;
+    if prepared && compressed {
+        return Err("--compressed does not apply to prepared keys".to_string());
+    }
+
+    let out = args.required("out")?;
+    write_file(out, |w| {
+        let written = if prepared {
+            prepare_verifying_key(&vk).write(w)
+        } else if compressed {
+            vk.write_encoded(w, Encoding::Compressed)
+        } else {
+            vk.write(w)
+        };
+        written.map_err(|e| format!("could not write {}: {}", out, e))
+    })
+}
+
+/// Reads the verifying key from `--params`, `--vk`, `--pvk` or `--bundle`.
+fn read_key(args: &Args) -> Result<PreparedVerifyingKey<Bn256>> {
+    if let Some(path) = args.get("pvk") {
+        return PreparedVerifyingKey::read(open(path)?)
+            .map_err(|e| format!("could not read prepared verifying key {}: {}", path, e));
+    }
+    if let Some(path) = args.get("bundle") {
+        let depth = args.required_number("depth")? as usize;
+        let vk = read_bundle(path)?.verifying_key(MERKLE_TREE_CIRCUIT, depth)
+            .map_err(|e| format!("could not read verifying key from {}: {}", path, e))?;
+        return Ok(prepare_verifying_key(&vk));
+    }
+
+    let vk = match args.get("vk") {
+        Some(path) => VerifyingKey::<Bn256>::read(open(path)?)
+            .map_err(|e| format!("could not read verifying key {}: {}", path, e))?,
+        None => read_params_vk(args.get("params").ok_or("missing --params, --vk or --pvk".to_string())?)?,
+    };
+    Ok(prepare_verifying_key(&vk))
+}
+
+/// Returns whether the proof is valid.
+fn verify(args: &Args) -> Result<bool> {
+    let pvk = read_key(args)?;
+    let proof_path = args.required("proof")?;
+    let file = ProofFile::read(open(proof_path)?)
+        .map_err(|e| format!("could not read proof {}: {}", proof_path, e))?;
+
+    let root = Fr::from_hex(args.required("root")?).map_err(|e| format!("invalid --root: {}", e))?;
+    if root != file.root {
+        return Ok(false);
+    }
+
+    rust_miximus::verify(&pvk, &file.proof, file.leaf, file.position, root)
+        .map_err(|e| format!("could not verify proof: {}", e))
+}
+
+/// Reads an accumulator and the public key of its last contribution, if
+/// the file has one.
+fn read_accumulator(path: &str) -> Result<(Accumulator<Bn256>, Option<PublicKey<Bn256>>)> {
+    let mut reader = open(path)?;
+    let accumulator = Accumulator::read(&mut reader)
+        .map_err(|e| format!("could not read accumulator {}: {}", path, e))?;
+    let mut rest = vec![];
+    reader.read_to_end(&mut rest).map_err(|e| format!("could not read accumulator {}: {}", path, e))?;
+    if rest.is_empty() {
+        return Ok((accumulator, None));
+    }
+    let key = PublicKey::read(&rest[..]).map_err(|e| format!("could not read public key in {}: {}", path, e))?;
+
+    Ok((accumulator, Some(key)))
+}
+
+fn write_accumulator(path: &str, accumulator: &Accumulator<Bn256>, key: &PublicKey<Bn256>) -> Result<()> {
+    write_file(path, |w| {
+        accumulator.write(&mut *w)
+            .and_then(|_| key.write(w))
+            .map_err(|e| format!("could not write {}: {}", path, e))
+    })?;
+
+    println!("{}", hex::encode(&accumulator.hash()[..]));
+    Ok(())
+}
+
+fn tau_new(args: &Args) -> Result<()> {
+    let exp = args.required_number("exp")?;
+    if exp >= 28 {
+        return Err(format!("--exp {} is too large", exp));
+    }
+    let accumulator = Accumulator::<Bn256>::new(exp as u32).map_err(|e| format!("invalid --exp: {}", e))?;
+    let out = args.required("out")?;
+    write_file(out, |w| accumulator.write(w).map_err(|e| format!("could not write {}: {}", out, e)))
+}
+
+fn tau_contribute(args: &Args) -> Result<()> {
+    let (mut accumulator, _) = read_accumulator(args.required("in")?)?;
+    let key = accumulator.contribute(&mut os_rng()?);
+    write_accumulator(args.required("out")?, &accumulator, &key)
+}
+
+fn beacon(args: &Args) -> Result<(Vec<u8>, u32)> {
+    let beacon = hex::decode(args.required("beacon")?).map_err(|e| format!("invalid --beacon: {}", e))?;
+    let iterations_exp = args.required_number("iterations-exp")?;
+    if iterations_exp >= 64 {
+        return Err(format!("--iterations-exp {} is too large", iterations_exp));
+    }
+
+    Ok((beacon, iterations_exp as u32))
+}
+
+fn tau_beacon(args: &Args) -> Result<()> {
+    let (beacon, iterations_exp) = beacon(args)?;
+    let (mut accumulator, _) = read_accumulator(args.required("in")?)?;
+    let key = accumulator.apply_beacon(&beacon, iterations_exp).map_err(|e| format!("invalid beacon: {}", e))?;
+    write_accumulator(args.required("out")?, &accumulator, &key)
+}
+
+fn tau_verify(args: &Args) -> Result<()> {
+    let (before, _) = read_accumulator(args.required("before")?)?;
+    let after_path = args.required("after")?;
+    let (after, key) = read_accumulator(after_path)?;
+
+    let result = if args.get("beacon").is_some() {
+        let (beacon, iterations_exp) = beacon(args)?;
+        Accumulator::verify_beacon(&before, &after, &beacon, iterations_exp)
+    } else {
+        let key = key.ok_or(format!("{} has no public key", after_path))?;
+        Accumulator::verify_contribution(&before, &after, &key)
+    };
+    result.map_err(|e| format!("contribution is invalid: {}", e))?;
+
+    println!("{}", hex::encode(&after.hash()[..]));
+    Ok(())
+}
+
+fn read_ceremony(path: &str) -> Result<MPCParameters<Bn256>> {
+    MPCParameters::read(open(path)?, true).map_err(|e| format!("could not read ceremony {}: {}", path, e))
+}
+
+fn ceremony_new(args: &Args) -> Result<()> {
+    let depth = args.required_number("depth")? as usize;
+    let params = match args.get("phase1") {
+        Some(path) => rust_miximus::ceremony_from_phase1(depth, &read_accumulator(path)?.0),
+        None => rust_miximus::new_ceremony(depth),
+    }.map_err(|e| format!("could not start ceremony: {}", e))?;
+    let out = args.required("out")?;
+    write_file(out, |w| params.write(w).map_err(|e| format!("could not write {}: {}", out, e)))
+}
+
+fn ceremony_contribute(args: &Args) -> Result<()> {
+    let mut params = read_ceremony(args.required("in")?)?;
+    let hash = params.contribute(&mut os_rng()?);
+    let out = args.required("out")?;
+    write_file(out, |w| params.write(w).map_err(|e| format!("could not write {}: {}", out, e)))?;
+
+    println!("{}", hex::encode(&hash[..]));
+    Ok(())
+}
+
+fn ceremony_verify(args: &Args) -> Result<()> {
+    let depth = args.required_number("depth")? as usize;
+    let params = read_ceremony(args.required("in")?)?;
+    let hashes = match args.get("phase1") {
+        Some(path) => rust_miximus::verify_ceremony_from_phase1(&params, depth, &read_accumulator(path)?.0),
+        None => rust_miximus::verify_ceremony(&params, &read_ceremony(args.required("initial")?)?, depth),
+    }.map_err(|e| format!("ceremony is invalid: {}", e))?;
+    for hash in &hashes {
+        println!("{}", hex::encode(&hash[..]));
+    }
+
+    if let Some(out) = args.get("out") {
+        let transcript = params.transcript_hash();
+        let mut file = ParameterFile::new(CircuitId::merkle_tree(depth), params.into_params());
+        file.header.transcript = Some(transcript);
+        let encoding = encoding(args)?;
+        write_file(out, |w| file.write(w, encoding).map_err(|e| format!("could not write {}: {}", out, e)))?;
+    }
+    Ok(())
+}
+
+fn read_bundle(path: &str) -> Result<Bundle> {
+    Bundle::open(path).map_err(|e| format!("could not read bundle {}: {}", path, e))
+}
+
+fn bundle_new(args: &Args) -> Result<()> {
+    let out = args.required("out")?;
+    let params = args.required("params")?;
+    write_file(out, |w| {
+        let mut bundle = BundleWriter::new(w, BundleKind::Parameters)
+            .map_err(|e| format!("could not write {}: {}", out, e))?;
+        for path in params.split(',') {
+            let (header, offset) = params_header(path)?;
+            let header = header.ok_or(format!("{} has no header naming its circuit", path))?;
+            let mut reader = open(path)?;
+            reader.seek(SeekFrom::Start(offset)).map_err(|e| format!("could not read {}: {}", path, e))?;
+            bundle.add_raw(header, reader).map_err(|e| format!("could not add {}: {}", path, e))?;
+        }
+        bundle.finish().map_err(|e| format!("could not write {}: {}", out, e))?;
+        Ok(())
+    })
+}
+
+fn bundle_vk(args: &Args) -> Result<()> {
+    let path = args.required("in")?;
+    let out = args.required("out")?;
+    let bundle = read_bundle(path)?;
+    let encoding = encoding(args)?;
+    write_file(out, |w| {
+        bundle.write_verifying_keys(w, encoding).map_err(|e| format!("could not write {}: {}", out, e))?;
+        Ok(())
+    })
+}
+
+fn bundle_list(args: &Args) -> Result<()> {
+    let bundle = read_bundle(args.required("in")?)?;
+    for header in bundle.headers() {
+        let transcript = header.transcript.map_or("-".to_string(), |hash| hex::encode(&hash[..]));
+        println!("{} {} {}", header.id.circuit, header.id.depth, transcript);
+    }
+    Ok(())
+}
+
+fn run(command: &str, args: &Args) -> Result<bool> {
+    match command {
+        "setup" => setup(args).map(|_| true),
+        "plot" => plot(args).map(|_| true),
+        "commit" => commit(args).map(|_| true),
+        "witness" => witness(args).map(|_| true),
+        "prove" => prove(args).map(|_| true),
+        "export-vk" => export_vk(args).map(|_| true),
+        "tau-new" => tau_new(args).map(|_| true),
+        "tau-contribute" => tau_contribute(args).map(|_| true),
+        "tau-beacon" => tau_beacon(args).map(|_| true),
+        "tau-verify" => tau_verify(args).map(|_| true),
+        "ceremony-new" => ceremony_new(args).map(|_| true),
+        "ceremony-contribute" => ceremony_contribute(args).map(|_| true),
+        "ceremony-verify" => ceremony_verify(args).map(|_| true),
+        "bundle-new" => bundle_new(args).map(|_| true),
+        "bundle-vk" => bundle_vk(args).map(|_| true),
+        "bundle-list" => bundle_list(args).map(|_| true),
+        "verify" => {
+            let valid = verify(args)?;
+            println!("{}", if valid { "valid" } else { "invalid" });
+            Ok(valid)
+        }
+        _ => Err(format!("unknown command {}\n{}", command, USAGE)),
+    }
+}
+
+fn main() {
+    let argv: Vec<String> = env::args().skip(1).collect();
+    if argv.is_empty() || argv[0] == "--help" || argv[0] == "help" {
+        println!("{}", USAGE);
+        return;
+    }
+
+    let result = Args::parse(&argv[1..]).and_then(|args| run(&argv[0], &args));
+    match result {
+        Ok(true) => {}
+        Ok(false) => process::exit(1),
+        Err(e) => {
+            eprintln!("error: {}", e);
+            process::exit(2);
+        }
+    }
+}
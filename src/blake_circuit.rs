@@ -1,14 +1,16 @@
 use sapling_crypto::circuit::blake2s::blake2s;
+use blake2_rfc::blake2s::Blake2s;
 use rand::{ChaChaRng, SeedableRng, Rng};
-use bellman::groth16::{Proof, Parameters, verify_proof, create_random_proof, prepare_verifying_key, generate_random_parameters};
+use bellman::groth16::{Proof, Parameters, VerifyingKey, PreparedVerifyingKey, verify_proof, create_random_proof, prepare_verifying_key, generate_random_parameters};
 use num_bigint::BigInt;
 use num_traits::Num;
-use std::error::Error;
 
 use pairing::{bn256::{Fr, Bn256}};
 
 use wasm_bindgen::prelude::*;
 
+use error::Error;
+
 use pairing::{
     Engine,
 };
@@ -23,7 +25,6 @@ use ff::{PrimeField};
 use sapling_crypto::{
     circuit::{
         multipack,
-        num::{AllocatedNum},
         boolean::{Boolean, AllocatedBit},
     }
 };
@@ -31,7 +32,9 @@ use sapling_crypto::{
 pub const SUBSTRATE_BLAKE2_PERSONALIZATION: &'static [u8; 8]
           = b"12345678";
 
-/// Circuit for proving knowledge of preimage of leaf in merkle tree
+/// Circuit for proving knowledge of preimage of leaf in merkle tree.
+/// The public inputs are the packed nullifier and the packed root, see
+/// `public_inputs`.
 pub struct BlakeTreeCircuit {
     // nullifier
     pub nullifier: Option<[u8; 32]>,
@@ -50,19 +53,18 @@ impl<E: Engine> Circuit<E> for BlakeTreeCircuit {
         let nullifier: Vec<Boolean> = witness_u256(
             cs.namespace(|| "nullifier as Vec<Boolean>"),
             self.nullifier.as_ref().map(|v| &v[..])
-        ).unwrap();
-        multipack::pack_into_inputs(cs.namespace(|| "nullifier pack"), &nullifier)?;
-
-        let nullifier_field_pt: E::Fr = multipack::compute_multipacking::<E>(&booleans_to_bools(nullifier.clone()))[0];
-        let nullifier_alloc: AllocatedNum<E> = AllocatedNum::alloc(cs.namespace(|| "nullifier"), || Ok(nullifier_field_pt))?;
-        nullifier_alloc.inputize(cs.namespace(|| "public input nullifier"))?;
-
-        // secret is the right side of the preimage
+        )?;
+        // the public nullifier is the packing of its first CAPACITY bits
+        multipack::pack_into_inputs(
+            cs.namespace(|| "public input nullifier"),
+            &nullifier[..E::Fr::CAPACITY as usize]
+        )?;
+
+        // secret is the right side of the preimage and stays private
         let secret: Vec<Boolean> = witness_u256(
             cs.namespace(|| "secret"),
             self.secret.as_ref().map(|v| &v[..])
-        ).unwrap();
-        multipack::pack_into_inputs(cs.namespace(|| "secret pack"), &secret)?;
+        )?;
 
         // construct preimage using [nullifier_bits|secret_bits] concatenation
         let mut preimage: Vec<Boolean> = vec![];
@@ -74,10 +76,7 @@ impl<E: Engine> Circuit<E> for BlakeTreeCircuit {
         preimage.resize(512, Boolean::Constant(false));
 
         // compute leaf hash using pedersen hash of preimage
-        let mut hash = match blake2s(cs.namespace(|| "preimage hash"), &preimage, SUBSTRATE_BLAKE2_PERSONALIZATION) {
-            Ok(value) => value,
-            Err(e) => panic!("{:?}", e),
-        };
+        let mut hash = blake2s(cs.namespace(|| "preimage hash"), &preimage, SUBSTRATE_BLAKE2_PERSONALIZATION)?;
 
         // reconstruct merkle root hash using the private merkle path
         for i in 0..self.proof.len() {
@@ -85,16 +84,24 @@ impl<E: Engine> Circuit<E> for BlakeTreeCircuit {
                 let elt = witness_u256(
                     cs.namespace(|| format!("elt {}", i)),
                     Some(element.as_ref())
-                ).unwrap();
-
-                // Swap the two if the current subtree is on the right
-                let (xl, xr): (Vec<Boolean>, Vec<Boolean>);
-                if !*side {
-                    xl = elt;
-                    xr = hash;
-                } else {
-                    xl = hash;
-                    xr = elt;
+                )?;
+
+                // Swap the two if the current subtree is on the right. The
+                // side is a private witness, so that the same parameters
+                // work for every path: with d = hash ^ elt,
+                // xl = elt ^ (side & d) and xr = hash ^ (side & d).
+                let side = Boolean::from(AllocatedBit::alloc(
+                    cs.namespace(|| format!("side {}", i)),
+                    Some(*side)
+                )?);
+                let mut xl = Vec::with_capacity(256);
+                let mut xr = Vec::with_capacity(256);
+                for (j, (h, e)) in hash.iter().zip(elt.iter()).enumerate() {
+                    let mut cs = cs.namespace(|| format!("swap {} bit {}", i, j));
+                    let d = Boolean::xor(cs.namespace(|| "d"), h, e)?;
+                    let swap = Boolean::and(cs.namespace(|| "side and d"), &side, &d)?;
+                    xl.push(Boolean::xor(cs.namespace(|| "xl"), e, &swap)?);
+                    xr.push(Boolean::xor(cs.namespace(|| "xr"), h, &swap)?);
                 }
 
                 // build preimage of merkle hash as concatenation of left and right nodes
@@ -106,24 +113,20 @@ impl<E: Engine> Circuit<E> for BlakeTreeCircuit {
                 preimage.resize(512, Boolean::Constant(false));
 
 
-                hash = blake2s(cs.namespace(|| format!("black hash depth: {}", i)), &preimage, SUBSTRATE_BLAKE2_PERSONALIZATION).unwrap();
+                hash = blake2s(cs.namespace(|| format!("black hash depth: {}", i)), &preimage, SUBSTRATE_BLAKE2_PERSONALIZATION)?;
             }
         }
 
         assert_eq!(hash.len(), 256);
-        let hash_pt = multipack::compute_multipacking::<E>(&booleans_to_bools(hash))[0];
-        let hash_alloc: AllocatedNum<E> = AllocatedNum::alloc(cs.namespace(|| "hash alloc"), || Ok(hash_pt))?;
-        hash_alloc.inputize(cs.namespace(|| "calculated root hash"))?;
+        // the public root is the packing of the first CAPACITY bits of the hash
+        multipack::pack_into_inputs(
+            cs.namespace(|| "calculated root hash"),
+            &hash[..E::Fr::CAPACITY as usize]
+        )?;
         Ok(())
     }
 }
 
-fn booleans_to_bools(booleans: Vec<Boolean>) -> Vec<bool> {
-    return booleans.clone().iter_mut().map(|b| {
-        b.get_value().unwrap()
-    }).collect();
-}
-
 fn print_booleans(booleans: Vec<Boolean>) {
     for i in 0..booleans.len() {
         println!("{:?}", booleans[i].get_value());
@@ -177,12 +180,26 @@ fn witness_u256<E, CS>(
 
 #[derive(Serialize)]
 pub struct KGGenerate {
-    pub params: String
+    pub params: String,
+    /// The verifying key alone, all a verifier needs
+    pub vk: String
 }
 
 #[derive(Serialize)]
 pub struct KGProof {
     pub proof: String,
+    /// Public nullifier input, as accepted by `verify`
+    pub nullifier: String,
+    /// Public root input, as accepted by `verify`
+    pub root: String,
+}
+
+/// Merkle path element as given in JSON: `hash` is 32 bytes of hex and
+/// `side` is `true` when the path so far is the left child.
+#[derive(Deserialize)]
+pub struct KGPathElement {
+    pub side: bool,
+    pub hash: String,
 }
 
 #[derive(Serialize)]
@@ -190,7 +207,8 @@ pub struct KGVerify {
     pub result: bool
 }
 
-pub fn generate(seed_slice: &[u32], depth: u32) -> Result<KGGenerate, Box<Error>> {
+/// Generates Groth16 parameters for paths of `depth` nodes.
+pub fn generate_parameters(seed_slice: &[u32], depth: u32) -> Result<Parameters<Bn256>, Error> {
     let rng = &mut ChaChaRng::from_seed(seed_slice);
     let mut proof_elts = vec![];
 
@@ -201,39 +219,44 @@ pub fn generate(seed_slice: &[u32], depth: u32) -> Result<KGGenerate, Box<Error>
         )));
     }
 
-    let result = generate_random_parameters::<Bn256, _, _>(
+    let params = generate_random_parameters::<Bn256, _, _>(
         BlakeTreeCircuit {
             nullifier: Some(rng.gen::<[u8; 32]>()),
             secret: Some(rng.gen::<[u8; 32]>()),
             proof: proof_elts,
         },
         rng,
-    );
+    )?;
 
-    let params = match result {
-        Ok(p) => p,
-        Err(m) => panic!(m),
-    };
+    Ok(params)
+}
+
+pub fn generate(seed_slice: &[u32], depth: u32) -> Result<KGGenerate, Error> {
+    let params = generate_parameters(seed_slice, depth)?;
 
     let mut v = vec![];
 
     params.write(&mut v)?;
 
+    let mut vk = vec![];
+    params.vk.write(&mut vk)?;
+
     Ok(KGGenerate {
-        params: hex::encode(&v[..])
+        params: hex::encode(&v[..]),
+        vk: hex::encode(&vk[..])
     })
 }
 
-pub fn prove(
+/// Proves knowledge of `secret` for `nullifier` under the root that
+/// `proof_path` leads to. The public inputs are given by `public_inputs`.
+pub fn create_proof(
     seed_slice: &[u32],
-    params: &str,
+    params: &Parameters<Bn256>,
     nullifier: &[u8; 32],
     secret: &[u8; 32],
     proof_path: Vec<Option<(bool,[u8; 32])>>,
-) -> Result<KGProof, Box<Error>> {
+) -> Result<Proof<Bn256>, Error> {
     let rng = &mut ChaChaRng::from_seed(seed_slice);
-    // construct proof path structure
-    let de_params = Parameters::<Bn256>::read(&hex::decode(params)?[..], true)?;
 
     let proof = create_random_proof(
         BlakeTreeCircuit {
@@ -241,44 +264,176 @@ pub fn prove(
             secret: Some(*secret),
             proof: proof_path,
         },
-        &de_params,
+        params,
         rng
-    ).unwrap();
+    )?;
+
+    Ok(proof)
+}
+
+pub fn prove(
+    seed_slice: &[u32],
+    params: &str,
+    nullifier: &[u8; 32],
+    secret: &[u8; 32],
+    proof_path: Vec<Option<(bool,[u8; 32])>>,
+) -> Result<KGProof, Error> {
+    let de_params = Parameters::<Bn256>::read(&hex::decode(params)?[..], true)?;
+    let proof = create_proof(seed_slice, &de_params, nullifier, secret, proof_path.clone())?;
+
+    let (nullifier, root) = public_inputs(nullifier, secret, &proof_path);
 
     let mut v = vec![];
     proof.write(&mut v)?;
     Ok(KGProof {
         proof: hex::encode(&v[..]),
+        nullifier: nullifier.to_hex(),
+        root: root.to_hex(),
     })
 }
 
+/// Checks a proof against the hex verifying key `vk`, as in
+/// `KGGenerate::vk`. Since parameters start with their verifying key, hex
+/// parameters are accepted too, at the cost of decoding them.
 pub fn verify(
-    params: &str,
+    vk: &str,
+    proof: &str,
+    nullifier_hex: &str,
+    root_hex: &str
+) -> Result<KGVerify, Error> {
+    let vk = VerifyingKey::<Bn256>::read(&hex::decode(vk)?[..])?;
+    let pvk = prepare_verifying_key(&vk);
+
+    Ok(KGVerify{
+        result: verify_prepared(&pvk, proof, nullifier_hex, root_hex)?
+    })
+}
+
+/// Like `verify` with an already prepared verifying key, e.g. one read with
+/// `PreparedVerifyingKey::read`, to check many proofs.
+pub fn verify_prepared(
+    pvk: &PreparedVerifyingKey<Bn256>,
     proof: &str,
     nullifier_hex: &str,
     root_hex: &str
-) -> Result<KGVerify, Box<Error>> {
-    let de_params = Parameters::read(&hex::decode(params)?[..], true)?;
-    let pvk = prepare_verifying_key::<Bn256>(&de_params.vk);
+) -> Result<bool, Error> {
     // Nullifier
-    let nullifier_big = BigInt::from_str_radix(nullifier_hex, 16)?;
-    let nullifier_raw = &nullifier_big.to_str_radix(10);
-    let nullifier = Fr::from_str(nullifier_raw).ok_or("couldn't parse Fr")?;
+    let nullifier = fr_from_hex(nullifier_hex)?;
     // Root hash
-    let root_big = BigInt::from_str_radix(root_hex, 16)?;
-    let root_raw = &root_big.to_str_radix(10);
-    let root = Fr::from_str(root_raw).ok_or("couldn't parse Fr")?;
+    let root = fr_from_hex(root_hex)?;
+
+    verify_inputs(pvk, &Proof::read(&hex::decode(proof)?[..])?, nullifier, root)
+}
+
+/// Checks a proof against the packed nullifier and root, as returned by
+/// `public_inputs`.
+pub fn verify_inputs(
+    pvk: &PreparedVerifyingKey<Bn256>,
+    proof: &Proof<Bn256>,
+    nullifier: Fr,
+    root: Fr
+) -> Result<bool, Error> {
     let result = verify_proof(
-        &pvk,
-        &Proof::read(&hex::decode(proof)?[..])?,
+        pvk,
+        proof,
         &[
             nullifier,
             root
         ])?;
 
-    Ok(KGVerify{
-        result: result
-    })
+    Ok(result)
+}
+
+/// Hashes two 32 byte nodes as the circuit does.
+fn hash_pair(left: &[u8], right: &[u8]) -> [u8; 32] {
+    let mut h = Blake2s::with_params(32, &[], &[], SUBSTRATE_BLAKE2_PERSONALIZATION);
+    h.update(left);
+    h.update(right);
+    let mut hash = [0u8; 32];
+    hash.copy_from_slice(h.finalize().as_bytes());
+    hash
+}
+
+/// Witnessed bytes enter the circuit most significant bit first, while the
+/// Blake2s gadget reads every byte least significant bit first.
+fn as_gadget_bytes(bytes: &[u8; 32]) -> [u8; 32] {
+    let mut out = *bytes;
+    for b in out.iter_mut() {
+        *b = b.reverse_bits();
+    }
+    out
+}
+
+/// Computes natively the public inputs `(nullifier, root)` of the circuit
+/// for the given witness.
+pub fn public_inputs(
+    nullifier: &[u8; 32],
+    secret: &[u8; 32],
+    proof_path: &[Option<(bool, [u8; 32])>],
+) -> (Fr, Fr) {
+    let mut hash = hash_pair(&as_gadget_bytes(nullifier), &as_gadget_bytes(secret));
+    for element in proof_path {
+        if let Some((side, ref element)) = *element {
+            let element = as_gadget_bytes(element);
+            hash = if !side {
+                hash_pair(&element, &hash)
+            } else {
+                hash_pair(&hash, &element)
+            };
+        }
+    }
+
+    let nullifier_bits = multipack::bytes_to_bits(nullifier);
+    let root_bits = multipack::bytes_to_bits_le(&hash);
+    (
+        multipack::compute_multipacking::<Bn256>(&nullifier_bits)[0],
+        multipack::compute_multipacking::<Bn256>(&root_bits)[0],
+    )
+}
+
+fn bytes32_from_hex(value: &str) -> Result<[u8; 32], Error> {
+    let value = if value.starts_with("0x") { &value[2..] } else { value };
+    let bytes = hex::decode(value)?;
+    if bytes.len() != 32 {
+        return Err(Error::InvalidInput(format!("{} is not 32 bytes", value)));
+    }
+    let mut out = [0u8; 32];
+    out.copy_from_slice(&bytes);
+    Ok(out)
+}
+
+/// Like `prove`, with the nullifier and the secret as 32 bytes of hex and
+/// the path as a JSON array of `KGPathElement` or `null`, from the leaf up.
+pub fn prove_json(
+    seed_slice: &[u32],
+    params: &str,
+    nullifier_hex: &str,
+    secret_hex: &str,
+    path_json: &str,
+) -> Result<KGProof, Error> {
+    let nullifier = bytes32_from_hex(nullifier_hex)?;
+    let secret = bytes32_from_hex(secret_hex)?;
+    let path: Vec<Option<KGPathElement>> = serde_json::from_str(path_json)
+        .map_err(|e| Error::InvalidInput(format!("malformed path: {}", e)))?;
+
+    let mut proof_path = Vec::with_capacity(path.len());
+    for element in path {
+        proof_path.push(match element {
+            Some(e) => Some((e.side, bytes32_from_hex(&e.hash)?)),
+            None => None,
+        });
+    }
+
+    prove(seed_slice, params, &nullifier, &secret, proof_path)
+}
+
+/// Parses a big endian hex field element, which may have an odd number of
+/// digits.
+fn fr_from_hex(value: &str) -> Result<Fr, Error> {
+    let big = BigInt::from_str_radix(value, 16)
+        .map_err(|e| Error::InvalidInput(format!("{}: {}", value, e)))?;
+    Fr::from_str(&big.to_str_radix(10))
+        .ok_or(Error::InvalidInput(format!("{} is not a field element", value)))
 }
 
 #[wasm_bindgen]
@@ -288,12 +443,8 @@ extern "C" {
 
 #[wasm_bindgen(catch)]
 pub fn generate_tree(seed_slice: &[u32], depth: u32) -> Result<JsValue, JsValue> {
-    let res = generate(seed_slice, depth);
-    if res.is_ok() {
-        Ok(JsValue::from_serde(&res.ok().unwrap()).unwrap())
-    } else {
-        Err(JsValue::from_str(&res.err().unwrap().to_string()))
-    }
+    let res = generate(seed_slice, depth).map_err(|e| JsValue::from_str(&e.to_string()))?;
+    JsValue::from_serde(&res).map_err(|e| JsValue::from_str(&e.to_string()))
 }
 
 
@@ -304,25 +455,74 @@ pub fn prove_tree(
     secret: &[u8; 32],
     proof_path: Vec<Option<(bool,[u8; 32])>>,
 ) -> Result<JsValue, JsValue> {
-    let res = prove(seed_slice, params, nullifier, secret, proof_path);
-    if res.is_ok() {
-        Ok(JsValue::from_serde(&res.ok().unwrap()).unwrap())
-    } else {
-        Err(JsValue::from_str(&res.err().unwrap().to_string()))
-    }
+    let res = prove(seed_slice, params, nullifier, secret, proof_path).map_err(|e| JsValue::from_str(&e.to_string()))?;
+    JsValue::from_serde(&res).map_err(|e| JsValue::from_str(&e.to_string()))
 }
 
+/// Proves with hex inputs and a JSON path, see `prove_json`. Returns
+/// `{ proof, nullifier, root }`, the latter two to be passed to `verify_tree`.
 #[wasm_bindgen(catch)]
-pub fn verify_tree(
+pub fn prove_tree_json(
+    seed_slice: &[u32],
     params: &str,
+    nullifier: &str,
+    secret: &str,
+    path: &str,
+) -> Result<JsValue, JsValue> {
+    let res = prove_json(seed_slice, params, nullifier, secret, path).map_err(|e| JsValue::from_str(&e.to_string()))?;
+    JsValue::from_serde(&res).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+/// Checks a proof against the hex verifying key `vk`, see `verify`.
+#[wasm_bindgen(catch)]
+pub fn verify_tree(
+    vk: &str,
     proof: &str,
     nullifier: &str,
     root: &str,
 ) -> Result<JsValue, JsValue> {
-    let res = verify(params, proof, nullifier, root);
-    if res.is_ok() {
-        Ok(JsValue::from_serde(&res.ok().unwrap()).unwrap())
-    } else {
-        Err(JsValue::from_str(&res.err().unwrap().to_string()))
-    }
+    let res = verify(vk, proof, nullifier, root).map_err(|e| JsValue::from_str(&e.to_string()))?;
+    JsValue::from_serde(&res).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+#[test]
+fn test_json_prove_verify() {
+    use sapling_crypto::circuit::test::TestConstraintSystem;
+
+    let seed_slice = &[1u32, 1u32, 1u32, 1u32];
+    let nullifier = "11".repeat(32);
+    let secret = "22".repeat(32);
+    let path = format!(r#"[{{"side": true, "hash": "{}"}}, {{"side": false, "hash": "{}"}}]"#, "33".repeat(32), "44".repeat(32));
+
+    // the native public inputs match the circuit
+    let proof_path = vec![Some((true, [0x33u8; 32])), Some((false, [0x44u8; 32]))];
+    let (nullifier_fr, root_fr) = public_inputs(&[0x11u8; 32], &[0x22u8; 32], &proof_path);
+    let mut cs = TestConstraintSystem::<Bn256>::new();
+    BlakeTreeCircuit {
+        nullifier: Some([0x11u8; 32]),
+        secret: Some([0x22u8; 32]),
+        proof: proof_path,
+    }.synthesize(&mut cs).unwrap();
+    assert!(cs.is_satisfied());
+    assert_eq!(cs.num_inputs(), 3);
+    assert_eq!(cs.get_input(1, "public input nullifier/input 0"), nullifier_fr);
+    assert_eq!(cs.get_input(2, "calculated root hash/input 0"), root_fr);
+
+    let generated = generate(seed_slice, 2).unwrap();
+    let params = generated.params;
+    let proof = prove_json(seed_slice, &params, &nullifier, &secret, &path).unwrap();
+    assert!(verify(&generated.vk, &proof.proof, &proof.nullifier, &proof.root).unwrap().result);
+    assert!(!verify(&generated.vk, &proof.proof, &proof.root, &proof.root).unwrap().result);
+    assert!(verify(&params, &proof.proof, &proof.nullifier, &proof.root).unwrap().result);
+
+    // a prepared key survives a round trip
+    let vk = VerifyingKey::<Bn256>::read(&hex::decode(&generated.vk).unwrap()[..]).unwrap();
+    let mut pvk_bytes = vec![];
+    prepare_verifying_key(&vk).write(&mut pvk_bytes).unwrap();
+    let pvk = PreparedVerifyingKey::<Bn256>::read(&pvk_bytes[..]).unwrap();
+    assert!(verify_prepared(&pvk, &proof.proof, &proof.nullifier, &proof.root).unwrap());
+    assert!(PreparedVerifyingKey::<Bn256>::read(&pvk_bytes[..pvk_bytes.len() - 1]).is_err());
+
+    assert!(prove_json(seed_slice, &params, "11", &secret, &path).is_err());
+    assert!(prove_json(seed_slice, &params, &nullifier, &secret, "[{}]").is_err());
 }
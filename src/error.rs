@@ -0,0 +1,68 @@
+use std::error;
+use std::fmt;
+use std::io;
+
+use bellman::SynthesisError;
+
+/// Errors returned by the public API of this crate.
+#[derive(Debug)]
+pub enum Error {
+    /// Reading or writing parameters, proofs or plots failed
+    Io(io::Error),
+    /// Parameter generation or proving failed
+    Synthesis(SynthesisError),
+    /// A hex string could not be decoded
+    Hex(hex::FromHexError),
+    /// An argument was malformed, e.g. not a field element or out of range
+    InvalidInput(String),
+    /// A proving service answered with an error code and message
+    Rpc(i64, String),
+}
+
+/// Result type of the public API of this crate.
+pub type Result<T> = ::std::result::Result<T, Error>;
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Error {
+        Error::Io(e)
+    }
+}
+
+impl From<SynthesisError> for Error {
+    fn from(e: SynthesisError) -> Error {
+        match e {
+            SynthesisError::IoError(e) => Error::Io(e),
+            e => Error::Synthesis(e),
+        }
+    }
+}
+
+impl From<hex::FromHexError> for Error {
+    fn from(e: hex::FromHexError) -> Error {
+        Error::Hex(e)
+    }
+}
+
+impl error::Error for Error {
+    fn description(&self) -> &str {
+        match *self {
+            Error::Io(_) => "encountered an I/O error",
+            Error::Synthesis(_) => "could not synthesize the circuit",
+            Error::Hex(_) => "invalid hex string",
+            Error::InvalidInput(_) => "invalid input",
+            Error::Rpc(..) => "the proving service returned an error",
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::Io(ref e) => write!(f, "I/O error: {}", e),
+            Error::Synthesis(ref e) => write!(f, "synthesis error: {}", e),
+            Error::Hex(ref e) => write!(f, "invalid hex: {}", e),
+            Error::InvalidInput(ref m) => write!(f, "invalid input: {}", m),
+            Error::Rpc(code, ref m) => write!(f, "service error {}: {}", code, m),
+        }
+    }
+}
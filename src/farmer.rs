@@ -0,0 +1,222 @@
+//! Farming: answering challenges with the plots we hold.
+//!
+//! For every challenge the farmer scores each plot natively, which only costs
+//! one leaf lookup per plot, keeps the plots whose quality is below the
+//! difficulty threshold and only then pays for a Groth16 proof of the
+//! selected leaf.
+
+use rand::{ChaChaRng, SeedableRng};
+use bellman::groth16::{Proof, Parameters, PreparedVerifyingKey, create_random_proof, verify_proof};
+use pairing::bn256::{Bn256, Fr};
+use sapling_crypto::babyjubjub::JubjubBn256;
+
+use error::{Error, Result};
+use plot::{PlotId, Challenge, Quality};
+use plot::merkle::{self, MerklePlot};
+use spaceproof_circuit::SpaceProofMerkleTreeCircuit;
+use spaceproof_merkle_tree::position_to_fr;
+
+/// Answer of one plot to a challenge.
+#[derive(Clone)]
+pub struct FarmerResponse {
+    pub plot_id: PlotId,
+    /// Root of the plot, a public input of the proof
+    pub root: Fr,
+    /// Leaf position selected by the challenge, a public input of the proof
+    pub position: u64,
+    /// Leaf value, a public input of the proof
+    pub leaf: Fr,
+    pub quality: Quality,
+    pub proof: Proof<Bn256>,
+}
+
+/// Holds plots of a single depth and the parameters to prove for them.
+pub struct Farmer {
+    depth: usize,
+    params: Parameters<Bn256>,
+    threshold: Quality,
+    plots: Vec<MerklePlot>,
+}
+
+impl Farmer {
+    /// Creates a farmer for plots of height `depth`; only qualities strictly
+    /// below `threshold` are proven.
+    pub fn new(depth: usize, params: Parameters<Bn256>, threshold: Quality) -> Farmer {
+        Farmer {
+            depth,
+            params,
+            threshold,
+            plots: vec![],
+        }
+    }
+
+    pub fn add_plot(&mut self, plot: MerklePlot) -> Result<()> {
+        if plot.height() != self.depth {
+            return Err(Error::InvalidInput(format!("plot height {} does not match farmer depth {}", plot.height(), self.depth)));
+        }
+        if self.plots.iter().any(|p| p.plot_id == plot.plot_id) {
+            return Err(Error::InvalidInput("plot is already farmed".to_string()));
+        }
+        self.plots.push(plot);
+        Ok(())
+    }
+
+    pub fn plots(&self) -> &[MerklePlot] {
+        &self.plots
+    }
+
+    /// Plots whose quality passes the threshold, as `(plot index, position,
+    /// quality)`, best first.
+    pub fn candidates(&self, challenge: &Challenge) -> Vec<(usize, u64, Quality)> {
+        let mut candidates: Vec<_> = self.plots.iter()
+            .enumerate()
+            .map(|(i, plot)| {
+                let (position, quality) = plot.quality(challenge);
+                (i, position, quality)
+            })
+            .filter(|&(_, _, ref quality)| *quality < self.threshold)
+            .collect();
+        candidates.sort_by(|a, b| a.2.cmp(&b.2));
+        candidates
+    }
+
+    /// Proves every winning candidate, best quality first.
+    pub fn respond(&self, seed_slice: &[u32], challenge: &Challenge) -> Result<Vec<FarmerResponse>> {
+        let rng = &mut ChaChaRng::from_seed(seed_slice);
+        let j_params = &JubjubBn256::new();
+
+        let mut responses = vec![];
+        for (i, position, quality) in self.candidates(challenge) {
+            let plot = &self.plots[i];
+            let leaf = plot.leaf(position)
+                .ok_or(Error::InvalidInput(format!("position {} is outside the plot", position)))?;
+            let path = plot.path(position)
+                .ok_or(Error::InvalidInput(format!("position {} is outside the plot", position)))?;
+
+            let proof = create_random_proof(
+                SpaceProofMerkleTreeCircuit {
+                    params: j_params,
+                    position: Some(position_to_fr(position)),
+                    node: Some(leaf),
+                    proof: path,
+                },
+                &self.params,
+                rng
+            )?;
+
+            responses.push(FarmerResponse {
+                plot_id: plot.plot_id,
+                root: plot.root(),
+                position,
+                leaf,
+                quality,
+                proof,
+            });
+        }
+
+        Ok(responses)
+    }
+}
+
+/// Checks a response of the plot `plot_id` committed to `root`: the position
+/// and quality must follow from the challenge, the quality must pass
+/// `threshold`, the leaf must be the plot's label at that position and the
+/// proof must show the leaf is in the tree with that root.
+///
+/// The root and plot id are the ones the farmer registered, not those of the
+/// response; otherwise anyone could grind a leaf of low quality and prove it
+/// in a tree of their own.
+pub fn verify_response(
+    pvk: &PreparedVerifyingKey<Bn256>,
+    depth: usize,
+    plot_id: &PlotId,
+    root: &Fr,
+    challenge: &Challenge,
+    threshold: &Quality,
+    response: &FarmerResponse,
+) -> Result<bool> {
+    if response.plot_id != *plot_id
+        || response.root != *root
+        || response.position != merkle::challenge_index(challenge, 1 << depth)
+        || response.leaf != merkle::label(plot_id, response.position)
+        || response.quality != merkle::quality(challenge, &response.leaf)
+        || response.quality >= *threshold
+    {
+        return Ok(false);
+    }
+
+    let result = verify_proof(
+        pvk,
+        &response.proof,
+        &[
+            response.leaf,
+            position_to_fr(response.position),
+            response.root
+        ])?;
+
+    Ok(result)
+}
+
+#[test]
+fn test_farmer_respond() {
+    use bellman::groth16::prepare_verifying_key;
+    use spaceproof_merkle_tree::{Node, build_tree, generate, prove, tree_root};
+
+    let depth = 3;
+    let seed_slice = &[1u32, 1u32, 1u32, 1u32];
+    let params = generate(seed_slice, depth).unwrap();
+    let pvk = prepare_verifying_key(&params.vk);
+
+    let challenge = [42u8; 32];
+    let plots: Vec<_> = (0..4u8).map(|i| MerklePlot::create([i; 32], depth)).collect();
+    let mut qualities: Vec<_> = plots.iter().map(|p| p.quality(&challenge).1).collect();
+    qualities.sort();
+    // only the two best plots pass
+    let threshold = qualities[2];
+
+    // a proof over a made-up tree holding a leaf ground for its quality
+    let position = merkle::challenge_index(&challenge, 1 << depth);
+    let leaf = (0..).map(position_to_fr).find(|leaf| merkle::quality(&challenge, leaf) < threshold).unwrap();
+    let mut leaves: Vec<_> = (0..1u64 << depth).map(|i| Node::new(position_to_fr(i))).collect();
+    leaves[position as usize] = Node::new(leaf);
+    let tree = build_tree(leaves);
+    let forged = FarmerResponse {
+        plot_id: [0u8; 32],
+        root: tree_root(&tree).unwrap(),
+        position,
+        leaf,
+        quality: merkle::quality(&challenge, &leaf),
+        proof: prove(seed_slice, &params, &tree, position).unwrap(),
+    };
+    assert!(verify_proof(&pvk, &forged.proof, &[forged.leaf, position_to_fr(position), forged.root]).unwrap());
+
+    let mut farmer = Farmer::new(depth, params, threshold);
+    for plot in plots {
+        farmer.add_plot(plot).unwrap();
+    }
+    assert!(farmer.add_plot(MerklePlot::create([0u8; 32], depth)).is_err());
+    assert!(farmer.add_plot(MerklePlot::create([9u8; 32], depth - 1)).is_err());
+
+    let responses = farmer.respond(seed_slice, &challenge).unwrap();
+    assert_eq!(responses.len(), 2);
+    assert_eq!(responses[0].quality, qualities[0]);
+    assert_eq!(responses[1].quality, qualities[1]);
+    for response in &responses {
+        let plot = farmer.plots().iter().find(|p| p.plot_id == response.plot_id).unwrap();
+        assert!(verify_response(&pvk, depth, &plot.plot_id, &plot.root(), &challenge, &threshold, response).unwrap());
+    }
+
+    // a response is only valid for the challenge it answers
+    let plot = farmer.plots().iter().find(|p| p.plot_id == responses[0].plot_id).unwrap();
+    assert!(!verify_response(&pvk, depth, &plot.plot_id, &plot.root(), &[43u8; 32], &threshold, &responses[0]).unwrap());
+
+    // and only for the plot it was registered for
+    let other = farmer.plots().iter().find(|p| p.plot_id != responses[0].plot_id).unwrap();
+    assert!(!verify_response(&pvk, depth, &other.plot_id, &other.root(), &challenge, &threshold, &responses[0]).unwrap());
+
+    // the forged response does not match the commitment of the plot it claims
+    let plot = &farmer.plots()[0];
+    assert!(!verify_response(&pvk, depth, &plot.plot_id, &plot.root(), &challenge, &threshold, &forged).unwrap());
+    let claimed = FarmerResponse { root: plot.root(), ..forged.clone() };
+    assert!(!verify_response(&pvk, depth, &plot.plot_id, &plot.root(), &challenge, &threshold, &claimed).unwrap());
+}
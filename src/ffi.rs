@@ -0,0 +1,680 @@
+//! C ABI for proving and verifying.
+//!
+//! Parameters, trees, proofs and verifying keys are opaque handles created
+//! by the `*_new`, `*_read` and `*_generate` functions and released with the
+//! matching `*_free`. Every function returns a `SpaceproofStatus`; on failure
+//! `spaceproof_last_error` describes what went wrong. Results are written
+//! through out pointers, which are left untouched on failure.
+//!
+//! Null pointers are reported as `InvalidInput` instead of being
+//! dereferenced; the `# Safety` section of each function says what the
+//! other pointers must point to and who owns what it returns.
+//!
+//! Field elements (leaves, roots and public inputs) are 32 bytes little
+//! endian. Serialized proofs are `SPACEPROOF_PROOF_SIZE` bytes, see
+//! `Proof::write`.
+//!
+//! The header `include/spaceproof.h` is generated with
+//! `cbindgen --config cbindgen.toml --output include/spaceproof.h`.
+
+use std::cell::RefCell;
+use std::ffi::CString;
+use std::os::raw::c_char;
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+use std::slice;
+
+use bellman::groth16::{Proof, Parameters, VerifyingKey, PreparedVerifyingKey, prepare_verifying_key};
+use ff::{PrimeField, PrimeFieldRepr};
+use pairing::bn256::{Bn256, Fr, FrRepr};
+
+use blake_circuit;
+use error::{Error, Result};
+use merkle::merkletree::MerkleTree;
+use spaceproof_merkle_tree::{self, Node};
+
+/// Size in bytes of a serialized proof.
+pub const SPACEPROOF_PROOF_SIZE: usize = 128;
+
+/// Size in bytes of a field element.
+pub const SPACEPROOF_FIELD_SIZE: usize = 32;
+
+/// Outcome of a call.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SpaceproofStatus {
+    Ok = 0,
+    /// A pointer was null, a buffer malformed or a value out of range
+    InvalidInput = 1,
+    /// Parameters, keys or proofs could not be decoded
+    Io = 2,
+    /// Parameter generation or proving failed
+    Synthesis = 3,
+    /// The library panicked; this is a bug
+    Panic = 4,
+}
+
+/// Groth16 parameters, of the space proof or of the Blake tree circuit.
+pub struct SpaceproofParams(Parameters<Bn256>);
+
+/// Prepared verifying key.
+pub struct SpaceproofVerifyingKey(PreparedVerifyingKey<Bn256>);
+
+/// Pedersen Merkle tree committing to leaves.
+pub struct SpaceproofTree(MerkleTree<Node>);
+
+/// Groth16 proof.
+pub struct SpaceproofProof(Proof<Bn256>);
+
+/// Bytes owned by the library, released with `spaceproof_buffer_free`.
+#[repr(C)]
+pub struct SpaceproofBuffer {
+    pub data: *mut u8,
+    pub len: usize,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = RefCell::new(None);
+}
+
+fn set_last_error(message: String) {
+    let message = CString::new(message.replace('\0', " ")).expect("nul bytes were replaced");
+    LAST_ERROR.with(|e| *e.borrow_mut() = Some(message));
+}
+
+/// Runs `f`, turning errors and panics into a status.
+fn run<F: FnOnce() -> Result<()>>(f: F) -> SpaceproofStatus {
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(())) => SpaceproofStatus::Ok,
+        Ok(Err(e)) => {
+            let status = match e {
+                Error::Io(_) => SpaceproofStatus::Io,
+                Error::Synthesis(_) => SpaceproofStatus::Synthesis,
+                Error::Hex(_) | Error::InvalidInput(_) | Error::Rpc(..) => SpaceproofStatus::InvalidInput,
+            };
+            set_last_error(e.to_string());
+            status
+        }
+        Err(_) => {
+            set_last_error("panic in spaceproof".to_string());
+            SpaceproofStatus::Panic
+        }
+    }
+}
+
+fn null(name: &str) -> Error {
+    Error::InvalidInput(format!("{} is null", name))
+}
+
+unsafe fn reference<'a, T>(ptr: *const T, name: &str) -> Result<&'a T> {
+    ptr.as_ref().ok_or_else(|| null(name))
+}
+
+/// The `len` bytes at `data`; `data` may only be null if `len` is zero.
+unsafe fn bytes<'a, T>(data: *const T, len: usize, name: &str) -> Result<&'a [T]> {
+    if len == 0 {
+        Ok(&[])
+    } else if data.is_null() {
+        Err(null(name))
+    } else {
+        Ok(slice::from_raw_parts(data, len))
+    }
+}
+
+unsafe fn read_fr(data: *const u8, name: &str) -> Result<Fr> {
+    let mut repr = FrRepr::default();
+    repr.read_le(bytes(data, SPACEPROOF_FIELD_SIZE, name)?)?;
+    Fr::from_repr(repr).map_err(|e| Error::InvalidInput(format!("{} is not a field element: {}", name, e)))
+}
+
+unsafe fn write_fr(value: Fr, out: *mut u8, name: &str) -> Result<()> {
+    if out.is_null() {
+        return Err(null(name));
+    }
+    value.into_repr().write_le(slice::from_raw_parts_mut(out, SPACEPROOF_FIELD_SIZE))?;
+    Ok(())
+}
+
+fn buffer(v: Vec<u8>) -> SpaceproofBuffer {
+    let v = Box::leak(v.into_boxed_slice());
+    SpaceproofBuffer { data: v.as_mut_ptr(), len: v.len() }
+}
+
+unsafe fn put<T>(out: *mut *mut T, value: T) {
+    *out = Box::into_raw(Box::new(value));
+}
+
+unsafe fn free<T>(ptr: *mut T) {
+    if !ptr.is_null() {
+        drop(Box::from_raw(ptr));
+    }
+}
+
+/// Message of the last failed call on this thread, or null. The string is
+/// valid until the next failing call on the same thread.
+#[no_mangle]
+pub extern "C" fn spaceproof_last_error() -> *const c_char {
+    LAST_ERROR.with(|e| e.borrow().as_ref().map(|m| m.as_ptr()).unwrap_or(ptr::null()))
+}
+
+/// Releases bytes the library returned in a `SpaceproofBuffer`.
+///
+/// # Safety
+///
+/// `buffer` must have been filled in by this library, with `data` and `len`
+/// unchanged, and not be freed again. A buffer with null `data` is ignored.
+#[no_mangle]
+pub unsafe extern "C" fn spaceproof_buffer_free(buffer: SpaceproofBuffer) {
+    if !buffer.data.is_null() {
+        drop(Box::from_raw(slice::from_raw_parts_mut(buffer.data, buffer.len)));
+    }
+}
+
+/// Generates parameters for space proofs in trees of height `depth`.
+///
+/// # Safety
+///
+/// `seed` must point to `seed_len` readable `u32`s, or be null if
+/// `seed_len` is zero. `out` must be valid for a write; the handle stored
+/// there belongs to the caller, who releases it with
+/// `spaceproof_params_free`.
+#[no_mangle]
+pub unsafe extern "C" fn spaceproof_params_generate(
+    seed: *const u32,
+    seed_len: usize,
+    depth: u32,
+    out: *mut *mut SpaceproofParams,
+) -> SpaceproofStatus {
+    run(|| {
+        let seed = bytes(seed, seed_len, "seed")?;
+        if out.is_null() {
+            return Err(null("out"));
+        }
+        let params = spaceproof_merkle_tree::generate(seed, depth as usize)?;
+        put(out, SpaceproofParams(params));
+        Ok(())
+    })
+}
+
+/// Generates parameters for Blake tree proofs of paths of `depth` nodes.
+///
+/// # Safety
+///
+/// As for `spaceproof_params_generate`.
+#[no_mangle]
+pub unsafe extern "C" fn spaceproof_blake_params_generate(
+    seed: *const u32,
+    seed_len: usize,
+    depth: u32,
+    out: *mut *mut SpaceproofParams,
+) -> SpaceproofStatus {
+    run(|| {
+        let seed = bytes(seed, seed_len, "seed")?;
+        if out.is_null() {
+            return Err(null("out"));
+        }
+        let params = blake_circuit::generate_parameters(seed, depth)?;
+        put(out, SpaceproofParams(params));
+        Ok(())
+    })
+}
+
+/// Reads parameters written by `spaceproof_params_write`.
+///
+/// # Safety
+///
+/// `data` must point to `len` readable bytes, or be null if `len` is zero;
+/// they are not used after the call. `out` must be valid for a write; the
+/// handle stored there belongs to the caller, who releases it with
+/// `spaceproof_params_free`.
+#[no_mangle]
+pub unsafe extern "C" fn spaceproof_params_read(
+    data: *const u8,
+    len: usize,
+    out: *mut *mut SpaceproofParams,
+) -> SpaceproofStatus {
+    run(|| {
+        let data = bytes(data, len, "data")?;
+        if out.is_null() {
+            return Err(null("out"));
+        }
+        let params = Parameters::read(data, true)?;
+        put(out, SpaceproofParams(params));
+        Ok(())
+    })
+}
+
+/// Writes `params` as `spaceproof_params_read` reads them.
+///
+/// # Safety
+///
+/// `params` must be a handle from this library that has not been freed.
+/// `out` must be valid for a write; the buffer stored there belongs to the
+/// caller, who releases it with `spaceproof_buffer_free`.
+#[no_mangle]
+pub unsafe extern "C" fn spaceproof_params_write(
+    params: *const SpaceproofParams,
+    out: *mut SpaceproofBuffer,
+) -> SpaceproofStatus {
+    run(|| {
+        let params = reference(params, "params")?;
+        if out.is_null() {
+            return Err(null("out"));
+        }
+        let mut v = vec![];
+        params.0.write(&mut v)?;
+        *out = buffer(v);
+        Ok(())
+    })
+}
+
+/// Writes the verifying key of `params`, readable with
+/// `spaceproof_verifying_key_read`.
+///
+/// # Safety
+///
+/// As for `spaceproof_params_write`.
+#[no_mangle]
+pub unsafe extern "C" fn spaceproof_params_write_verifying_key(
+    params: *const SpaceproofParams,
+    out: *mut SpaceproofBuffer,
+) -> SpaceproofStatus {
+    run(|| {
+        let params = reference(params, "params")?;
+        if out.is_null() {
+            return Err(null("out"));
+        }
+        let mut v = vec![];
+        params.0.vk.write(&mut v)?;
+        *out = buffer(v);
+        Ok(())
+    })
+}
+
+/// Releases parameters.
+///
+/// # Safety
+///
+/// `params` must be null or a handle from this library, which must not be
+/// used or freed again afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn spaceproof_params_free(params: *mut SpaceproofParams) {
+    free(params)
+}
+
+/// Reads a verifying key as written by `VerifyingKey::write`. Parameters
+/// start with their verifying key, so parameter files are accepted too.
+///
+/// # Safety
+///
+/// `data` must point to `len` readable bytes, or be null if `len` is zero;
+/// they are not used after the call. `out` must be valid for a write; the
+/// handle stored there belongs to the caller, who releases it with
+/// `spaceproof_verifying_key_free`.
+#[no_mangle]
+pub unsafe extern "C" fn spaceproof_verifying_key_read(
+    data: *const u8,
+    len: usize,
+    out: *mut *mut SpaceproofVerifyingKey,
+) -> SpaceproofStatus {
+    run(|| {
+        let data = bytes(data, len, "data")?;
+        if out.is_null() {
+            return Err(null("out"));
+        }
+        let vk = VerifyingKey::<Bn256>::read(data)?;
+        put(out, SpaceproofVerifyingKey(prepare_verifying_key(&vk)));
+        Ok(())
+    })
+}
+
+/// Reads a verifying key as written by `PreparedVerifyingKey::write`.
+///
+/// # Safety
+///
+/// As for `spaceproof_verifying_key_read`.
+#[no_mangle]
+pub unsafe extern "C" fn spaceproof_prepared_verifying_key_read(
+    data: *const u8,
+    len: usize,
+    out: *mut *mut SpaceproofVerifyingKey,
+) -> SpaceproofStatus {
+    run(|| {
+        let data = bytes(data, len, "data")?;
+        if out.is_null() {
+            return Err(null("out"));
+        }
+        let pvk = PreparedVerifyingKey::read(data)?;
+        put(out, SpaceproofVerifyingKey(pvk));
+        Ok(())
+    })
+}
+
+/// Releases a verifying key.
+///
+/// # Safety
+///
+/// `vk` must be null or a handle from this library, which must not be used
+/// or freed again afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn spaceproof_verifying_key_free(vk: *mut SpaceproofVerifyingKey) {
+    free(vk)
+}
+
+/// Builds the tree committing to `count` leaves of `SPACEPROOF_FIELD_SIZE`
+/// bytes each.
+///
+/// # Safety
+///
+/// `leaves` must point to `count * SPACEPROOF_FIELD_SIZE` readable bytes, or
+/// be null if `count` is zero. `out` must be valid for a write; the handle
+/// stored there belongs to the caller, who releases it with
+/// `spaceproof_tree_free`.
+#[no_mangle]
+pub unsafe extern "C" fn spaceproof_tree_new(
+    leaves: *const u8,
+    count: usize,
+    out: *mut *mut SpaceproofTree,
+) -> SpaceproofStatus {
+    run(|| {
+        let len = count.checked_mul(SPACEPROOF_FIELD_SIZE).ok_or_else(|| Error::InvalidInput("too many leaves".to_string()))?;
+        let leaves = bytes(leaves, len, "leaves")?;
+        if out.is_null() {
+            return Err(null("out"));
+        }
+        let mut nodes = Vec::with_capacity(count);
+        for leaf in leaves.chunks(SPACEPROOF_FIELD_SIZE) {
+            nodes.push(Node::new(read_fr(leaf.as_ptr(), "leaf")?));
+        }
+        put(out, SpaceproofTree(spaceproof_merkle_tree::build_tree(nodes)));
+        Ok(())
+    })
+}
+
+/// Writes the root of `tree` to the `SPACEPROOF_FIELD_SIZE` bytes at `root`.
+///
+/// # Safety
+///
+/// `tree` must be a handle from `spaceproof_tree_new` that has not been
+/// freed, and `root` must be valid for writes of `SPACEPROOF_FIELD_SIZE`
+/// bytes.
+#[no_mangle]
+pub unsafe extern "C" fn spaceproof_tree_root(
+    tree: *const SpaceproofTree,
+    root: *mut u8,
+) -> SpaceproofStatus {
+    run(|| {
+        let tree = reference(tree, "tree")?;
+        write_fr(spaceproof_merkle_tree::tree_root(&tree.0)?, root, "root")
+    })
+}
+
+/// Releases a tree.
+///
+/// # Safety
+///
+/// `tree` must be null or a handle from `spaceproof_tree_new`, which must
+/// not be used or freed again afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn spaceproof_tree_free(tree: *mut SpaceproofTree) {
+    free(tree)
+}
+
+/// Proves that the leaf at `position` is in `tree`.
+///
+/// # Safety
+///
+/// `seed` must point to `seed_len` readable `u32`s, or be null if
+/// `seed_len` is zero. `params` and `tree` must be handles from this library
+/// that have not been freed; they are only read. `out` must be valid for a
+/// write; the proof stored there belongs to the caller, who releases it with
+/// `spaceproof_proof_free`.
+#[no_mangle]
+pub unsafe extern "C" fn spaceproof_prove(
+    seed: *const u32,
+    seed_len: usize,
+    params: *const SpaceproofParams,
+    tree: *const SpaceproofTree,
+    position: u64,
+    out: *mut *mut SpaceproofProof,
+) -> SpaceproofStatus {
+    run(|| {
+        let seed = bytes(seed, seed_len, "seed")?;
+        let params = reference(params, "params")?;
+        let tree = reference(tree, "tree")?;
+        if out.is_null() {
+            return Err(null("out"));
+        }
+        let proof = spaceproof_merkle_tree::prove(seed, &params.0, &tree.0, position)?;
+        put(out, SpaceproofProof(proof));
+        Ok(())
+    })
+}
+
+/// Sets `valid` to whether `proof` shows that `leaf` sits at `position` in
+/// the tree with `root`.
+///
+/// # Safety
+///
+/// `vk` and `proof` must be handles from this library that have not been
+/// freed. `leaf` and `root` must each point to `SPACEPROOF_FIELD_SIZE`
+/// readable bytes, and `valid` must be valid for a write.
+#[no_mangle]
+pub unsafe extern "C" fn spaceproof_verify(
+    vk: *const SpaceproofVerifyingKey,
+    proof: *const SpaceproofProof,
+    leaf: *const u8,
+    position: u64,
+    root: *const u8,
+    valid: *mut bool,
+) -> SpaceproofStatus {
+    run(|| {
+        let vk = reference(vk, "vk")?;
+        let proof = reference(proof, "proof")?;
+        let leaf = read_fr(leaf, "leaf")?;
+        let root = read_fr(root, "root")?;
+        if valid.is_null() {
+            return Err(null("valid"));
+        }
+        *valid = spaceproof_merkle_tree::verify(&vk.0, &proof.0, leaf, position, root)?;
+        Ok(())
+    })
+}
+
+/// Proves knowledge of `secret` for `nullifier` in a Blake tree. The path
+/// has `depth` nodes from the leaf up: `sides[i]` is non-zero when the path
+/// so far is the left child and `hashes` holds the 32 byte siblings. The
+/// public inputs of the proof are written to `nullifier_out` and `root_out`,
+/// `SPACEPROOF_FIELD_SIZE` bytes each.
+///
+/// # Safety
+///
+/// `seed` must point to `seed_len` readable `u32`s, or be null if
+/// `seed_len` is zero, and `params` must be a handle from this library that
+/// has not been freed. `nullifier` and `secret` must each point to 32
+/// readable bytes, `sides` to `depth` and `hashes` to `depth * 32`.
+/// `nullifier_out` and `root_out` must each be valid for writes of
+/// `SPACEPROOF_FIELD_SIZE` bytes. `out` must be valid for a write; the proof
+/// stored there belongs to the caller, who releases it with
+/// `spaceproof_proof_free`.
+#[no_mangle]
+pub unsafe extern "C" fn spaceproof_blake_prove(
+    seed: *const u32,
+    seed_len: usize,
+    params: *const SpaceproofParams,
+    nullifier: *const u8,
+    secret: *const u8,
+    sides: *const u8,
+    hashes: *const u8,
+    depth: usize,
+    out: *mut *mut SpaceproofProof,
+    nullifier_out: *mut u8,
+    root_out: *mut u8,
+) -> SpaceproofStatus {
+    run(|| {
+        let seed = bytes(seed, seed_len, "seed")?;
+        let params = reference(params, "params")?;
+        let mut nullifier_bytes = [0u8; 32];
+        nullifier_bytes.copy_from_slice(bytes(nullifier, 32, "nullifier")?);
+        let mut secret_bytes = [0u8; 32];
+        secret_bytes.copy_from_slice(bytes(secret, 32, "secret")?);
+        let sides = bytes(sides, depth, "sides")?;
+        let len = depth.checked_mul(32).ok_or_else(|| Error::InvalidInput("path is too long".to_string()))?;
+        let hashes = bytes(hashes, len, "hashes")?;
+        if out.is_null() {
+            return Err(null("out"));
+        }
+
+        let mut path = Vec::with_capacity(depth);
+        for (side, hash) in sides.iter().zip(hashes.chunks(32)) {
+            let mut node = [0u8; 32];
+            node.copy_from_slice(hash);
+            path.push(Some((*side != 0, node)));
+        }
+
+        let (nullifier_fr, root_fr) = blake_circuit::public_inputs(&nullifier_bytes, &secret_bytes, &path);
+        let proof = blake_circuit::create_proof(seed, &params.0, &nullifier_bytes, &secret_bytes, path)?;
+        write_fr(nullifier_fr, nullifier_out, "nullifier_out")?;
+        write_fr(root_fr, root_out, "root_out")?;
+        put(out, SpaceproofProof(proof));
+        Ok(())
+    })
+}
+
+/// Sets `valid` to whether `proof` is a Blake tree proof for the public
+/// inputs `nullifier` and `root`, as written by `spaceproof_blake_prove`.
+///
+/// # Safety
+///
+/// `vk` and `proof` must be handles from this library that have not been
+/// freed. `nullifier` and `root` must each point to `SPACEPROOF_FIELD_SIZE`
+/// readable bytes, and `valid` must be valid for a write.
+#[no_mangle]
+pub unsafe extern "C" fn spaceproof_blake_verify(
+    vk: *const SpaceproofVerifyingKey,
+    proof: *const SpaceproofProof,
+    nullifier: *const u8,
+    root: *const u8,
+    valid: *mut bool,
+) -> SpaceproofStatus {
+    run(|| {
+        let vk = reference(vk, "vk")?;
+        let proof = reference(proof, "proof")?;
+        let nullifier = read_fr(nullifier, "nullifier")?;
+        let root = read_fr(root, "root")?;
+        if valid.is_null() {
+            return Err(null("valid"));
+        }
+        *valid = blake_circuit::verify_inputs(&vk.0, &proof.0, nullifier, root)?;
+        Ok(())
+    })
+}
+
+/// Reads a proof of `SPACEPROOF_PROOF_SIZE` bytes.
+///
+/// # Safety
+///
+/// `data` must point to `len` readable bytes, or be null if `len` is zero;
+/// they are not used after the call. `out` must be valid for a write; the
+/// proof stored there belongs to the caller, who releases it with
+/// `spaceproof_proof_free`.
+#[no_mangle]
+pub unsafe extern "C" fn spaceproof_proof_read(
+    data: *const u8,
+    len: usize,
+    out: *mut *mut SpaceproofProof,
+) -> SpaceproofStatus {
+    run(|| {
+        let data = bytes(data, len, "data")?;
+        if out.is_null() {
+            return Err(null("out"));
+        }
+        let proof = Proof::read(data)?;
+        put(out, SpaceproofProof(proof));
+        Ok(())
+    })
+}
+
+/// Writes `proof` to the `SPACEPROOF_PROOF_SIZE` bytes at `out`.
+///
+/// # Safety
+///
+/// `proof` must be a handle from this library that has not been freed, and
+/// `out` must be valid for writes of `SPACEPROOF_PROOF_SIZE` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn spaceproof_proof_write(
+    proof: *const SpaceproofProof,
+    out: *mut u8,
+) -> SpaceproofStatus {
+    run(|| {
+        let proof = reference(proof, "proof")?;
+        if out.is_null() {
+            return Err(null("out"));
+        }
+        proof.0.write(slice::from_raw_parts_mut(out, SPACEPROOF_PROOF_SIZE))?;
+        Ok(())
+    })
+}
+
+/// Releases a proof.
+///
+/// # Safety
+///
+/// `proof` must be null or a handle from this library, which must not be
+/// used or freed again afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn spaceproof_proof_free(proof: *mut SpaceproofProof) {
+    free(proof)
+}
+
+#[test]
+fn test_ffi_prove_verify() {
+    use std::ffi::CStr;
+
+    let seed = [1u32, 1, 1, 1];
+    let mut leaves = vec![];
+    for i in 1..5u64 {
+        let mut leaf = [0u8; 32];
+        spaceproof_merkle_tree::position_to_fr(i).into_repr().write_le(&mut leaf[..]).unwrap();
+        leaves.extend_from_slice(&leaf);
+    }
+
+    unsafe {
+        let mut params = ptr::null_mut();
+        assert_eq!(spaceproof_params_generate(seed.as_ptr(), seed.len(), 2, &mut params), SpaceproofStatus::Ok);
+        let mut tree = ptr::null_mut();
+        assert_eq!(spaceproof_tree_new(leaves.as_ptr(), 4, &mut tree), SpaceproofStatus::Ok);
+        let mut root = [0u8; 32];
+        assert_eq!(spaceproof_tree_root(tree, root.as_mut_ptr()), SpaceproofStatus::Ok);
+
+        let mut proof = ptr::null_mut();
+        assert_eq!(spaceproof_prove(seed.as_ptr(), seed.len(), params, tree, 2, &mut proof), SpaceproofStatus::Ok);
+        let mut proof_bytes = [0u8; SPACEPROOF_PROOF_SIZE];
+        assert_eq!(spaceproof_proof_write(proof, proof_bytes.as_mut_ptr()), SpaceproofStatus::Ok);
+        let mut read_proof = ptr::null_mut();
+        assert_eq!(spaceproof_proof_read(proof_bytes.as_ptr(), proof_bytes.len(), &mut read_proof), SpaceproofStatus::Ok);
+
+        let mut vk_bytes = SpaceproofBuffer { data: ptr::null_mut(), len: 0 };
+        assert_eq!(spaceproof_params_write_verifying_key(params, &mut vk_bytes), SpaceproofStatus::Ok);
+        let mut vk = ptr::null_mut();
+        assert_eq!(spaceproof_verifying_key_read(vk_bytes.data, vk_bytes.len, &mut vk), SpaceproofStatus::Ok);
+        spaceproof_buffer_free(vk_bytes);
+
+        let mut valid = false;
+        assert_eq!(spaceproof_verify(vk, read_proof, leaves[64..].as_ptr(), 2, root.as_ptr(), &mut valid), SpaceproofStatus::Ok);
+        assert!(valid);
+        assert_eq!(spaceproof_verify(vk, read_proof, leaves[32..].as_ptr(), 2, root.as_ptr(), &mut valid), SpaceproofStatus::Ok);
+        assert!(!valid);
+
+        assert_eq!(spaceproof_prove(seed.as_ptr(), seed.len(), params, tree, 4, &mut proof), SpaceproofStatus::InvalidInput);
+        assert!(!spaceproof_last_error().is_null());
+        assert!(CStr::from_ptr(spaceproof_last_error()).to_str().unwrap().contains("outside the tree"));
+        assert_eq!(spaceproof_verify(vk, ptr::null(), root.as_ptr(), 2, root.as_ptr(), &mut valid), SpaceproofStatus::InvalidInput);
+
+        spaceproof_proof_free(proof);
+        spaceproof_proof_free(read_proof);
+        spaceproof_verifying_key_free(vk);
+        spaceproof_tree_free(tree);
+        spaceproof_params_free(params);
+    }
+}
@@ -1,5 +1,27 @@
+//! Space proofs over Pedersen Merkle trees on bn256.
+//!
+//! A prover commits to a set of leaves with `build_tree` and later proves,
+//! with a Groth16 proof of the `SpaceProofMerkleTreeCircuit`, that a leaf
+//! sits at a given position under the committed root:
+//!
+//! ```ignore
+//! let tree = build_tree(leaves);
+//! let params = generate(seed_slice, tree.height() as usize)?;
+//! let proof = prove(seed_slice, &params, &tree, position)?;
+//! let pvk = prepare_verifying_key(&params.vk);
+//! assert!(verify(&pvk, &proof, leaf, position, tree_root(&tree)?)?);
+//! ```
+//!
+//! Verifying needs neither this crate nor its prover: the `spaceproof-verifier`
+//! crate checks proofs against a `VerifyingKey` alone, without `std` and
+//! as a wasm package.
+//!
+//! `plot` and `farmer` build proofs of space on top of this, `vdf` provides
+//! a MiMC delay function and `blake_circuit` the Blake2s mixer circuit.
+
 extern crate sapling_crypto;
 extern crate bellman;
+extern crate spaceproof_verifier;
 extern crate pairing;
 extern crate ff;
 extern crate num_bigint;
@@ -8,18 +30,60 @@ extern crate rand;
 extern crate time;
 extern crate wasm_bindgen;
 extern crate blake2_rfc;
+extern crate byteorder;
 
 #[cfg(feature = "serialization-serde")]
 extern crate serde;
+#[cfg(feature = "serialization-serde")]
+extern crate bincode;
+
+#[cfg(feature = "serialization-protobuf")]
+extern crate protobuf;
 
 #[macro_use]
 extern crate serde_derive;
 
 extern crate hex;
+extern crate serde_json;
 
 extern crate ring;
 
+mod error;
 mod merkle;
-mod blake_circuit;
+pub mod blake_circuit;
 mod spaceproof_circuit;
 mod spaceproof_merkle_tree;
+pub mod vdf;
+pub mod plot;
+pub mod farmer;
+pub mod mpc;
+pub mod powersoftau;
+pub mod params;
+pub mod ffi;
+pub mod rpc;
+#[cfg(feature = "serialization-protobuf")]
+pub mod proto;
+
+pub use error::{Error, Result};
+pub use merkle::digest::{Algorithm, BLAKE2S_256, PEDERSEN_BN256};
+pub use merkle::merkletree::MerkleTree;
+pub use merkle::tree::{Proof as MerkleProof, Lemma, Positioned};
+pub use spaceproof_circuit::SpaceProofMerkleTreeCircuit;
+pub use spaceproof_merkle_tree::{
+    Node,
+    build_tree,
+    tree_root,
+    merkle_path,
+    position_to_fr,
+    generate,
+    new_ceremony,
+    ceremony_from_phase1,
+    verify_ceremony,
+    verify_ceremony_from_phase1,
+    prove,
+    witness,
+    prove_assignment,
+    circuit_shape,
+    prove_with_shape,
+    verify,
+};
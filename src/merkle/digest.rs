@@ -35,15 +35,51 @@ impl core::fmt::Debug for Digest {
 
 
 /// A digest algorithm.
+///
+/// Leaves are kept as they are and nodes are hashed with the Baby Jubjub
+/// Pedersen hash over bn256, which the circuit proves paths with, or with
+/// Blake2s for trees that are only checked natively.
 pub struct Algorithm {
+    kind: Kind,
 }
 
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Kind {
+    PedersenBn256,
+    Blake2s256,
+}
+
+/// The algorithm of the trees the circuit proves paths in.
+pub static PEDERSEN_BN256: Algorithm = Algorithm { kind: Kind::PedersenBn256 };
+
+/// Blake2s-256 over the height and both children, for native trees.
+pub static BLAKE2S_256: Algorithm = Algorithm { kind: Kind::Blake2s256 };
+
 impl core::fmt::Debug for Algorithm{
     fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
-        write!(fmt, "")
+        write!(fmt, "{}", self.id())
     }
 }
 
 impl Algorithm {
-    
+    pub(crate) fn kind(&self) -> Kind {
+        self.kind
+    }
+
+    /// Stable name of the algorithm, written when proofs are serialized.
+    pub fn id(&self) -> &'static str {
+        match self.kind {
+            Kind::PedersenBn256 => "pedersen-bn256",
+            Kind::Blake2s256 => "blake2s-256",
+        }
+    }
+
+    /// The algorithm named `id`, if known.
+    pub fn from_id(id: &str) -> Option<&'static Algorithm> {
+        match id {
+            "pedersen-bn256" => Some(&PEDERSEN_BN256),
+            "blake2s-256" => Some(&BLAKE2S_256),
+            _ => None,
+        }
+    }
 }
@@ -1,5 +1,6 @@
 // use ring::digest::{digest, Algorithm, Context, Digest};
-use super::digest::{Algorithm, Digest};
+use super::digest::{Algorithm, Digest, Kind};
+use blake2_rfc::blake2s::Blake2s;
 use ff::{Field, PrimeField, PrimeFieldRepr};
 use pairing::{bn256::{Bn256, Fr, FrRepr},Engine};
 use sapling_crypto::{
@@ -63,12 +64,15 @@ pub trait HashUtils {
 
 }
 
+/// Prefix of the nodes of `BLAKE2S_256` trees.
+const BLAKE2S_PERSONALIZATION: &'static [u8; 8] = b"SPMerkle";
+
 impl HashUtils for Algorithm {
     fn hash_empty(&'static self) -> Digest {
         // digest(self, &[])
         Digest{
             value:vec![],
-            algorithm:&Algorithm{}
+            algorithm:self
         }
     }
 
@@ -96,6 +100,18 @@ impl HashUtils for Algorithm {
         // left.update_context(&mut ctx);
         // right.update_context(&mut ctx);
         // ctx.finish()
+        if self.kind() == Kind::Blake2s256 {
+            let mut h = Blake2s::new(32);
+            h.update(BLAKE2S_PERSONALIZATION);
+            h.update(&(height as u32).to_be_bytes());
+            h.update(left.get_val());
+            h.update(right.get_val());
+            return Digest{
+                value:h.finalize().as_bytes().to_vec(),
+                algorithm:self
+            };
+        }
+
         let params = &JubjubBn256::new();
         //Fr -> bit iterator 
         let mut lhs_bool: Vec<bool> = BitIterator::new(left.get_val()).collect();
@@ -113,7 +129,7 @@ impl HashUtils for Algorithm {
     
         Digest{
             value:fr_to_vecu8(hash),
-            algorithm:&Algorithm{}
+            algorithm:self
         }
     }
     
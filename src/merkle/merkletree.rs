@@ -8,7 +8,6 @@ use super::hashutils::{HashUtils, Hashable};
 use super::tree::{LeavesIntoIterator, LeavesIterator, Tree};
 
 use super::proof::{Lemma, Proof};
-use time::PreciseTime;
 /// A Merkle tree is a binary tree, with values of type `T` at the leafs,
 /// and where every internal node holds the hash of the concatenation of the hashes of its children nodes.
 #[derive(Clone, Debug)]
@@ -91,23 +90,22 @@ impl<T> MerkleTree<T> {
         }
 
         while cur.len() > 1 {
-            let mut next = Vec::new();
-            while !cur.is_empty() {
-                if cur.len() == 1 {
-                    next.push(cur.remove(0));
-                } else {
-                    let left = cur.remove(0);
-                    let right = cur.remove(0);
-                    let start = PreciseTime::now();
-                    let combined_hash = algorithm.hash_nodes(height,left.hash(), right.hash());
-                    println!("algorithm.hash_nodes in {} s", start.to(PreciseTime::now()).num_milliseconds() as f64/1000.0);
-                    let node = Tree::Node {
-                        hash: combined_hash.as_ref().into(),
-                        left: Box::new(left),
-                        right: Box::new(right),
-                    };
-
-                    next.push(node);
+            let mut next = Vec::with_capacity((cur.len() + 1) / 2);
+            // pairs of neighbours, the last node carried up if it has none
+            let mut nodes = cur.into_iter();
+            while let Some(left) = nodes.next() {
+                match nodes.next() {
+                    Some(right) => {
+                        let combined_hash = algorithm.hash_nodes(height,left.hash(), right.hash());
+                        let node = Tree::Node {
+                            hash: combined_hash.as_ref().into(),
+                            left: Box::new(left),
+                            right: Box::new(right),
+                        };
+
+                        next.push(node);
+                    }
+                    None => next.push(left),
                 }
             }
 
@@ -118,7 +116,7 @@ impl<T> MerkleTree<T> {
 
         debug_assert!(cur.len() == 1);
 
-        let root = cur.remove(0);
+        let root = cur.pop().expect("values is not empty");
 
         MerkleTree {
             algorithm,
@@ -128,6 +126,114 @@ impl<T> MerkleTree<T> {
         }
     }
 
+    /// Rebuilds a Merkle Tree from its leaves and the node hashes returned by
+    /// `levels`, without hashing anything.
+    /// Returns `None` if `levels` does not have the shape `from_vec` would
+    /// produce for `values`.
+    pub fn from_levels(algorithm: &'static Algorithm, values: Vec<T>, levels: Vec<Vec<Vec<u8>>>) -> Option<Self>
+    where
+        T: Hashable,
+    {
+        if values.is_empty() {
+            return if levels.is_empty() { Some(MerkleTree::from_vec(algorithm, values)) } else { None };
+        }
+
+        let count = values.len();
+        let mut cur = Vec::with_capacity(count);
+        for v in values {
+            cur.push(Tree::new_leaf(algorithm, v));
+        }
+        if levels.first().map(|l| l.len()) != Some(count)
+            || cur.iter().zip(levels[0].iter()).any(|(leaf, hash)| leaf.hash() != hash)
+        {
+            return None;
+        }
+
+        let mut height = 0;
+        for level in levels.into_iter().skip(1) {
+            if level.len() != (cur.len() + 1) / 2 {
+                return None;
+            }
+            let mut next = Vec::with_capacity(level.len());
+            let mut hashes = level.into_iter();
+            let mut nodes = cur.into_iter();
+            while let Some(left) = nodes.next() {
+                let hash = hashes.next()?;
+                match nodes.next() {
+                    Some(right) => next.push(Tree::Node {
+                        hash,
+                        left: Box::new(left),
+                        right: Box::new(right),
+                    }),
+                    None => {
+                        if *left.hash() != hash {
+                            return None;
+                        }
+                        next.push(left);
+                    }
+                }
+            }
+
+            height += 1;
+
+            cur = next;
+        }
+
+        if cur.len() != 1 {
+            return None;
+        }
+
+        let root = cur.pop()?;
+
+        Some(MerkleTree {
+            algorithm,
+            root,
+            height,
+            count,
+        })
+    }
+
+    /// Returns the node hashes level by level, from the leaves up to the root.
+    pub fn levels(&self) -> Vec<Vec<Vec<u8>>> {
+        let mut levels = vec![];
+        if self.is_empty() {
+            return levels;
+        }
+
+        // number of nodes on every level, from the leaves up
+        let mut sizes = vec![self.count];
+        while *sizes.last().unwrap() > 1 {
+            let size = *sizes.last().unwrap();
+            sizes.push((size + 1) / 2);
+        }
+
+        let mut cur = vec![&self.root];
+        let mut depth_levels = vec![];
+        // walk down from the root; when the level below has an odd size, its
+        // last node was carried up unpaired and is the last node of this level
+        for below in (0..sizes.len() - 1).rev() {
+            depth_levels.push(cur.iter().map(|t| t.hash().clone()).collect::<Vec<_>>());
+            let carried = sizes[below] % 2 == 1;
+            let mut next = Vec::with_capacity(sizes[below]);
+            for (i, tree) in cur.iter().enumerate() {
+                match **tree {
+                    Tree::Node { ref left, ref right, .. } if !(carried && i == cur.len() - 1) => {
+                        next.push(&**left);
+                        next.push(&**right);
+                    }
+                    _ => next.push(*tree),
+                }
+            }
+            cur = next;
+        }
+        depth_levels.push(cur.iter().map(|t| t.hash().clone()).collect::<Vec<_>>());
+
+        while let Some(level) = depth_levels.pop() {
+            levels.push(level);
+        }
+        levels
+    }
+
     /// Returns the root hash of Merkle tree
     pub fn root_hash(&self) -> &Vec<u8> {
         self.root.hash()
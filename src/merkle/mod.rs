@@ -9,7 +9,7 @@ pub mod tree;
 
 #[cfg(feature = "serialization-protobuf")]
 #[allow(unused_qualifications)]
-mod proto;
+pub mod proto;
 
 #[cfg(test)]
 mod tests;
@@ -7,6 +7,13 @@ use super::digest::{Algorithm, Digest};
 use super::hashutils::HashUtils;
 use super::tree::Tree;
 
+#[cfg(feature = "serialization-serde")]
+use bincode;
+#[cfg(feature = "serialization-serde")]
+use serde::{de::DeserializeOwned, Serialize};
+#[cfg(feature = "serialization-serde")]
+use serde_json;
+
 /// An inclusion proof represent the fact that a `value` is a member
 /// of a `MerkleTree` with root hash `root_hash`, and hash function `algorithm`.
 #[cfg_attr(feature = "serialization-serde", derive(Serialize, Deserialize))]
@@ -17,6 +24,7 @@ pub struct Proof<T> {
     pub algorithm: &'static Algorithm,
 
     /// The hash of the root of the original `MerkleTree`
+    #[cfg_attr(feature = "serialization-serde", serde(with = "hash_serde"))]
     pub root_hash: Vec<u8>,
 
     /// The first `Lemma` of the `Proof`
@@ -31,75 +39,140 @@ pub struct Proof<T> {
 
 #[cfg(feature = "serialization-serde")]
 mod algorithm_serde {
-    use ring::digest::{self, Algorithm};
     use serde::de::Error;
     use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
+    use super::super::digest::Algorithm;
+
     pub fn serialize<S: Serializer>(
         algorithm: &'static Algorithm,
         se: S,
     ) -> Result<S::Ok, S::Error> {
-        // The `Debug` implementation of `Algorithm` prints its ID.
-        format!("{:?}", algorithm).serialize(se)
+        algorithm.id().serialize(se)
     }
 
     pub fn deserialize<'de, D: Deserializer<'de>>(de: D) -> Result<&'static Algorithm, D::Error> {
         let alg_str: String = Deserialize::deserialize(de)?;
-        match &*alg_str {
-            "SHA1" => Ok(&digest::SHA1_FOR_LEGACY_USE_ONLY),
-            "SHA256" => Ok(&digest::SHA256),
-            "SHA384" => Ok(&digest::SHA384),
-            "SHA512" => Ok(&digest::SHA512),
-            "SHA512_256" => Ok(&digest::SHA512_256),
-            _ => Err(D::Error::custom("unknown hash algorithm")),
-        }
+        Algorithm::from_id(&alg_str).ok_or_else(|| D::Error::custom("unknown hash algorithm"))
     }
 
     #[cfg(test)]
     mod test {
         use super::*;
-        use ring::digest::{
-            SHA1_FOR_LEGACY_USE_ONLY as sha1, SHA256 as sha256, SHA384 as sha384, SHA512 as sha512,
-            SHA512_256 as sha512_256,
-        };
-
-        static SHA1: &Algorithm = &sha1;
-        static SHA256: &Algorithm = &sha256;
-        static SHA384: &Algorithm = &sha384;
-        static SHA512: &Algorithm = &sha512;
-        static SHA512_256: &Algorithm = &sha512_256;
+        use super::super::super::digest::{BLAKE2S_256, PEDERSEN_BN256};
 
         #[test]
         fn test_serialize_known_algorithms() {
             extern crate serde_json;
 
-            for alg in &[SHA1, SHA256, SHA384, SHA512, SHA512_256] {
-                let mut serializer = serde_json::Serializer::with_formatter(
-                    vec![],
-                    serde_json::ser::PrettyFormatter::new(),
-                );
+            for &(alg, id) in &[(&PEDERSEN_BN256, &b"\"pedersen-bn256\""[..]), (&BLAKE2S_256, &b"\"blake2s-256\""[..])] {
+                let alg: &'static Algorithm = alg;
+                let mut serializer = serde_json::Serializer::new(vec![]);
+                serialize(alg, &mut serializer).unwrap();
 
-                serialize(alg, &mut serializer).unwrap_or_else(|_| panic!("{:?}", alg));
+                let serialized = serializer.into_inner();
+                assert_eq!(&serialized[..], id);
 
-                let alg_ = deserialize(&mut serde_json::Deserializer::from_slice(
-                    &serializer.into_inner()[..],
-                ))
-                .unwrap_or_else(|_| panic!("{:?}", alg));
-
-                assert_eq!(*alg, alg_);
+                let alg_ = deserialize(&mut serde_json::Deserializer::from_slice(&serialized[..])).unwrap();
+                assert_eq!(alg.id(), alg_.id());
             }
         }
 
         #[test]
-        #[should_panic(expected = "unknown hash algorithm")]
         fn test_serialize_unknown_algorithm() {
             extern crate serde_json;
-            {
-                let alg_str = "\"BLAKE2b\"";
-                let mut deserializer = serde_json::Deserializer::from_str(alg_str);
-                let _ = deserialize(&mut deserializer)
-                    .unwrap_or_else(|_| panic!("unknown hash algorithm {:?}", alg_str));
+
+            let mut deserializer = serde_json::Deserializer::from_str("\"SHA256\"");
+            assert!(deserialize(&mut deserializer).is_err());
+        }
+    }
+}
+
+/// Hashes as hex in human readable formats like JSON and as plain bytes in
+/// binary ones.
+#[cfg(feature = "serialization-serde")]
+mod hash_serde {
+    use std::fmt;
+
+    use hex;
+    use serde::de::{self, SeqAccess, Visitor};
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(hash: &[u8], se: S) -> Result<S::Ok, S::Error> {
+        if se.is_human_readable() {
+            se.serialize_str(&hex::encode(hash))
+        } else {
+            se.serialize_bytes(hash)
+        }
+    }
+
+    struct HashVisitor;
+
+    impl<'de> Visitor<'de> for HashVisitor {
+        type Value = Vec<u8>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a hash as hex or bytes")
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<Vec<u8>, E> {
+            hex::decode(v).map_err(E::custom)
+        }
+
+        fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Vec<u8>, E> {
+            Ok(v.to_vec())
+        }
+
+        fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Vec<u8>, E> {
+            Ok(v)
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Vec<u8>, A::Error> {
+            let mut v = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+            while let Some(byte) = seq.next_element()? {
+                v.push(byte);
             }
+            Ok(v)
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(de: D) -> Result<Vec<u8>, D::Error> {
+        if de.is_human_readable() {
+            de.deserialize_str(HashVisitor)
+        } else {
+            de.deserialize_byte_buf(HashVisitor)
+        }
+    }
+
+    /// A hash on its own, for `Positioned` hashes.
+    #[derive(Serialize, Deserialize)]
+    struct Hash(#[serde(with = "self")] Vec<u8>);
+
+    pub mod sibling {
+        use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+        use super::Hash;
+        use super::super::Positioned;
+
+        pub fn serialize<S: Serializer>(
+            sibling: &Option<Positioned<Vec<u8>>>,
+            se: S,
+        ) -> Result<S::Ok, S::Error> {
+            let sibling = sibling.as_ref().map(|sibling| match *sibling {
+                Positioned::Left(ref hash) => Positioned::Left(Hash(hash.clone())),
+                Positioned::Right(ref hash) => Positioned::Right(Hash(hash.clone())),
+            });
+            sibling.serialize(se)
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            de: D,
+        ) -> Result<Option<Positioned<Vec<u8>>>, D::Error> {
+            let sibling: Option<Positioned<Hash>> = Deserialize::deserialize(de)?;
+            Ok(sibling.map(|sibling| match sibling {
+                Positioned::Left(Hash(hash)) => Positioned::Left(hash),
+                Positioned::Right(Hash(hash)) => Positioned::Right(hash),
+            }))
         }
     }
 }
@@ -175,13 +248,38 @@ impl<T> Proof<T> {
 
 }
 
+#[cfg(feature = "serialization-serde")]
+impl<T: Serialize + DeserializeOwned> Proof<T> {
+    /// Serialize this `Proof` as JSON, with hex hashes.
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+
+    /// Parse a `Proof` written by `to_json`.
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    /// Serialize this `Proof` in the compact binary format of `bincode`.
+    pub fn to_binary(&self) -> bincode::Result<Vec<u8>> {
+        bincode::serialize(self)
+    }
+
+    /// Parse a `Proof` written by `to_binary`.
+    pub fn from_binary(bytes: &[u8]) -> bincode::Result<Self> {
+        bincode::deserialize(bytes)
+    }
+}
+
 /// A `Lemma` holds the hash of a node, the hash of its sibling node,
 /// and a sub lemma, whose `node_hash`, when combined with this `sibling_hash`
 /// must be equal to this `node_hash`.
 #[cfg_attr(feature = "serialization-serde", derive(Serialize, Deserialize))]
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Lemma {
+    #[cfg_attr(feature = "serialization-serde", serde(with = "hash_serde"))]
     pub node_hash: Vec<u8>,
+    #[cfg_attr(feature = "serialization-serde", serde(with = "hash_serde::sibling"))]
     pub sibling_hash: Option<Positioned<Vec<u8>>>,
     pub sub_lemma: Option<Box<Lemma>>,
 }
@@ -0,0 +1,200 @@
+//! Protocol Buffers messages for inclusion proofs, defined in
+//! `protobuf/spaceproof.proto`.
+
+// generated code, written for older compilers
+#[allow(unknown_lints, renamed_and_removed_lints, mismatched_lifetime_syntaxes)]
+mod spaceproof;
+
+use std::convert::TryFrom;
+
+use protobuf::{Message, ProtobufResult};
+
+use super::digest::Algorithm;
+use super::proof::{Lemma, Positioned, Proof};
+
+pub use self::spaceproof::{ProofProto, LemmaProto, PositionedProto, PositionedProto_oneof_value};
+
+impl<T> Proof<T> {
+    /// Constructs a `Proof` struct from its Protobuf representation.
+    pub fn from_protobuf(algorithm: &'static Algorithm, proto: ProofProto) -> Option<Self>
+    where
+        T: From<Vec<u8>>,
+    {
+        proto.into_proof(algorithm)
+    }
+
+    /// Encode this `Proof` to its Protobuf representation.
+    pub fn into_protobuf(self) -> ProofProto
+    where
+        T: Into<Vec<u8>>,
+    {
+        ProofProto::from_proof(self)
+    }
+
+    /// Parse a `Proof` from its Protobuf binary representation.
+    pub fn parse_from_bytes(bytes: &[u8], algorithm: &'static Algorithm) -> ProtobufResult<Option<Self>>
+    where
+        T: From<Vec<u8>>,
+    {
+        ProofProto::parse_from_bytes(bytes).map(|proto| proto.into_proof(algorithm))
+    }
+
+    /// Serialize this `Proof` with Protobuf.
+    pub fn write_to_bytes(self) -> ProtobufResult<Vec<u8>>
+    where
+        T: Into<Vec<u8>>,
+    {
+        self.into_protobuf().write_to_bytes()
+    }
+}
+
+impl ProofProto {
+    pub fn from_proof<T>(proof: Proof<T>) -> Self
+    where
+        T: Into<Vec<u8>>,
+    {
+        let mut proto = Self::new();
+
+        match proof {
+            Proof {
+                root_hash,
+                lemma,
+                value,
+                height,
+                ..
+            } => {
+                proto.set_root_hash(root_hash);
+                proto.set_lemma(LemmaProto::from_lemma(lemma));
+                proto.set_value(value.into());
+                proto.set_height(height as i64);
+            }
+        }
+
+        proto
+    }
+
+    pub fn into_proof<T>(mut self, algorithm: &'static Algorithm) -> Option<Proof<T>>
+    where
+        T: From<Vec<u8>>,
+    {
+        if !self.has_lemma() {
+            return None;
+        }
+
+        // negative heights and heights beyond isize are malformed, not truncated
+        let height = match isize::try_from(self.get_height()) {
+            Ok(height) if height >= 0 => height,
+            _ => return None,
+        };
+
+        self.take_lemma().into_lemma().map(|lemma| {
+            Proof::new(
+                algorithm,
+                self.take_root_hash(),
+                lemma,
+                self.take_value().into(),
+                height,
+            )
+        })
+    }
+}
+
+impl Lemma {
+    pub fn from_protobuf(proto: LemmaProto) -> Option<Self> {
+        proto.into_lemma()
+    }
+
+    pub fn into_protobuf(self) -> LemmaProto {
+        LemmaProto::from_lemma(self)
+    }
+}
+
+impl LemmaProto {
+    pub fn from_lemma(lemma: Lemma) -> Self {
+        let mut proto = Self::new();
+
+        match lemma {
+            Lemma {
+                node_hash,
+                sibling_hash,
+                sub_lemma,
+            } => {
+                proto.set_node_hash(node_hash);
+
+                if let Some(sibling_hash) = sibling_hash {
+                    proto.set_sibling_hash(PositionedProto::from_positioned(sibling_hash));
+                }
+
+                if let Some(sub_lemma) = sub_lemma {
+                    proto.set_sub_lemma(Self::from_lemma(*sub_lemma));
+                }
+            }
+        }
+
+        proto
+    }
+
+    pub fn into_lemma(mut self) -> Option<Lemma> {
+        let node_hash = self.take_node_hash();
+
+        let sibling_hash = if self.has_sibling_hash() {
+            Some(self.take_sibling_hash().into_positioned()?)
+        } else {
+            None
+        };
+
+        if self.has_sub_lemma() {
+            // If a `sub_lemma` is present is the Protobuf,
+            // then we expect it to unserialize to a valid `Lemma`,
+            // otherwise we return `None`
+            self.take_sub_lemma().into_lemma().map(|sub_lemma| Lemma {
+                node_hash,
+                sibling_hash,
+                sub_lemma: Some(Box::new(sub_lemma)),
+            })
+        } else {
+            // We might very well not have a sub_lemma,
+            // in which case we just set it to `None`,
+            // but still return a potentially valid `Lemma`.
+            Some(Lemma {
+                node_hash,
+                sibling_hash,
+                sub_lemma: None,
+            })
+        }
+    }
+}
+
+impl Positioned<Vec<u8>> {
+    pub fn from_protobuf(proto: PositionedProto) -> Option<Self> {
+        proto.into_positioned()
+    }
+
+    pub fn into_protobuf(self) -> PositionedProto {
+        PositionedProto::from_positioned(self)
+    }
+}
+
+impl PositionedProto {
+    pub fn from_positioned(positioned: Positioned<Vec<u8>>) -> Self {
+        let mut proto = Self::new();
+
+        match positioned {
+            Positioned::Left(hash) => proto.set_left(hash),
+            Positioned::Right(hash) => proto.set_right(hash),
+        }
+
+        proto
+    }
+
+    /// `None` if neither side is set.
+    pub fn into_positioned(mut self) -> Option<Positioned<Vec<u8>>> {
+        if self.has_left() {
+            Some(Positioned::Left(self.take_left()))
+        } else if self.has_right() {
+            Some(Positioned::Right(self.take_right()))
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,882 @@
+// This file is generated by rust-protobuf 2.28.0. Do not edit
+// @generated
+
+// https://github.com/rust-lang/rust-clippy/issues/702
+#![allow(unknown_lints)]
+#![allow(clippy::all)]
+
+#![allow(unused_attributes)]
+#![cfg_attr(rustfmt, rustfmt::skip)]
+
+#![allow(box_pointers)]
+#![allow(dead_code)]
+#![allow(missing_docs)]
+#![allow(non_camel_case_types)]
+#![allow(non_snake_case)]
+#![allow(non_upper_case_globals)]
+#![allow(trivial_casts)]
+#![allow(unused_imports)]
+#![allow(unused_results)]
+//! Generated file from `spaceproof.proto`
+
+/// Generated files are compatible only with the same version
+/// of protobuf runtime.
+// const _PROTOBUF_VERSION_CHECK: () = ::protobuf::VERSION_2_28_0;
+
+#[derive(PartialEq,Clone,Default)]
+pub struct ProofProto {
+    // message fields
+    pub root_hash: ::std::vec::Vec<u8>,
+    pub lemma: ::protobuf::SingularPtrField<LemmaProto>,
+    pub value: ::std::vec::Vec<u8>,
+    pub height: i64,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a ProofProto {
+    fn default() -> &'a ProofProto {
+        <ProofProto as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl ProofProto {
+    pub fn new() -> ProofProto {
+        ::std::default::Default::default()
+    }
+
+    // bytes root_hash = 1;
+
+
+    pub fn get_root_hash(&self) -> &[u8] {
+        &self.root_hash
+    }
+    pub fn clear_root_hash(&mut self) {
+        self.root_hash.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_root_hash(&mut self, v: ::std::vec::Vec<u8>) {
+        self.root_hash = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_root_hash(&mut self) -> &mut ::std::vec::Vec<u8> {
+        &mut self.root_hash
+    }
+
+    // Take field
+    pub fn take_root_hash(&mut self) -> ::std::vec::Vec<u8> {
+        ::std::mem::replace(&mut self.root_hash, ::std::vec::Vec::new())
+    }
+
+    // .spaceproof.LemmaProto lemma = 2;
+
+
+    pub fn get_lemma(&self) -> &LemmaProto {
+        self.lemma.as_ref().unwrap_or_else(|| <LemmaProto as ::protobuf::Message>::default_instance())
+    }
+    pub fn clear_lemma(&mut self) {
+        self.lemma.clear();
+    }
+
+    pub fn has_lemma(&self) -> bool {
+        self.lemma.is_some()
+    }
+
+    // Param is passed by value, moved
+    pub fn set_lemma(&mut self, v: LemmaProto) {
+        self.lemma = ::protobuf::SingularPtrField::some(v);
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_lemma(&mut self) -> &mut LemmaProto {
+        if self.lemma.is_none() {
+            self.lemma.set_default();
+        }
+        self.lemma.as_mut().unwrap()
+    }
+
+    // Take field
+    pub fn take_lemma(&mut self) -> LemmaProto {
+        self.lemma.take().unwrap_or_else(|| LemmaProto::new())
+    }
+
+    // bytes value = 3;
+
+
+    pub fn get_value(&self) -> &[u8] {
+        &self.value
+    }
+    pub fn clear_value(&mut self) {
+        self.value.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_value(&mut self, v: ::std::vec::Vec<u8>) {
+        self.value = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_value(&mut self) -> &mut ::std::vec::Vec<u8> {
+        &mut self.value
+    }
+
+    // Take field
+    pub fn take_value(&mut self) -> ::std::vec::Vec<u8> {
+        ::std::mem::replace(&mut self.value, ::std::vec::Vec::new())
+    }
+
+    // int64 height = 4;
+
+
+    pub fn get_height(&self) -> i64 {
+        self.height
+    }
+    pub fn clear_height(&mut self) {
+        self.height = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_height(&mut self, v: i64) {
+        self.height = v;
+    }
+}
+
+impl ::protobuf::Message for ProofProto {
+    fn is_initialized(&self) -> bool {
+        for v in &self.lemma {
+            if !v.is_initialized() {
+                return false;
+            }
+        };
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_proto3_bytes_into(wire_type, is, &mut self.root_hash)?;
+                },
+                2 => {
+                    ::protobuf::rt::read_singular_message_into(wire_type, is, &mut self.lemma)?;
+                },
+                3 => {
+                    ::protobuf::rt::read_singular_proto3_bytes_into(wire_type, is, &mut self.value)?;
+                },
+                4 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_int64()?;
+                    self.height = tmp;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if !self.root_hash.is_empty() {
+            my_size += ::protobuf::rt::bytes_size(1, &self.root_hash);
+        }
+        if let Some(ref v) = self.lemma.as_ref() {
+            let len = v.compute_size();
+            my_size += 1 + ::protobuf::rt::compute_raw_varint32_size(len) + len;
+        }
+        if !self.value.is_empty() {
+            my_size += ::protobuf::rt::bytes_size(3, &self.value);
+        }
+        if self.height != 0 {
+            my_size += ::protobuf::rt::value_size(4, self.height, ::protobuf::wire_format::WireTypeVarint);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if !self.root_hash.is_empty() {
+            os.write_bytes(1, &self.root_hash)?;
+        }
+        if let Some(ref v) = self.lemma.as_ref() {
+            os.write_tag(2, ::protobuf::wire_format::WireTypeLengthDelimited)?;
+            os.write_raw_varint32(v.get_cached_size())?;
+            v.write_to_with_cached_sizes(os)?;
+        }
+        if !self.value.is_empty() {
+            os.write_bytes(3, &self.value)?;
+        }
+        if self.height != 0 {
+            os.write_int64(4, self.height)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: ::std::boxed::Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> ProofProto {
+        ProofProto::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            let mut fields = ::std::vec::Vec::new();
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBytes>(
+                "root_hash",
+                |m: &ProofProto| { &m.root_hash },
+                |m: &mut ProofProto| { &mut m.root_hash },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_singular_ptr_field_accessor::<_, ::protobuf::types::ProtobufTypeMessage<LemmaProto>>(
+                "lemma",
+                |m: &ProofProto| { &m.lemma },
+                |m: &mut ProofProto| { &mut m.lemma },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBytes>(
+                "value",
+                |m: &ProofProto| { &m.value },
+                |m: &mut ProofProto| { &mut m.value },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeInt64>(
+                "height",
+                |m: &ProofProto| { &m.height },
+                |m: &mut ProofProto| { &mut m.height },
+            ));
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<ProofProto>(
+                "ProofProto",
+                fields,
+                file_descriptor_proto()
+            )
+        })
+    }
+
+    fn default_instance() -> &'static ProofProto {
+        static instance: ::protobuf::rt::LazyV2<ProofProto> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(ProofProto::new)
+    }
+}
+
+impl ::protobuf::Clear for ProofProto {
+    fn clear(&mut self) {
+        self.root_hash.clear();
+        self.lemma.clear();
+        self.value.clear();
+        self.height = 0;
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for ProofProto {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for ProofProto {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct LemmaProto {
+    // message fields
+    pub node_hash: ::std::vec::Vec<u8>,
+    pub sibling_hash: ::protobuf::SingularPtrField<PositionedProto>,
+    pub sub_lemma: ::protobuf::SingularPtrField<LemmaProto>,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a LemmaProto {
+    fn default() -> &'a LemmaProto {
+        <LemmaProto as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl LemmaProto {
+    pub fn new() -> LemmaProto {
+        ::std::default::Default::default()
+    }
+
+    // bytes node_hash = 1;
+
+
+    pub fn get_node_hash(&self) -> &[u8] {
+        &self.node_hash
+    }
+    pub fn clear_node_hash(&mut self) {
+        self.node_hash.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_node_hash(&mut self, v: ::std::vec::Vec<u8>) {
+        self.node_hash = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_node_hash(&mut self) -> &mut ::std::vec::Vec<u8> {
+        &mut self.node_hash
+    }
+
+    // Take field
+    pub fn take_node_hash(&mut self) -> ::std::vec::Vec<u8> {
+        ::std::mem::replace(&mut self.node_hash, ::std::vec::Vec::new())
+    }
+
+    // .spaceproof.PositionedProto sibling_hash = 2;
+
+
+    pub fn get_sibling_hash(&self) -> &PositionedProto {
+        self.sibling_hash.as_ref().unwrap_or_else(|| <PositionedProto as ::protobuf::Message>::default_instance())
+    }
+    pub fn clear_sibling_hash(&mut self) {
+        self.sibling_hash.clear();
+    }
+
+    pub fn has_sibling_hash(&self) -> bool {
+        self.sibling_hash.is_some()
+    }
+
+    // Param is passed by value, moved
+    pub fn set_sibling_hash(&mut self, v: PositionedProto) {
+        self.sibling_hash = ::protobuf::SingularPtrField::some(v);
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_sibling_hash(&mut self) -> &mut PositionedProto {
+        if self.sibling_hash.is_none() {
+            self.sibling_hash.set_default();
+        }
+        self.sibling_hash.as_mut().unwrap()
+    }
+
+    // Take field
+    pub fn take_sibling_hash(&mut self) -> PositionedProto {
+        self.sibling_hash.take().unwrap_or_else(|| PositionedProto::new())
+    }
+
+    // .spaceproof.LemmaProto sub_lemma = 3;
+
+
+    pub fn get_sub_lemma(&self) -> &LemmaProto {
+        self.sub_lemma.as_ref().unwrap_or_else(|| <LemmaProto as ::protobuf::Message>::default_instance())
+    }
+    pub fn clear_sub_lemma(&mut self) {
+        self.sub_lemma.clear();
+    }
+
+    pub fn has_sub_lemma(&self) -> bool {
+        self.sub_lemma.is_some()
+    }
+
+    // Param is passed by value, moved
+    pub fn set_sub_lemma(&mut self, v: LemmaProto) {
+        self.sub_lemma = ::protobuf::SingularPtrField::some(v);
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_sub_lemma(&mut self) -> &mut LemmaProto {
+        if self.sub_lemma.is_none() {
+            self.sub_lemma.set_default();
+        }
+        self.sub_lemma.as_mut().unwrap()
+    }
+
+    // Take field
+    pub fn take_sub_lemma(&mut self) -> LemmaProto {
+        self.sub_lemma.take().unwrap_or_else(|| LemmaProto::new())
+    }
+}
+
+impl ::protobuf::Message for LemmaProto {
+    fn is_initialized(&self) -> bool {
+        for v in &self.sibling_hash {
+            if !v.is_initialized() {
+                return false;
+            }
+        };
+        for v in &self.sub_lemma {
+            if !v.is_initialized() {
+                return false;
+            }
+        };
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_proto3_bytes_into(wire_type, is, &mut self.node_hash)?;
+                },
+                2 => {
+                    ::protobuf::rt::read_singular_message_into(wire_type, is, &mut self.sibling_hash)?;
+                },
+                3 => {
+                    ::protobuf::rt::read_singular_message_into(wire_type, is, &mut self.sub_lemma)?;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if !self.node_hash.is_empty() {
+            my_size += ::protobuf::rt::bytes_size(1, &self.node_hash);
+        }
+        if let Some(ref v) = self.sibling_hash.as_ref() {
+            let len = v.compute_size();
+            my_size += 1 + ::protobuf::rt::compute_raw_varint32_size(len) + len;
+        }
+        if let Some(ref v) = self.sub_lemma.as_ref() {
+            let len = v.compute_size();
+            my_size += 1 + ::protobuf::rt::compute_raw_varint32_size(len) + len;
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if !self.node_hash.is_empty() {
+            os.write_bytes(1, &self.node_hash)?;
+        }
+        if let Some(ref v) = self.sibling_hash.as_ref() {
+            os.write_tag(2, ::protobuf::wire_format::WireTypeLengthDelimited)?;
+            os.write_raw_varint32(v.get_cached_size())?;
+            v.write_to_with_cached_sizes(os)?;
+        }
+        if let Some(ref v) = self.sub_lemma.as_ref() {
+            os.write_tag(3, ::protobuf::wire_format::WireTypeLengthDelimited)?;
+            os.write_raw_varint32(v.get_cached_size())?;
+            v.write_to_with_cached_sizes(os)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: ::std::boxed::Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> LemmaProto {
+        LemmaProto::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            let mut fields = ::std::vec::Vec::new();
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBytes>(
+                "node_hash",
+                |m: &LemmaProto| { &m.node_hash },
+                |m: &mut LemmaProto| { &mut m.node_hash },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_singular_ptr_field_accessor::<_, ::protobuf::types::ProtobufTypeMessage<PositionedProto>>(
+                "sibling_hash",
+                |m: &LemmaProto| { &m.sibling_hash },
+                |m: &mut LemmaProto| { &mut m.sibling_hash },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_singular_ptr_field_accessor::<_, ::protobuf::types::ProtobufTypeMessage<LemmaProto>>(
+                "sub_lemma",
+                |m: &LemmaProto| { &m.sub_lemma },
+                |m: &mut LemmaProto| { &mut m.sub_lemma },
+            ));
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<LemmaProto>(
+                "LemmaProto",
+                fields,
+                file_descriptor_proto()
+            )
+        })
+    }
+
+    fn default_instance() -> &'static LemmaProto {
+        static instance: ::protobuf::rt::LazyV2<LemmaProto> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(LemmaProto::new)
+    }
+}
+
+impl ::protobuf::Clear for LemmaProto {
+    fn clear(&mut self) {
+        self.node_hash.clear();
+        self.sibling_hash.clear();
+        self.sub_lemma.clear();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for LemmaProto {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for LemmaProto {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct PositionedProto {
+    // message oneof groups
+    pub value: ::std::option::Option<PositionedProto_oneof_value>,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a PositionedProto {
+    fn default() -> &'a PositionedProto {
+        <PositionedProto as ::protobuf::Message>::default_instance()
+    }
+}
+
+#[derive(Clone,PartialEq,Debug)]
+pub enum PositionedProto_oneof_value {
+    left(::std::vec::Vec<u8>),
+    right(::std::vec::Vec<u8>),
+}
+
+impl PositionedProto {
+    pub fn new() -> PositionedProto {
+        ::std::default::Default::default()
+    }
+
+    // bytes left = 1;
+
+
+    pub fn get_left(&self) -> &[u8] {
+        match self.value {
+            ::std::option::Option::Some(PositionedProto_oneof_value::left(ref v)) => v,
+            _ => &[],
+        }
+    }
+    pub fn clear_left(&mut self) {
+        self.value = ::std::option::Option::None;
+    }
+
+    pub fn has_left(&self) -> bool {
+        match self.value {
+            ::std::option::Option::Some(PositionedProto_oneof_value::left(..)) => true,
+            _ => false,
+        }
+    }
+
+    // Param is passed by value, moved
+    pub fn set_left(&mut self, v: ::std::vec::Vec<u8>) {
+        self.value = ::std::option::Option::Some(PositionedProto_oneof_value::left(v))
+    }
+
+    // Mutable pointer to the field.
+    pub fn mut_left(&mut self) -> &mut ::std::vec::Vec<u8> {
+        if let ::std::option::Option::Some(PositionedProto_oneof_value::left(_)) = self.value {
+        } else {
+            self.value = ::std::option::Option::Some(PositionedProto_oneof_value::left(::std::vec::Vec::new()));
+        }
+        match self.value {
+            ::std::option::Option::Some(PositionedProto_oneof_value::left(ref mut v)) => v,
+            _ => panic!(),
+        }
+    }
+
+    // Take field
+    pub fn take_left(&mut self) -> ::std::vec::Vec<u8> {
+        if self.has_left() {
+            match self.value.take() {
+                ::std::option::Option::Some(PositionedProto_oneof_value::left(v)) => v,
+                _ => panic!(),
+            }
+        } else {
+            ::std::vec::Vec::new()
+        }
+    }
+
+    // bytes right = 2;
+
+
+    pub fn get_right(&self) -> &[u8] {
+        match self.value {
+            ::std::option::Option::Some(PositionedProto_oneof_value::right(ref v)) => v,
+            _ => &[],
+        }
+    }
+    pub fn clear_right(&mut self) {
+        self.value = ::std::option::Option::None;
+    }
+
+    pub fn has_right(&self) -> bool {
+        match self.value {
+            ::std::option::Option::Some(PositionedProto_oneof_value::right(..)) => true,
+            _ => false,
+        }
+    }
+
+    // Param is passed by value, moved
+    pub fn set_right(&mut self, v: ::std::vec::Vec<u8>) {
+        self.value = ::std::option::Option::Some(PositionedProto_oneof_value::right(v))
+    }
+
+    // Mutable pointer to the field.
+    pub fn mut_right(&mut self) -> &mut ::std::vec::Vec<u8> {
+        if let ::std::option::Option::Some(PositionedProto_oneof_value::right(_)) = self.value {
+        } else {
+            self.value = ::std::option::Option::Some(PositionedProto_oneof_value::right(::std::vec::Vec::new()));
+        }
+        match self.value {
+            ::std::option::Option::Some(PositionedProto_oneof_value::right(ref mut v)) => v,
+            _ => panic!(),
+        }
+    }
+
+    // Take field
+    pub fn take_right(&mut self) -> ::std::vec::Vec<u8> {
+        if self.has_right() {
+            match self.value.take() {
+                ::std::option::Option::Some(PositionedProto_oneof_value::right(v)) => v,
+                _ => panic!(),
+            }
+        } else {
+            ::std::vec::Vec::new()
+        }
+    }
+}
+
+impl ::protobuf::Message for PositionedProto {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeLengthDelimited {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    self.value = ::std::option::Option::Some(PositionedProto_oneof_value::left(is.read_bytes()?));
+                },
+                2 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeLengthDelimited {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    self.value = ::std::option::Option::Some(PositionedProto_oneof_value::right(is.read_bytes()?));
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if let ::std::option::Option::Some(ref v) = self.value {
+            match v {
+                &PositionedProto_oneof_value::left(ref v) => {
+                    my_size += ::protobuf::rt::bytes_size(1, &v);
+                },
+                &PositionedProto_oneof_value::right(ref v) => {
+                    my_size += ::protobuf::rt::bytes_size(2, &v);
+                },
+            };
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if let ::std::option::Option::Some(ref v) = self.value {
+            match v {
+                &PositionedProto_oneof_value::left(ref v) => {
+                    os.write_bytes(1, v)?;
+                },
+                &PositionedProto_oneof_value::right(ref v) => {
+                    os.write_bytes(2, v)?;
+                },
+            };
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: ::std::boxed::Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> PositionedProto {
+        PositionedProto::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            let mut fields = ::std::vec::Vec::new();
+            fields.push(::protobuf::reflect::accessor::make_singular_bytes_accessor::<_>(
+                "left",
+                PositionedProto::has_left,
+                PositionedProto::get_left,
+            ));
+            fields.push(::protobuf::reflect::accessor::make_singular_bytes_accessor::<_>(
+                "right",
+                PositionedProto::has_right,
+                PositionedProto::get_right,
+            ));
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<PositionedProto>(
+                "PositionedProto",
+                fields,
+                file_descriptor_proto()
+            )
+        })
+    }
+
+    fn default_instance() -> &'static PositionedProto {
+        static instance: ::protobuf::rt::LazyV2<PositionedProto> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(PositionedProto::new)
+    }
+}
+
+impl ::protobuf::Clear for PositionedProto {
+    fn clear(&mut self) {
+        self.value = ::std::option::Option::None;
+        self.value = ::std::option::Option::None;
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for PositionedProto {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for PositionedProto {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
+    }
+}
+
+static file_descriptor_proto_data: &'static [u8] = b"\
+    \n\x10spaceproof.proto\x12\nspaceproof\"\x8f\x01\n\nProofProto\x12\x1d\n\
+    \troot_hash\x18\x01\x20\x01(\x0cR\x08rootHashB\0\x12.\n\x05lemma\x18\x02\
+    \x20\x01(\x0b2\x16.spaceproof.LemmaProtoR\x05lemmaB\0\x12\x16\n\x05value\
+    \x18\x03\x20\x01(\x0cR\x05valueB\0\x12\x18\n\x06height\x18\x04\x20\x01(\
+    \x03R\x06heightB\0:\0\"\xa6\x01\n\nLemmaProto\x12\x1d\n\tnode_hash\x18\
+    \x01\x20\x01(\x0cR\x08nodeHashB\0\x12@\n\x0csibling_hash\x18\x02\x20\x01\
+    (\x0b2\x1b.spaceproof.PositionedProtoR\x0bsiblingHashB\0\x125\n\tsub_lem\
+    ma\x18\x03\x20\x01(\x0b2\x16.spaceproof.LemmaProtoR\x08subLemmaB\0:\0\"N\
+    \n\x0fPositionedProto\x12\x16\n\x04left\x18\x01\x20\x01(\x0cH\0R\x04left\
+    B\0\x12\x18\n\x05right\x18\x02\x20\x01(\x0cH\0R\x05rightB\0B\x07\n\x05va\
+    lue:\0B\0b\x06proto3\
+";
+
+static file_descriptor_proto_lazy: ::protobuf::rt::LazyV2<::protobuf::descriptor::FileDescriptorProto> = ::protobuf::rt::LazyV2::INIT;
+
+fn parse_descriptor_proto() -> ::protobuf::descriptor::FileDescriptorProto {
+    ::protobuf::Message::parse_from_bytes(file_descriptor_proto_data).unwrap()
+}
+
+pub fn file_descriptor_proto() -> &'static ::protobuf::descriptor::FileDescriptorProto {
+    file_descriptor_proto_lazy.get(|| {
+        parse_descriptor_proto()
+    })
+}
@@ -4,13 +4,13 @@
 extern crate serde_json;
 
 // use ring::digest::{Algorithm, Context, SHA256};
-use super::digest::{Algorithm, Digest};
+use super::digest::{Algorithm, Digest, BLAKE2S_256, PEDERSEN_BN256};
 use super::hashutils::{HashUtils, Hashable, BitIterator};
 use super::merkletree::MerkleTree;
 use super::proof::Positioned;
 use time::PreciseTime;
 // static DIGEST: &Algorithm = &SHA256;
-static DIGEST: &Algorithm = &Algorithm{};
+static DIGEST: &Algorithm = &PEDERSEN_BN256;
 
 #[test]
 fn test_bit_iterator() {
@@ -135,6 +135,21 @@ fn test_valid_proof() {
     }
 }
 
+#[test]
+fn test_valid_proof_blake2s() {
+    let values = (1..10).map(|x| vec![x]).collect::<Vec<_>>();
+    let tree = MerkleTree::from_vec(&BLAKE2S_256, values.clone());
+    let root_hash = tree.root_hash();
+    assert_eq!(root_hash.len(), 32);
+    assert!(root_hash != MerkleTree::from_vec(DIGEST, values.clone()).root_hash());
+
+    for value in values {
+        let proof = tree.gen_proof(value).unwrap();
+        assert_eq!(proof.algorithm.id(), "blake2s-256");
+        assert!(proof.validate(&root_hash));
+    }
+}
+
 fn u8_to_str(buf :&[u8]) -> &str{
     match std::str::from_utf8(buf){
         Ok(v) => v,
@@ -269,6 +284,25 @@ fn test_nth_proof() {
     }
 }
 
+#[test]
+fn test_levels_roundtrip() {
+    // odd, even and power of two leaf counts carry nodes differently
+    for &count in &[1, 5, 6, 8] {
+        let values = (1..=count).map(|x| vec![x as u8]).collect::<Vec<_>>();
+        let tree = MerkleTree::from_vec(DIGEST, values.clone());
+        let levels = tree.levels();
+        assert_eq!(levels.len(), tree.height() as usize + 1);
+        assert_eq!(levels.last().unwrap(), &vec![tree.root_hash().clone()]);
+
+        let rebuilt = MerkleTree::from_levels(DIGEST, values.clone(), levels.clone()).unwrap();
+        assert_eq!(rebuilt, tree);
+
+        let mut corrupted = levels.clone();
+        corrupted[0][0] = vec![0xff];
+        assert!(MerkleTree::from_levels(DIGEST, values, corrupted).is_none());
+    }
+}
+
 #[test]
 fn test_mutate_proof_first_lemma() {
     let values = (1..10).map(|x| vec![x]).collect::<Vec<_>>();
@@ -404,3 +438,52 @@ fn test_serialize_proof_with_serde() {
         serde_json::from_str(&serialized).expect("deserialize proof")
     );
 }
+
+#[cfg(feature = "serialization-serde")]
+#[test]
+fn test_serialize_proof_as_json_and_binary() {
+    use super::proof::Proof;
+
+    let values = (1..10).map(|x| vec![x]).collect::<Vec<_>>();
+    let tree = MerkleTree::from_vec(DIGEST, values);
+    let proof = tree.gen_proof(vec![5]).unwrap();
+
+    let json = proof.to_json().unwrap();
+    assert!(json.contains("\"algorithm\":\"pedersen-bn256\""));
+    assert!(json.contains(&format!("\"root_hash\":\"{}\"", hex::encode(tree.root_hash()))));
+    let parsed = Proof::<Vec<u8>>::from_json(&json).unwrap();
+    assert_eq!(parsed, proof);
+    assert!(parsed.validate(tree.root_hash()));
+
+    let binary = proof.to_binary().unwrap();
+    assert!(binary.len() < json.len());
+    let parsed = Proof::<Vec<u8>>::from_binary(&binary).unwrap();
+    assert_eq!(parsed, proof);
+    assert_eq!(parsed.height, proof.height);
+    assert!(parsed.validate(tree.root_hash()));
+
+    let unknown = json.replace("pedersen-bn256", "SHA256");
+    assert!(Proof::<Vec<u8>>::from_json(&unknown).is_err());
+}
+
+#[cfg(feature = "serialization-protobuf")]
+#[test]
+fn test_serialize_proof_with_protobuf() {
+    use super::proof::Proof;
+
+    let values = (1..10).map(|x| vec![x]).collect::<Vec<_>>();
+    let tree = MerkleTree::from_vec(DIGEST, values);
+    let proof = tree.gen_proof(vec![5]).unwrap();
+
+    let bytes = proof.clone().write_to_bytes().expect("serialize proof");
+    let parsed = Proof::<Vec<u8>>::parse_from_bytes(&bytes, DIGEST).expect("deserialize proof").unwrap();
+    assert_eq!(parsed, proof);
+    assert_eq!(parsed.height, proof.height);
+    assert!(parsed.validate(tree.root_hash()));
+
+    for height in &[-1, ::std::i64::MIN] {
+        let mut proto = proof.clone().into_protobuf();
+        proto.set_height(*height);
+        assert!(Proof::<Vec<u8>>::from_protobuf(DIGEST, proto).is_none());
+    }
+}
@@ -0,0 +1,536 @@
+//! Multi-party computation of Groth16 parameters (phase 2).
+//!
+//! `generate` samples every secret of the parameters from one seed, and
+//! whoever knows the seed can forge proofs. In a ceremony, `delta` is instead
+//! the product of secrets contributed one after another: each participant
+//! reads the latest `MPCParameters`, calls `contribute`, publishes the result
+//! and forgets their secret. The parameters are sound as long as a single
+//! participant did.
+//!
+//! ```ignore
+//! let mut params = new_ceremony(depth)?;   // published as the initial file
+//! let hash = params.contribute(&mut OsRng::new()?);
+//! // ... more participants ...
+//! let hashes = params.verify(circuit, &initial)?;   // anyone, from the two files
+//! let params = params.into_params();       // used like `generate`'s
+//! ```
+//!
+//! Every contribution carries a proof of knowledge of its secret that is
+//! bound to the initial parameters and all contributions before it, so
+//! `verify` can check the whole transcript. It returns the hash of every
+//! contribution; participants look for the hash `contribute` returned to
+//! them.
+//!
+//! `new` samples `tau`, `alpha` and `beta` itself, so whoever runs it must be
+//! trusted to forget them. `from_phase1` takes them from a powers-of-tau
+//! ceremony instead, see `powersoftau`.
+//!
+//! ## File format
+//!
+//! Big endian, points uncompressed:
+//!
+//! ```text
+//! parameters      Parameters::write, with the current delta
+//! cs_hash         64 bytes, BLAKE2b of the initial parameters
+//! contributions   u32 count, then for each:
+//!   delta_after   G1, delta after the contribution
+//!   s, s_delta    G1, G1
+//!   r_delta       G2
+//!   transcript    64 bytes
+//! ```
+
+use std::io::{self, Read, Write};
+use std::sync::Arc;
+
+use bellman::groth16::{Parameters, Phase1, generate_parameters, generate_parameters_from_phase1};
+use bellman::Circuit;
+use blake2_rfc::blake2b::Blake2b;
+use byteorder::{BigEndian, ByteOrder, ReadBytesExt, WriteBytesExt};
+use ff::{Field, PrimeField};
+use pairing::{CurveAffine, CurveProjective, EncodedPoint, Engine, Wnaf};
+use rand::{ChaChaRng, Rng, SeedableRng};
+
+use error::{Error, Result};
+
+/// Groth16 parameters together with the transcript of the contributions to
+/// their `delta`.
+#[derive(Clone)]
+pub struct MPCParameters<E: Engine> {
+    params: Parameters<E>,
+    /// Hash of the initial parameters
+    cs_hash: [u8; 64],
+    contributions: Vec<PublicKey<E>>,
+}
+
+/// What a participant publishes about their secret `d`.
+#[derive(Clone)]
+struct PublicKey<E: Engine> {
+    /// `delta` of the parameters times `d`
+    delta_after: E::G1Affine,
+    /// A random `s` and `s * d`
+    s: E::G1Affine,
+    s_delta: E::G1Affine,
+    /// `r * d`, where `r` is derived from `transcript`
+    r_delta: E::G2Affine,
+    /// Hash of `cs_hash`, the contributions before and `s`, `s_delta`
+    transcript: [u8; 64],
+}
+
+impl<E: Engine> PartialEq for PublicKey<E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.delta_after == other.delta_after &&
+        self.s == other.s &&
+        self.s_delta == other.s_delta &&
+        self.r_delta == other.r_delta &&
+        &self.transcript[..] == &other.transcript[..]
+    }
+}
+
+/// Collects what is written into a BLAKE2b hash.
+pub(crate) struct HashWriter {
+    hasher: Blake2b,
+}
+
+impl HashWriter {
+    pub(crate) fn new() -> HashWriter {
+        HashWriter { hasher: Blake2b::new(64) }
+    }
+
+    pub(crate) fn into_hash(self) -> [u8; 64] {
+        let mut hash = [0u8; 64];
+        hash.copy_from_slice(self.hasher.finalize().as_ref());
+        hash
+    }
+}
+
+impl Write for HashWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.hasher.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+pub(crate) fn write_point<W: Write, G: CurveAffine>(mut writer: W, point: &G) -> io::Result<()> {
+    writer.write_all(point.into_uncompressed().as_ref())
+}
+
+pub(crate) fn read_point<R: Read, G: CurveAffine>(mut reader: R) -> io::Result<G> {
+    let mut repr = G::Uncompressed::empty();
+    reader.read_exact(repr.as_mut())?;
+    let point = repr.into_affine().map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    if point.is_zero() {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "point at infinity"));
+    }
+    Ok(point)
+}
+
+impl<E: Engine> PublicKey<E> {
+    fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        write_point(&mut writer, &self.delta_after)?;
+        write_point(&mut writer, &self.s)?;
+        write_point(&mut writer, &self.s_delta)?;
+        write_point(&mut writer, &self.r_delta)?;
+        writer.write_all(&self.transcript)
+    }
+
+    fn read<R: Read>(mut reader: R) -> io::Result<PublicKey<E>> {
+        let delta_after = read_point(&mut reader)?;
+        let s = read_point(&mut reader)?;
+        let s_delta = read_point(&mut reader)?;
+        let r_delta = read_point(&mut reader)?;
+        let mut transcript = [0u8; 64];
+        reader.read_exact(&mut transcript)?;
+
+        Ok(PublicKey { delta_after, s, s_delta, r_delta, transcript })
+    }
+
+    /// Hash identifying the contribution.
+    fn hash(&self) -> [u8; 64] {
+        let mut sink = HashWriter::new();
+        self.write(&mut sink).expect("hashing does not fail");
+        sink.into_hash()
+    }
+}
+
+/// A point of G2 nobody knows the discrete logarithm of. The `Rand` of G2
+/// picks a point on the curve by its x-coordinate and clears the cofactor,
+/// rather than multiplying the generator by a scalar derived from `digest`,
+/// which anyone could recompute.
+pub(crate) fn hash_to_g2<E: Engine>(digest: &[u8; 64]) -> E::G2 {
+    let seed: Vec<u32> = digest[..32].chunks(4).map(BigEndian::read_u32).collect();
+    ChaChaRng::from_seed(&seed).gen()
+}
+
+/// Whether `g1.1 / g1.0 == g2.1 / g2.0`.
+pub(crate) fn same_ratio<E: Engine>(g1: (E::G1Affine, E::G1Affine), g2: (E::G2Affine, E::G2Affine)) -> bool {
+    E::pairing(g1.0, g2.1) == E::pairing(g1.1, g2.0)
+}
+
+/// Random linear combinations of `v1` and `v2` with the same coefficients,
+/// which have the same ratio as every pair `(v1[i], v2[i])` if these share
+/// one, and else with overwhelming probability not.
+pub(crate) fn merge_pairs<G: CurveAffine, R: Rng>(v1: &[G], v2: &[G], rng: &mut R) -> (G, G) {
+    assert_eq!(v1.len(), v2.len());
+
+    let mut s = G::Projective::zero();
+    let mut sx = G::Projective::zero();
+    for (a, b) in v1.iter().zip(v2.iter()) {
+        let rho: G::Scalar = rng.gen();
+        s.add_assign(&a.mul(rho));
+        sx.add_assign(&b.mul(rho));
+    }
+
+    (s.into_affine(), sx.into_affine())
+}
+
+/// Multiplies every point by `scalar`.
+pub(crate) fn batch_exp<G: CurveAffine>(points: &[G], scalar: G::Scalar) -> Vec<G> {
+    let mut wnaf = Wnaf::new();
+    let mut wnaf = wnaf.scalar(scalar.into_repr());
+    let mut projective: Vec<G::Projective> = points.iter().map(|p| wnaf.base(p.into_projective())).collect();
+    G::Projective::batch_normalization(&mut projective);
+    projective.iter().map(|p| p.into_affine()).collect()
+}
+
+fn invalid(message: &str) -> Error {
+    Error::InvalidInput(message.to_string())
+}
+
+impl<E: Engine> MPCParameters<E> {
+    /// Initial parameters for `circuit`, with `delta` one.
+    pub fn new<C: Circuit<E>, R: Rng>(circuit: C, rng: &mut R) -> Result<MPCParameters<E>> {
+        let params = generate_parameters(
+            circuit,
+            E::G1::one(),
+            E::G2::one(),
+            rng.gen(),
+            rng.gen(),
+            E::Fr::one(),
+            E::Fr::one(),
+            rng.gen(),
+        )?;
+
+        MPCParameters::initial(params)
+    }
+
+    /// Initial parameters for `circuit` from the powers of a phase 1
+    /// ceremony, with `delta` one. Unlike `new`, nobody learns a secret and
+    /// anyone can recompute them.
+    pub fn from_phase1<C: Circuit<E>>(circuit: C, phase1: &Phase1<E>) -> Result<MPCParameters<E>> {
+        MPCParameters::initial(generate_parameters_from_phase1(circuit, phase1)?)
+    }
+
+    fn initial(params: Parameters<E>) -> Result<MPCParameters<E>> {
+        let mut sink = HashWriter::new();
+        params.write(&mut sink)?;
+
+        Ok(MPCParameters {
+            params,
+            cs_hash: sink.into_hash(),
+            contributions: vec![],
+        })
+    }
+
+    /// The parameters with every contribution so far.
+    pub fn params(&self) -> &Parameters<E> {
+        &self.params
+    }
+
+    pub fn into_params(self) -> Parameters<E> {
+        self.params
+    }
+
+    /// Number of contributions so far.
+    pub fn contributions(&self) -> usize {
+        self.contributions.len()
+    }
+
+    /// Hash of the initial parameters and every contribution, which names
+    /// the ceremony the parameters came from.
+    pub fn transcript_hash(&self) -> [u8; 64] {
+        let mut sink = HashWriter::new();
+        sink.write_all(&self.cs_hash).expect("hashing does not fail");
+        for pubkey in &self.contributions {
+            pubkey.write(&mut sink).expect("hashing does not fail");
+        }
+        sink.into_hash()
+    }
+
+    /// Transcript hash a contribution on top of these parameters would
+    /// commit to.
+    fn transcript(&self, s: &E::G1Affine, s_delta: &E::G1Affine) -> [u8; 64] {
+        let mut sink = HashWriter::new();
+        sink.write_all(&self.cs_hash).expect("hashing does not fail");
+        for pubkey in &self.contributions {
+            pubkey.write(&mut sink).expect("hashing does not fail");
+        }
+        write_point(&mut sink, s).expect("hashing does not fail");
+        write_point(&mut sink, s_delta).expect("hashing does not fail");
+        sink.into_hash()
+    }
+
+    /// Multiplies `delta` by a secret drawn from `rng` and returns the hash
+    /// of the contribution, which `verify` reports again. The secret is
+    /// dropped before returning.
+    pub fn contribute<R: Rng>(&mut self, rng: &mut R) -> [u8; 64] {
+        let secret: E::Fr = loop {
+            let secret: E::Fr = rng.gen();
+            if !secret.is_zero() {
+                break secret;
+            }
+        };
+
+        let s = rng.gen::<E::G1>().into_affine();
+        let s_delta = s.mul(secret).into_affine();
+        let transcript = self.transcript(&s, &s_delta);
+        let r_delta = hash_to_g2::<E>(&transcript).into_affine().mul(secret).into_affine();
+        let pubkey = PublicKey {
+            delta_after: self.params.vk.delta_g1.mul(secret).into_affine(),
+            s,
+            s_delta,
+            r_delta,
+            transcript,
+        };
+
+        let inverse = secret.inverse().expect("secret is not zero");
+        self.params.h = Arc::new(batch_exp(&self.params.h, inverse));
+        self.params.l = Arc::new(batch_exp(&self.params.l, inverse));
+        self.params.vk.delta_g1 = pubkey.delta_after;
+        self.params.vk.delta_g2 = self.params.vk.delta_g2.mul(secret).into_affine();
+
+        let hash = pubkey.hash();
+        self.contributions.push(pubkey);
+        hash
+    }
+
+    /// Checks that these parameters follow from `initial` by the recorded
+    /// contributions, and returns their hashes in order. `initial` must be
+    /// parameters for `circuit` as `new` makes them. Their points depend on
+    /// the secrets of whoever ran `new`, so only their layout is recomputed
+    /// from the circuit; `verify_from_phase1` recomputes all of them.
+    pub fn verify<C: Circuit<E>>(&self, circuit: C, initial: &MPCParameters<E>) -> Result<Vec<[u8; 64]>> {
+        let rng = &mut ::rand::OsRng::new()?;
+        let expected = generate_parameters(
+            circuit,
+            E::G1::one(),
+            E::G2::one(),
+            rng.gen(),
+            rng.gen(),
+            E::Fr::one(),
+            E::Fr::one(),
+            rng.gen(),
+        )?;
+        let params = &initial.params;
+        if params.vk.ic.len() != expected.vk.ic.len() ||
+            params.h.len() != expected.h.len() ||
+            params.l.len() != expected.l.len() ||
+            params.a.len() != expected.a.len() ||
+            params.b_g1.len() != expected.b_g1.len() ||
+            params.b_g2.len() != expected.b_g2.len()
+        {
+            return Err(invalid("the initial parameters do not fit the circuit"));
+        }
+
+        self.verify_contributions(initial)
+    }
+
+    /// Like `verify`, for a ceremony started by `from_phase1`, whose initial
+    /// parameters are derived again from `circuit` and `phase1`.
+    pub fn verify_from_phase1<C: Circuit<E>>(&self, circuit: C, phase1: &Phase1<E>) -> Result<Vec<[u8; 64]>> {
+        self.verify_contributions(&MPCParameters::from_phase1(circuit, phase1)?)
+    }
+
+    fn verify_contributions(&self, initial: &MPCParameters<E>) -> Result<Vec<[u8; 64]>> {
+        if !initial.contributions.is_empty() {
+            return Err(invalid("the initial parameters already have contributions"));
+        }
+        // `new` and `from_phase1` start with gamma and delta one
+        let vk = &initial.params.vk;
+        if vk.delta_g1 != E::G1Affine::one() || vk.delta_g2 != E::G2Affine::one() || vk.gamma_g2 != E::G2Affine::one() {
+            return Err(invalid("the initial parameters do not start from the generators"));
+        }
+        let mut sink = HashWriter::new();
+        initial.params.write(&mut sink)?;
+        if &sink.into_hash()[..] != &initial.cs_hash[..] || &self.cs_hash[..] != &initial.cs_hash[..] {
+            return Err(invalid("the parameters do not start from the initial parameters"));
+        }
+
+        let (before, after) = (&initial.params, &self.params);
+        if before.vk.alpha_g1 != after.vk.alpha_g1 ||
+            before.vk.beta_g1 != after.vk.beta_g1 ||
+            before.vk.beta_g2 != after.vk.beta_g2 ||
+            before.vk.gamma_g2 != after.vk.gamma_g2 ||
+            before.vk.ic != after.vk.ic ||
+            before.a != after.a ||
+            before.b_g1 != after.b_g1 ||
+            before.b_g2 != after.b_g2 ||
+            before.h.len() != after.h.len() ||
+            before.l.len() != after.l.len()
+        {
+            return Err(invalid("the parameters differ from the initial parameters beyond delta"));
+        }
+
+        // replay the transcript, one contribution at a time
+        let mut current = MPCParameters {
+            params: before.clone(),
+            cs_hash: initial.cs_hash,
+            contributions: vec![],
+        };
+        let mut hashes = vec![];
+        for (i, pubkey) in self.contributions.iter().enumerate() {
+            if &current.transcript(&pubkey.s, &pubkey.s_delta)[..] != &pubkey.transcript[..] {
+                return Err(Error::InvalidInput(format!("contribution {} has a wrong transcript", i)));
+            }
+            let r = hash_to_g2::<E>(&pubkey.transcript).into_affine();
+            // the participant knows the secret relating s and s_delta ...
+            if !same_ratio::<E>((pubkey.s, pubkey.s_delta), (r, pubkey.r_delta)) {
+                return Err(Error::InvalidInput(format!("contribution {} has an invalid proof of knowledge", i)));
+            }
+            // ... and multiplied delta by it
+            if !same_ratio::<E>((current.params.vk.delta_g1, pubkey.delta_after), (r, pubkey.r_delta)) {
+                return Err(Error::InvalidInput(format!("contribution {} does not follow the one before", i)));
+            }
+
+            current.params.vk.delta_g1 = pubkey.delta_after;
+            current.contributions.push(pubkey.clone());
+            hashes.push(pubkey.hash());
+        }
+
+        if after.vk.delta_g1 != current.params.vk.delta_g1 {
+            return Err(invalid("delta does not match the last contribution"));
+        }
+        if !same_ratio::<E>((E::G1Affine::one(), after.vk.delta_g1), (E::G2Affine::one(), after.vk.delta_g2)) {
+            return Err(invalid("delta differs in G1 and G2"));
+        }
+
+        // h and l were divided by delta
+        let rng = &mut ::rand::OsRng::new()?;
+        let h = merge_pairs(&before.h, &after.h, rng);
+        if !same_ratio::<E>(h, (after.vk.delta_g2, before.vk.delta_g2)) {
+            return Err(invalid("h is not divided by delta"));
+        }
+        let l = merge_pairs(&before.l, &after.l, rng);
+        if !same_ratio::<E>(l, (after.vk.delta_g2, before.vk.delta_g2)) {
+            return Err(invalid("l is not divided by delta"));
+        }
+
+        Ok(hashes)
+    }
+
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        self.params.write(&mut writer)?;
+        writer.write_all(&self.cs_hash)?;
+        writer.write_u32::<BigEndian>(self.contributions.len() as u32)?;
+        for pubkey in &self.contributions {
+            pubkey.write(&mut writer)?;
+        }
+
+        Ok(())
+    }
+
+    /// Reads parameters written by `write`. With `checked` false, points of
+    /// the parameters are not checked to be on the curve; only do that for
+    /// files produced locally.
+    pub fn read<R: Read>(mut reader: R, checked: bool) -> io::Result<MPCParameters<E>> {
+        let params = Parameters::read(&mut reader, checked)?;
+        let mut cs_hash = [0u8; 64];
+        reader.read_exact(&mut cs_hash)?;
+        let count = reader.read_u32::<BigEndian>()?;
+        let mut contributions = vec![];
+        for _ in 0..count {
+            contributions.push(PublicKey::read(&mut reader)?);
+        }
+
+        Ok(MPCParameters { params, cs_hash, contributions })
+    }
+}
+
+impl<E: Engine> PartialEq for MPCParameters<E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.params == other.params &&
+        &self.cs_hash[..] == &other.cs_hash[..] &&
+        self.contributions == other.contributions
+    }
+}
+
+#[test]
+fn test_hash_to_g2() {
+    use pairing::bn256::{Bn256, Fr, G2};
+
+    let digest = [7u8; 64];
+    let p = hash_to_g2::<Bn256>(&digest);
+    assert!(!p.is_zero());
+    assert!(p.into_affine().mul(Fr::char()).is_zero());
+
+    // not the generator times a scalar drawn from the same stream
+    let seed: Vec<u32> = digest[..32].chunks(4).map(BigEndian::read_u32).collect();
+    let k: Fr = ChaChaRng::from_seed(&seed).gen();
+    let mut q = G2::one();
+    q.mul_assign(k);
+    assert!(p != q);
+}
+
+#[test]
+fn test_mpc_ceremony() {
+    use pairing::bn256::Bn256;
+    use rand::XorShiftRng;
+    use spaceproof_merkle_tree::{self, Node, build_tree, new_ceremony, position_to_fr, verify_ceremony};
+
+    let rng = &mut XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+    let initial = new_ceremony(2).unwrap();
+    let mut params = initial.clone();
+    let first = params.contribute(rng);
+    let mut v = vec![];
+    params.write(&mut v).unwrap();
+    let mut params = MPCParameters::<Bn256>::read(&v[..], true).unwrap();
+    let second = params.contribute(rng);
+
+    let hashes = verify_ceremony(&params, &initial, 2).unwrap();
+    assert_eq!(hashes.len(), 2);
+    assert_eq!(&hashes[0][..], &first[..]);
+    assert_eq!(&hashes[1][..], &second[..]);
+    assert!(params.params().vk.delta_g1 != initial.params().vk.delta_g1);
+
+    // the final parameters prove and verify like generated ones
+    let seed = [1u32, 2, 3, 4];
+    let tree = build_tree((1..5u64).map(|i| Node::new(position_to_fr(i))).collect());
+    let proof = spaceproof_merkle_tree::prove(&seed, params.params(), &tree, 2).unwrap();
+    let pvk = ::bellman::groth16::prepare_verifying_key(&params.params().vk);
+    let root = spaceproof_merkle_tree::tree_root(&tree).unwrap();
+    assert!(spaceproof_merkle_tree::verify(&pvk, &proof, position_to_fr(3), 2, root).unwrap());
+
+    // a contribution that skips its proof of knowledge is rejected
+    let mut forged = params.clone();
+    {
+        let last = forged.contributions.last_mut().unwrap();
+        last.s_delta = last.s;
+    }
+    assert!(verify_ceremony(&forged, &initial, 2).is_err());
+
+    // so is changing delta without a contribution
+    let mut forged = params.clone();
+    forged.contributions.pop();
+    assert!(verify_ceremony(&forged, &initial, 2).is_err());
+
+    // and parameters of another ceremony
+    let other = new_ceremony(2).unwrap();
+    assert!(verify_ceremony(&params, &other, 2).is_err());
+
+    // a ceremony must start from delta one, not from parameters whose
+    // delta someone knows
+    let rogue = MPCParameters::initial(spaceproof_merkle_tree::generate(&seed, 2).unwrap()).unwrap();
+    let mut forged = rogue.clone();
+    forged.contribute(rng);
+    assert!(verify_ceremony(&forged, &rogue, 2).is_err());
+
+    // and from parameters for the circuit being verified
+    let other = new_ceremony(3).unwrap();
+    let mut forged = other.clone();
+    forged.contribute(rng);
+    verify_ceremony(&forged, &other, 3).unwrap();
+    assert!(verify_ceremony(&forged, &other, 2).is_err());
+}
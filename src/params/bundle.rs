@@ -0,0 +1,484 @@
+//! Parameters for several circuits and depths in one file.
+//!
+//! Every depth of the `SpaceProofMerkleTreeCircuit` has parameters of its
+//! own. A bundle keeps them together with the header of each, so a prover
+//! or verifier serving trees of several heights ships one file. Opening a
+//! `Bundle` only reads its index; the parameters of one circuit are read
+//! when they are asked for, and checked against their digest then. A
+//! verifying key bundle holds only the verifying keys, which is all a
+//! verifier needs, and is derived from a parameter bundle with
+//! `Bundle::write_verifying_keys`. `Registry` looks entries up in any number
+//! of bundles and keeps what was loaded. Entries are looked up by circuit
+//! and depth, so a bundle, and a registry, holds at most one entry for each
+//! pair, whatever hash its tree uses.
+//!
+//! ## File format
+//!
+//! All integers are big endian, like those of `Parameters::write`. The
+//! index is at the end, so bundles are written in one pass.
+//!
+//! ```text
+//! magic        8 bytes   "SPBUNDLE"
+//! version      u8        1
+//! kind         u8        0 for parameters, 1 for verifying keys
+//! entries      `Parameters::write` or `VerifyingKey::write`, or their
+//!              `write_encoded` if compressed, one after another
+//! index:
+//!   count      u32
+//!   count times:
+//!     header   circuit, depth, hash and transcript as in a parameter file
+//!     offset   u64       of the entry from the start of the file
+//!     length   u64
+//!     digest   64 bytes  BLAKE2b of the header fields and the entry
+//! index offset u64
+//! ```
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use bellman::groth16::{
+    Encoding,
+    MappedParameters,
+    Parameters,
+    PreparedVerifyingKey,
+    VerifyingKey,
+    prepare_verifying_key,
+};
+use blake2_rfc::blake2b::Blake2b;
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use pairing::bn256::Bn256;
+
+use error::{Error, Result};
+use super::{CircuitId, HashReader, Header, check_digest, invalid, write_params};
+
+pub const MAGIC: &'static [u8; 8] = b"SPBUNDLE";
+const VERSION: u8 = 1;
+
+/// What the entries of a bundle hold.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BundleKind {
+    Parameters,
+    VerifyingKeys,
+}
+
+/// Position and digest of one entry.
+#[derive(Clone, Debug)]
+struct Entry {
+    header: Header,
+    offset: u64,
+    length: u64,
+    digest: [u8; 64],
+}
+
+/// A hasher that has already absorbed the fields of `header`.
+fn hasher(header: &Header) -> io::Result<Blake2b> {
+    let mut fields = vec![];
+    header.write(&mut fields)?;
+    let mut hasher = Blake2b::new(64);
+    hasher.update(&fields);
+    Ok(hasher)
+}
+
+/// Counts and hashes what is written through it.
+struct HashWriter<'a, W: 'a> {
+    inner: &'a mut W,
+    hasher: Blake2b,
+    count: u64,
+}
+
+impl<'a, W: Write> Write for HashWriter<'a, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.hasher.update(&buf[..n]);
+        self.count += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Writes a bundle one entry at a time.
+pub struct BundleWriter<W: Write> {
+    writer: W,
+    kind: BundleKind,
+    position: u64,
+    entries: Vec<Entry>,
+}
+
+impl<W: Write> BundleWriter<W> {
+    pub fn new(mut writer: W, kind: BundleKind) -> io::Result<BundleWriter<W>> {
+        writer.write_all(MAGIC)?;
+        writer.write_u8(VERSION)?;
+        writer.write_u8(match kind {
+            BundleKind::Parameters => 0,
+            BundleKind::VerifyingKeys => 1,
+        })?;
+
+        Ok(BundleWriter { writer, kind, position: MAGIC.len() as u64 + 2, entries: vec![] })
+    }
+
+    /// Adds the parameters described by `header` to a parameter bundle.
+    pub fn add_parameters(&mut self, header: Header, params: &Parameters<Bn256>, encoding: Encoding) -> Result<()> {
+        self.expect(BundleKind::Parameters)?;
+        self.add_with(header, |writer| write_params(writer, params, encoding))
+    }
+
+    /// Adds the verifying key described by `header` to a verifying key bundle.
+    pub fn add_verifying_key(&mut self, header: Header, vk: &VerifyingKey<Bn256>, encoding: Encoding) -> Result<()> {
+        self.expect(BundleKind::VerifyingKeys)?;
+        self.add_with(header, |writer| match encoding {
+            Encoding::Uncompressed => vk.write(writer),
+            Encoding::Compressed => vk.write_encoded(writer, encoding),
+        })
+    }
+
+    /// Adds an entry copied from `reader`, which must hold parameters or a
+    /// verifying key as the kind of the bundle requires, e.g. the
+    /// parameters in a `ParameterFile` after its checked header.
+    pub fn add_raw<R: Read>(&mut self, header: Header, mut reader: R) -> Result<()> {
+        self.add_with(header, |writer| io::copy(&mut reader, writer).map(|_| ()))
+    }
+
+    fn expect(&self, kind: BundleKind) -> Result<()> {
+        if self.kind != kind {
+            return Err(Error::InvalidInput(format!("cannot add to a bundle of {:?}", self.kind)));
+        }
+
+        Ok(())
+    }
+
+    fn add_with<F>(&mut self, header: Header, write: F) -> Result<()>
+        where F: FnOnce(&mut HashWriter<W>) -> io::Result<()>
+    {
+        if self.entries.iter().any(|entry| same_slot(&entry.header.id, &header.id)) {
+            return Err(Error::InvalidInput(format!("the bundle already holds a {} of depth {}", header.id.circuit, header.id.depth)));
+        }
+
+        let mut writer = HashWriter { inner: &mut self.writer, hasher: hasher(&header)?, count: 0 };
+        write(&mut writer)?;
+        let mut digest = [0u8; 64];
+        digest.copy_from_slice(writer.hasher.finalize().as_bytes());
+
+        self.entries.push(Entry { header, offset: self.position, length: writer.count, digest });
+        self.position += writer.count;
+        Ok(())
+    }
+
+    /// Writes the index and returns the writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.writer.write_u32::<BigEndian>(self.entries.len() as u32)?;
+        for entry in &self.entries {
+            entry.header.write(&mut self.writer)?;
+            self.writer.write_u64::<BigEndian>(entry.offset)?;
+            self.writer.write_u64::<BigEndian>(entry.length)?;
+            self.writer.write_all(&entry.digest)?;
+        }
+        self.writer.write_u64::<BigEndian>(self.position)?;
+        self.writer.flush()?;
+
+        Ok(self.writer)
+    }
+}
+
+/// Whether `a` and `b` would be found by the same lookup.
+fn same_slot(a: &CircuitId, b: &CircuitId) -> bool {
+    a.circuit == b.circuit && a.depth == b.depth
+}
+
+/// The index of a bundle file, whose entries are read on demand.
+pub struct Bundle {
+    path: PathBuf,
+    kind: BundleKind,
+    entries: Vec<Entry>,
+}
+
+impl Bundle {
+    /// Reads the index of the bundle at `path`.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Bundle> {
+        let mut file = BufReader::new(File::open(&path)?);
+
+        let mut magic = [0u8; 8];
+        file.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid("not a parameter bundle").into());
+        }
+        if file.read_u8()? != VERSION {
+            return Err(invalid("unsupported bundle version").into());
+        }
+        let kind = match file.read_u8()? {
+            0 => BundleKind::Parameters,
+            1 => BundleKind::VerifyingKeys,
+            _ => return Err(invalid("unknown bundle kind").into()),
+        };
+
+        let end = file.seek(SeekFrom::End(-8))?;
+        let index = file.read_u64::<BigEndian>()?;
+        if index < MAGIC.len() as u64 + 2 || index > end {
+            return Err(invalid("malformed bundle index offset").into());
+        }
+        file.seek(SeekFrom::Start(index))?;
+
+        let count = file.read_u32::<BigEndian>()?;
+        let mut entries: Vec<Entry> = vec![];
+        for _ in 0..count {
+            let header = Header::read(&mut file)?;
+            let offset = file.read_u64::<BigEndian>()?;
+            let length = file.read_u64::<BigEndian>()?;
+            let mut digest = [0u8; 64];
+            file.read_exact(&mut digest)?;
+            if offset.checked_add(length).map_or(true, |e| e > index) {
+                return Err(invalid("a bundle entry lies outside the file").into());
+            }
+            if entries.iter().any(|entry| same_slot(&entry.header.id, &header.id)) {
+                return Err(invalid("the bundle holds a circuit and depth twice").into());
+            }
+            entries.push(Entry { header, offset, length, digest });
+        }
+
+        Ok(Bundle { path: path.as_ref().to_path_buf(), kind, entries })
+    }
+
+    pub fn kind(&self) -> BundleKind {
+        self.kind
+    }
+
+    /// Headers of the entries, in the order they were added.
+    pub fn headers(&self) -> Vec<&Header> {
+        self.entries.iter().map(|entry| &entry.header).collect()
+    }
+
+    /// The header of the entry for `circuit` at `depth`, if there is one.
+    pub fn find(&self, circuit: &str, depth: usize) -> Option<&Header> {
+        self.entry(circuit, depth).ok().map(|entry| &entry.header)
+    }
+
+    fn entry(&self, circuit: &str, depth: usize) -> Result<&Entry> {
+        self.entries.iter()
+            .find(|entry| entry.header.id.circuit == circuit && entry.header.id.depth == depth)
+            .ok_or(Error::InvalidInput(format!("the bundle holds no {} of depth {}", circuit, depth)))
+    }
+
+    /// A reader over `entry` that hashes what is read.
+    fn reader(&self, entry: &Entry) -> Result<HashReader<io::Take<BufReader<File>>>> {
+        let mut file = BufReader::new(File::open(&self.path)?);
+        file.seek(SeekFrom::Start(entry.offset))?;
+
+        Ok(HashReader { inner: file.take(entry.length), hasher: hasher(&entry.header)?, count: 0 })
+    }
+
+    /// Hashes the rest of the entry and checks the digest.
+    fn finish(mut reader: HashReader<io::Take<BufReader<File>>>, entry: &Entry) -> Result<()> {
+        io::copy(&mut reader, &mut io::sink())?;
+        if reader.count != entry.length {
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "the bundle is truncated").into());
+        }
+        check_digest(reader, &entry.digest)?;
+
+        Ok(())
+    }
+
+    /// Loads the parameters for `circuit` at `depth`. With `checked` false,
+    /// uncompressed points are not checked to be on the curve.
+    pub fn parameters(&self, circuit: &str, depth: usize, checked: bool) -> Result<Parameters<Bn256>> {
+        if self.kind != BundleKind::Parameters {
+            return Err(Error::InvalidInput("the bundle only holds verifying keys".to_string()));
+        }
+        let entry = self.entry(circuit, depth)?;
+        let mut reader = self.reader(entry)?;
+        let params = Parameters::read(&mut reader, checked)?;
+        Self::finish(reader, entry)?;
+
+        Ok(params)
+    }
+
+    /// Maps the parameters for `circuit` at `depth` into memory after
+    /// checking their digest, see `MappedParameters`.
+    pub fn map(&self, circuit: &str, depth: usize) -> Result<MappedParameters<Bn256>> {
+        if self.kind != BundleKind::Parameters {
+            return Err(Error::InvalidInput("the bundle only holds verifying keys".to_string()));
+        }
+        let entry = self.entry(circuit, depth)?;
+        Self::finish(self.reader(entry)?, entry)?;
+
+        Ok(MappedParameters::open_at(&self.path, entry.offset, false)?)
+    }
+
+    /// Loads the verifying key for `circuit` at `depth`, from either kind
+    /// of bundle.
+    pub fn verifying_key(&self, circuit: &str, depth: usize) -> Result<VerifyingKey<Bn256>> {
+        self.read_vk(self.entry(circuit, depth)?)
+    }
+
+    fn read_vk(&self, entry: &Entry) -> Result<VerifyingKey<Bn256>> {
+        let mut reader = self.reader(entry)?;
+        // the verifying key is the first part of the parameters
+        let vk = VerifyingKey::read(&mut reader)?;
+        Self::finish(reader, entry)?;
+
+        Ok(vk)
+    }
+
+    /// Writes a verifying key bundle with the keys of every entry.
+    pub fn write_verifying_keys<W: Write>(&self, writer: W, encoding: Encoding) -> Result<W> {
+        let mut keys = BundleWriter::new(writer, BundleKind::VerifyingKeys)?;
+        for entry in &self.entries {
+            keys.add_verifying_key(entry.header.clone(), &self.read_vk(entry)?, encoding)?;
+        }
+
+        Ok(keys.finish()?)
+    }
+}
+
+/// Parameters and verifying keys of several bundles, looked up by circuit
+/// and depth and loaded once.
+pub struct Registry {
+    bundles: Vec<Bundle>,
+    params: Mutex<HashMap<(String, usize), Arc<Parameters<Bn256>>>>,
+    keys: Mutex<HashMap<(String, usize), Arc<PreparedVerifyingKey<Bn256>>>>,
+}
+
+impl Registry {
+    pub fn new() -> Registry {
+        Registry {
+            bundles: vec![],
+            params: Mutex::new(HashMap::new()),
+            keys: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Adds the entries of `bundle`, none of which may already be known.
+    pub fn add(&mut self, bundle: Bundle) -> Result<()> {
+        for header in bundle.headers() {
+            if self.ids().iter().any(|id| same_slot(id, &header.id)) {
+                return Err(Error::InvalidInput(format!("the registry already holds a {} of depth {}", header.id.circuit, header.id.depth)));
+            }
+        }
+        self.bundles.push(bundle);
+
+        Ok(())
+    }
+
+    /// Every circuit of every bundle.
+    pub fn ids(&self) -> Vec<&CircuitId> {
+        self.bundles.iter().flat_map(|b| b.entries.iter().map(|entry| &entry.header.id)).collect()
+    }
+
+    fn bundle(&self, circuit: &str, depth: usize, kind: Option<BundleKind>) -> Result<&Bundle> {
+        self.bundles.iter()
+            .find(|b| kind.map_or(true, |kind| b.kind == kind) && b.find(circuit, depth).is_some())
+            .ok_or(Error::InvalidInput(format!("no bundle holds the {} of depth {}", circuit, depth)))
+    }
+
+    /// The parameters for `circuit` at `depth`, loaded on first use.
+    pub fn parameters(&self, circuit: &str, depth: usize) -> Result<Arc<Parameters<Bn256>>> {
+        let mut params = self.params.lock().expect("the registry lock is not poisoned");
+        let key = (circuit.to_string(), depth);
+        if let Some(p) = params.get(&key) {
+            return Ok(p.clone());
+        }
+
+        let p = Arc::new(self.bundle(circuit, depth, Some(BundleKind::Parameters))?.parameters(circuit, depth, false)?);
+        params.insert(key, p.clone());
+        Ok(p)
+    }
+
+    /// The prepared verifying key for `circuit` at `depth`, loaded on first
+    /// use from a bundle of either kind.
+    pub fn verifying_key(&self, circuit: &str, depth: usize) -> Result<Arc<PreparedVerifyingKey<Bn256>>> {
+        let mut keys = self.keys.lock().expect("the registry lock is not poisoned");
+        let key = (circuit.to_string(), depth);
+        if let Some(pvk) = keys.get(&key) {
+            return Ok(pvk.clone());
+        }
+
+        let vk = self.bundle(circuit, depth, None)?.verifying_key(circuit, depth)?;
+        let pvk = Arc::new(prepare_verifying_key(&vk));
+        keys.insert(key, pvk.clone());
+        Ok(pvk)
+    }
+}
+
+#[test]
+fn test_bundle() {
+    use std::env;
+    use std::fs;
+
+    use super::{MERKLE_TREE_CIRCUIT, ParameterFile};
+    use merkle::digest::BLAKE2S_256;
+    use spaceproof_merkle_tree::generate;
+
+    let p1 = generate(&[1, 2, 3, 4], 1).unwrap();
+    let p2 = generate(&[1, 2, 3, 4], 2).unwrap();
+    let h1 = Header { id: CircuitId::merkle_tree(1), transcript: None };
+    let h2 = Header { id: CircuitId::merkle_tree(2), transcript: Some([3u8; 64]) };
+
+    let dir = env::temp_dir();
+    let path = dir.join(format!("spaceproof-test-{}.bundle", ::std::process::id()));
+    let vk_path = dir.join(format!("spaceproof-test-vk-{}.bundle", ::std::process::id()));
+
+    let mut writer = BundleWriter::new(vec![], BundleKind::Parameters).unwrap();
+    writer.add_parameters(h1.clone(), &p1, Encoding::Uncompressed).unwrap();
+    assert!(writer.add_parameters(h1.clone(), &p1, Encoding::Uncompressed).is_err());
+    assert!(writer.add_verifying_key(h2.clone(), &p2.vk, Encoding::Uncompressed).is_err());
+    // nor twice with different hashes, which lookups do not tell apart
+    let mut blake = h1.clone();
+    blake.id.hash = &BLAKE2S_256;
+    assert!(writer.add_parameters(blake, &p1, Encoding::Uncompressed).is_err());
+    // entries can be copied out of a parameter file
+    let mut file = vec![];
+    ParameterFile { header: h2.clone(), params: p2.clone() }.write(&mut file, Encoding::Compressed).unwrap();
+    let file_path = dir.join(format!("spaceproof-test-bundle-entry-{}.bin", ::std::process::id()));
+    fs::write(&file_path, &file).unwrap();
+    let (header, offset) = ParameterFile::check(&file_path).unwrap();
+    writer.add_raw(header, &file[offset as usize..]).unwrap();
+    fs::remove_file(&file_path).unwrap();
+    let v = writer.finish().unwrap();
+    fs::write(&path, &v).unwrap();
+
+    let bundle = Bundle::open(&path).unwrap();
+    assert_eq!(bundle.kind(), BundleKind::Parameters);
+    assert_eq!(bundle.headers(), vec![&h1, &h2]);
+    assert_eq!(bundle.find(MERKLE_TREE_CIRCUIT, 2), Some(&h2));
+    assert!(bundle.find(MERKLE_TREE_CIRCUIT, 3).is_none());
+    assert!(bundle.parameters(MERKLE_TREE_CIRCUIT, 1, true).unwrap() == p1);
+    assert!(bundle.parameters(MERKLE_TREE_CIRCUIT, 2, true).unwrap() == p2);
+    assert!(bundle.map(MERKLE_TREE_CIRCUIT, 2).unwrap().vk() == &p2.vk);
+    assert!(bundle.parameters(MERKLE_TREE_CIRCUIT, 3, true).is_err());
+
+    // the companion bundle holds the verifying keys alone
+    bundle.write_verifying_keys(File::create(&vk_path).unwrap(), Encoding::Compressed).unwrap();
+    let keys = Bundle::open(&vk_path).unwrap();
+    assert_eq!(keys.kind(), BundleKind::VerifyingKeys);
+    assert_eq!(keys.headers(), vec![&h1, &h2]);
+    assert!(keys.verifying_key(MERKLE_TREE_CIRCUIT, 1).unwrap() == p1.vk);
+    assert!(keys.parameters(MERKLE_TREE_CIRCUIT, 1, false).is_err());
+    assert!(fs::metadata(&vk_path).unwrap().len() * 10 < v.len() as u64);
+
+    let mut registry = Registry::new();
+    registry.add(Bundle::open(&vk_path).unwrap()).unwrap();
+    assert!(registry.parameters(MERKLE_TREE_CIRCUIT, 2).is_err());
+    assert!(registry.verifying_key(MERKLE_TREE_CIRCUIT, 2).is_ok());
+    assert!(registry.add(Bundle::open(&path).unwrap()).is_err());
+    let mut registry = Registry::new();
+    registry.add(Bundle::open(&path).unwrap()).unwrap();
+    let p = registry.parameters(MERKLE_TREE_CIRCUIT, 2).unwrap();
+    assert!(*p == p2);
+    assert!(Arc::ptr_eq(&p, &registry.parameters(MERKLE_TREE_CIRCUIT, 2).unwrap()));
+    assert_eq!(registry.ids().len(), 2);
+
+    // a corrupt entry is found when it is loaded
+    let mut corrupt = v.clone();
+    corrupt[20] ^= 1;
+    fs::write(&path, &corrupt).unwrap();
+    let bundle = Bundle::open(&path).unwrap();
+    assert!(bundle.parameters(MERKLE_TREE_CIRCUIT, 1, false).is_err());
+    assert!(bundle.verifying_key(MERKLE_TREE_CIRCUIT, 1).is_err());
+
+    fs::remove_file(&path).unwrap();
+    fs::remove_file(&vk_path).unwrap();
+}
@@ -0,0 +1,349 @@
+//! Self-describing parameter files.
+//!
+//! Groth16 parameters as written by `Parameters::write` say nothing about the
+//! circuit they belong to, so parameters for another tree depth are only
+//! noticed once a proof fails to verify. A `ParameterFile` puts a header in
+//! front of them naming the circuit, its depth and hash, and the ceremony
+//! they came from, and a BLAKE2b digest over the header and the parameters,
+//! which is checked whenever the file is read.
+//!
+//! ## File format
+//!
+//! All integers are big endian, like those of `Parameters::write`.
+//!
+//! ```text
+//! magic        8 bytes   "SPPARAMS"
+//! version      u8        1
+//! circuit      u8 length, then the circuit name, e.g. "spaceproof-merkle-tree"
+//! depth        u32
+//! hash         u8 length, then the `Algorithm::id` of the tree hash
+//! transcript   u8 flag, then 64 bytes if it is 1: `MPCParameters::transcript_hash`
+//! length       u64       length of the parameters
+//! digest       64 bytes  BLAKE2b of everything above and the parameters
+//! parameters   `length` bytes, `Parameters::write` or `write_encoded`
+//! ```
+
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufReader, Cursor, Read, Write};
+use std::path::Path;
+
+use bellman::groth16::{Encoding, Parameters};
+use blake2_rfc::blake2b::Blake2b;
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use pairing::bn256::Bn256;
+
+use error::{Error, Result};
+use merkle::digest::{Algorithm, PEDERSEN_BN256};
+
+pub mod bundle;
+
+pub const MAGIC: &'static [u8; 8] = b"SPPARAMS";
+const VERSION: u8 = 1;
+
+/// Name of the `SpaceProofMerkleTreeCircuit` in circuit ids.
+pub const MERKLE_TREE_CIRCUIT: &'static str = "spaceproof-merkle-tree";
+
+/// The circuit, and its size, parameters were generated for.
+#[derive(Clone, Debug)]
+pub struct CircuitId {
+    pub circuit: String,
+    pub depth: usize,
+    pub hash: &'static Algorithm,
+}
+
+impl CircuitId {
+    /// The `SpaceProofMerkleTreeCircuit` for trees of height `depth`.
+    pub fn merkle_tree(depth: usize) -> CircuitId {
+        CircuitId {
+            circuit: MERKLE_TREE_CIRCUIT.to_string(),
+            depth,
+            hash: &PEDERSEN_BN256,
+        }
+    }
+}
+
+impl PartialEq for CircuitId {
+    fn eq(&self, other: &Self) -> bool {
+        self.circuit == other.circuit &&
+        self.depth == other.depth &&
+        self.hash.id() == other.hash.id()
+    }
+}
+
+impl Eq for CircuitId {}
+
+impl fmt::Display for CircuitId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} of depth {} over {}", self.circuit, self.depth, self.hash.id())
+    }
+}
+
+/// What a parameter file records about its parameters.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Header {
+    pub id: CircuitId,
+    /// `MPCParameters::transcript_hash` of the ceremony the parameters came
+    /// from, if they came from one
+    pub transcript: Option<[u8; 64]>,
+}
+
+/// Groth16 parameters of the `SpaceProofMerkleTreeCircuit` with their header.
+#[derive(Clone)]
+pub struct ParameterFile {
+    pub header: Header,
+    pub params: Parameters<Bn256>,
+}
+
+/// Hashes what is read through it into a BLAKE2b hash.
+struct HashReader<R> {
+    inner: R,
+    hasher: Blake2b,
+    count: u64,
+}
+
+impl<R: Read> Read for HashReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.hasher.update(&buf[..n]);
+        self.count += n as u64;
+        Ok(n)
+    }
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn write_str<W: Write>(mut writer: W, s: &str) -> io::Result<()> {
+    if s.len() > u8::max_value() as usize {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "name is longer than 255 bytes"));
+    }
+    writer.write_u8(s.len() as u8)?;
+    writer.write_all(s.as_bytes())
+}
+
+fn read_str<R: Read>(mut reader: R) -> io::Result<String> {
+    let mut s = vec![0u8; reader.read_u8()? as usize];
+    reader.read_exact(&mut s)?;
+    String::from_utf8(s).map_err(|_| invalid("name is not UTF-8"))
+}
+
+impl Header {
+    /// Fails unless the parameters are for the circuit `id`.
+    pub fn check(&self, id: &CircuitId) -> Result<()> {
+        if &self.id != id {
+            return Err(Error::InvalidInput(format!("the parameters are for the {}, not the {}", self.id, id)));
+        }
+
+        Ok(())
+    }
+
+    /// Writes the fields from `circuit` to `transcript`.
+    fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        write_str(&mut writer, &self.id.circuit)?;
+        writer.write_u32::<BigEndian>(self.id.depth as u32)?;
+        write_str(&mut writer, self.id.hash.id())?;
+        match self.transcript {
+            Some(ref hash) => {
+                writer.write_u8(1)?;
+                writer.write_all(hash)
+            },
+            None => writer.write_u8(0),
+        }
+    }
+
+    fn read<R: Read>(mut reader: R) -> io::Result<Header> {
+        let circuit = read_str(&mut reader)?;
+        let depth = reader.read_u32::<BigEndian>()? as usize;
+        let hash = Algorithm::from_id(&read_str(&mut reader)?).ok_or(invalid("unknown tree hash"))?;
+        let transcript = match reader.read_u8()? {
+            0 => None,
+            1 => {
+                let mut hash = [0u8; 64];
+                reader.read_exact(&mut hash)?;
+                Some(hash)
+            },
+            _ => return Err(invalid("malformed transcript flag")),
+        };
+
+        Ok(Header { id: CircuitId { circuit, depth, hash }, transcript })
+    }
+}
+
+/// Writes bare parameters, without a header of their own unless they are
+/// compressed, so uncompressed parameters keep the layout older readers
+/// expect.
+fn write_params<W: Write>(writer: W, params: &Parameters<Bn256>, encoding: Encoding) -> io::Result<()> {
+    match encoding {
+        Encoding::Uncompressed => params.write(writer),
+        Encoding::Compressed => params.write_encoded(writer, encoding),
+    }
+}
+
+/// Reads the header, parameter length and digest of a file.
+fn read_header<R: Read>(reader: &mut HashReader<R>) -> io::Result<(Header, u64, [u8; 64])> {
+    let mut magic = [0u8; 8];
+    reader.read_exact(&mut magic)?;
+    if &magic != MAGIC {
+        return Err(invalid("not a parameter file"));
+    }
+    if reader.read_u8()? != VERSION {
+        return Err(invalid("unsupported parameter file version"));
+    }
+    let header = Header::read(&mut *reader)?;
+    let length = reader.read_u64::<BigEndian>()?;
+    // the digest does not cover itself
+    let mut digest = [0u8; 64];
+    reader.inner.read_exact(&mut digest)?;
+
+    Ok((header, length, digest))
+}
+
+fn check_digest<R>(reader: HashReader<R>, digest: &[u8; 64]) -> io::Result<()> {
+    if reader.hasher.finalize().as_bytes() != &digest[..] {
+        return Err(invalid("the parameter file is corrupt, its digest does not match"));
+    }
+
+    Ok(())
+}
+
+impl ParameterFile {
+    /// Parameters of the circuit `id` that did not come from a ceremony.
+    pub fn new(id: CircuitId, params: Parameters<Bn256>) -> ParameterFile {
+        ParameterFile { header: Header { id, transcript: None }, params }
+    }
+
+    pub fn write<W: Write>(&self, mut writer: W, encoding: Encoding) -> io::Result<()> {
+        let mut header = MAGIC.to_vec();
+        header.push(VERSION);
+        self.header.write(&mut header)?;
+        let mut params = vec![];
+        write_params(&mut params, &self.params, encoding)?;
+        header.write_u64::<BigEndian>(params.len() as u64)?;
+
+        let mut hasher = Blake2b::new(64);
+        hasher.update(&header);
+        hasher.update(&params);
+
+        writer.write_all(&header)?;
+        writer.write_all(hasher.finalize().as_bytes())?;
+        writer.write_all(&params)
+    }
+
+    /// Reads a file written by `write` and checks its digest. With `checked`
+    /// false, uncompressed points are not checked to be on the curve.
+    pub fn read<R: Read>(reader: R, checked: bool) -> Result<ParameterFile> {
+        Self::read_if(reader, checked, |_| Ok(()))
+    }
+
+    /// Like `read`, but fails before loading the parameters if they are not
+    /// for the circuit `id`.
+    pub fn read_for<R: Read>(reader: R, id: &CircuitId, checked: bool) -> Result<ParameterFile> {
+        Self::read_if(reader, checked, |header| header.check(id))
+    }
+
+    fn read_if<R: Read, F>(reader: R, checked: bool, accept: F) -> Result<ParameterFile>
+        where F: FnOnce(&Header) -> Result<()>
+    {
+        let mut reader = HashReader { inner: reader, hasher: Blake2b::new(64), count: 0 };
+        let (header, length, digest) = read_header(&mut reader)?;
+        accept(&header)?;
+
+        let params = {
+            let mut body = (&mut reader).take(length);
+            let params = Parameters::read(&mut body, checked)?;
+            if body.limit() != 0 {
+                return Err(invalid("the parameters are shorter than recorded").into());
+            }
+            params
+        };
+        check_digest(reader, &digest)?;
+
+        Ok(ParameterFile { header, params })
+    }
+
+    /// Checks the digest of the file at `path` without loading the
+    /// parameters, and returns its header and the offset of the parameters,
+    /// e.g. for `MappedParameters::open_at`.
+    pub fn check<P: AsRef<Path>>(path: P) -> Result<(Header, u64)> {
+        let file = File::open(path)?;
+        let mut reader = HashReader { inner: BufReader::new(file), hasher: Blake2b::new(64), count: 0 };
+        let (header, length, digest) = read_header(&mut reader)?;
+        let offset = reader.count + digest.len() as u64;
+
+        if io::copy(&mut (&mut reader).take(length), &mut io::sink())? != length {
+            return Err(Error::Io(io::Error::new(io::ErrorKind::UnexpectedEof, "the parameter file is truncated")));
+        }
+        check_digest(reader, &digest)?;
+
+        Ok((header, offset))
+    }
+}
+
+/// Reads either a `ParameterFile` or bare parameters as written by
+/// `Parameters::write`, which have no header to return.
+pub fn read_parameters<R: Read>(mut reader: R, checked: bool) -> Result<(Option<Header>, Parameters<Bn256>)> {
+    let mut magic = [0u8; 8];
+    reader.read_exact(&mut magic)?;
+    let reader = Cursor::new(magic).chain(reader);
+
+    if &magic == MAGIC {
+        let file = ParameterFile::read(reader, checked)?;
+        Ok((Some(file.header), file.params))
+    } else {
+        Ok((None, Parameters::read(reader, checked)?))
+    }
+}
+
+#[test]
+fn test_parameter_file() {
+    use spaceproof_merkle_tree::generate;
+
+    let params = generate(&[1, 2, 3, 4], 2).unwrap();
+    let mut file = ParameterFile::new(CircuitId::merkle_tree(2), params.clone());
+    file.header.transcript = Some([7u8; 64]);
+
+    for &encoding in &[Encoding::Uncompressed, Encoding::Compressed] {
+        let mut v = vec![];
+        file.write(&mut v, encoding).unwrap();
+
+        let read = ParameterFile::read(&v[..], false).unwrap();
+        assert_eq!(read.header, file.header);
+        assert!(read.params == params);
+        assert!(ParameterFile::read_for(&v[..], &CircuitId::merkle_tree(2), false).is_ok());
+        let (header, read) = read_parameters(&v[..], false).unwrap();
+        assert_eq!(header, Some(file.header.clone()));
+        assert!(read == params);
+
+        // parameters for another depth are refused before they are loaded
+        match ParameterFile::read_for(&v[..200], &CircuitId::merkle_tree(3), false) {
+            Err(Error::InvalidInput(_)) => {},
+            _ => panic!("parameters of another depth were accepted"),
+        }
+
+        // corruption anywhere is caught by the digest
+        for &i in &[12, v.len() - 1] {
+            let mut corrupt = v.clone();
+            corrupt[i] ^= 1;
+            assert!(ParameterFile::read(&corrupt[..], false).is_err());
+        }
+        assert!(ParameterFile::read(&v[..v.len() - 1], false).is_err());
+
+        let path = ::std::env::temp_dir().join(format!("spaceproof-params-{}-{:?}.bin", ::std::process::id(), encoding));
+        ::std::fs::write(&path, &v).unwrap();
+        let (header, offset) = ParameterFile::check(&path).unwrap();
+        assert_eq!(header, file.header);
+        assert!(Parameters::<Bn256>::read(&v[offset as usize..], false).unwrap() == params);
+        ::std::fs::write(&path, &v[..v.len() - 1]).unwrap();
+        assert!(ParameterFile::check(&path).is_err());
+        ::std::fs::remove_file(&path).unwrap();
+    }
+
+    // bare parameters are still read
+    let mut v = vec![];
+    params.write(&mut v).unwrap();
+    let (header, read) = read_parameters(&v[..], false).unwrap();
+    assert!(header.is_none());
+    assert!(read == params);
+}
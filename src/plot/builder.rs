@@ -0,0 +1,385 @@
+//! Resumable construction of Merkle plots.
+//!
+//! `MerklePlot::create` labels and hashes a whole plot in one go, so a crash
+//! hours into a large plot loses all of the work. `PlotBuilder` does the same
+//! work one unit (one label or one Pedersen hash) at a time, in the order
+//! `MerkleTree::from_vec` would, and periodically appends the units completed
+//! since the last checkpoint to a checkpoint file, so checkpointing costs as
+//! much I/O as writing the plot once. A builder resumed from its checkpoint
+//! produces a plot that is byte-identical to an uninterrupted build.
+//! `finish_to` removes the checkpoint only once the plot is saved.
+//!
+//! ## Checkpoint format
+//!
+//! All integers are little endian. Units are completed level by level, so
+//! the hashes of all checkpoints, in order, are the leaves followed by the
+//! levels above them.
+//!
+//! ```text
+//! magic        8 bytes   "SPMCKPT1"
+//! plot id      32 bytes
+//! height       u8
+//! one batch per checkpoint:
+//!   count      u64       number of hashes in the batch
+//!   count times:
+//!     hash     32 bytes
+//!   digest     32 bytes  Blake2s of the count and the hashes
+//! ```
+//!
+//! A batch cut short by a crash fails its digest and is dropped on resume,
+//! with everything after it.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Write, Seek, SeekFrom, BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+use ff::{PrimeField, PrimeFieldRepr};
+
+use merkle::digest::{Algorithm, PEDERSEN_BN256};
+use merkle::hashutils::HashUtils;
+use merkle::merkletree::MerkleTree;
+use spaceproof_merkle_tree::Node;
+
+use super::{hash, PlotId};
+use super::merkle::{label, MerklePlot};
+
+static DIGEST: &Algorithm = &PEDERSEN_BN256;
+
+pub const CHECKPOINT_MAGIC: &'static [u8; 8] = b"SPMCKPT1";
+
+/// Largest supported plot height.
+pub const MAX_HEIGHT: usize = 40;
+
+const HASH_SIZE: usize = 32;
+
+/// Length of the checkpoint header: magic, plot id and height.
+const HEADER_SIZE: u64 = 8 + 32 + 1;
+
+const BATCH_PERSONALIZATION: &'static [u8; 8] = b"SPMCkpt_";
+
+pub struct PlotBuilder {
+    plot_id: PlotId,
+    height: usize,
+    /// Node hashes computed so far, from the leaves up
+    levels: Vec<Vec<Vec<u8>>>,
+    checkpoint: PathBuf,
+    /// Length of the intact part of the checkpoint file, once there is one
+    checkpoint_len: Option<u64>,
+    /// Number of units saved in the checkpoint file
+    saved: usize,
+    /// Number of units between two checkpoints
+    interval: usize,
+    since_checkpoint: usize,
+}
+
+/// Number of nodes on every level of a tree with `2^height` leaves.
+fn level_sizes(height: usize) -> Vec<usize> {
+    (0..(height + 1)).map(|j| 1usize << (height - j)).collect()
+}
+
+fn batch_digest(count: &[u8], hashes: &[&[u8]]) -> [u8; 32] {
+    let mut parts = Vec::with_capacity(hashes.len() + 1);
+    parts.push(count);
+    parts.extend_from_slice(hashes);
+    hash(BATCH_PERSONALIZATION, &parts)
+}
+
+/// Reads one batch of at most `max` hashes, or `None` at the end of the
+/// file or at a torn batch.
+fn read_batch<R: Read>(reader: &mut R, max: usize) -> io::Result<Option<Vec<Vec<u8>>>> {
+    let torn = |e: io::Error| if e.kind() == io::ErrorKind::UnexpectedEof { Ok(None) } else { Err(e) };
+
+    let mut count = [0u8; 8];
+    if let Err(e) = reader.read_exact(&mut count) {
+        return torn(e);
+    }
+    let len = (&count[..]).read_u64::<LittleEndian>()?;
+    if len > max as u64 {
+        return Ok(None);
+    }
+    let mut hashes = Vec::with_capacity(len as usize);
+    for _ in 0..len {
+        let mut hash = vec![0u8; HASH_SIZE];
+        if let Err(e) = reader.read_exact(&mut hash) {
+            return torn(e);
+        }
+        hashes.push(hash);
+    }
+    let mut digest = [0u8; 32];
+    if let Err(e) = reader.read_exact(&mut digest) {
+        return torn(e);
+    }
+    let parts: Vec<&[u8]> = hashes.iter().map(|h| &h[..]).collect();
+    if digest != batch_digest(&count, &parts) {
+        return Ok(None);
+    }
+
+    Ok(Some(hashes))
+}
+
+impl PlotBuilder {
+    /// Starts a new plot of `2^height` leaves, checkpointing to `checkpoint`
+    /// every `interval` units of work.
+    pub fn new<P: AsRef<Path>>(plot_id: PlotId, height: usize, checkpoint: P, interval: usize) -> io::Result<PlotBuilder> {
+        if height > MAX_HEIGHT {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "plot height out of range"));
+        }
+
+        Ok(PlotBuilder {
+            plot_id,
+            height,
+            levels: vec![vec![]; height + 1],
+            checkpoint: checkpoint.as_ref().to_path_buf(),
+            checkpoint_len: None,
+            saved: 0,
+            interval: interval.max(1),
+            since_checkpoint: 0,
+        })
+    }
+
+    /// Resumes the build saved in `checkpoint`.
+    pub fn resume<P: AsRef<Path>>(checkpoint: P, interval: usize) -> io::Result<PlotBuilder> {
+        let mut reader = BufReader::new(File::open(checkpoint.as_ref())?);
+
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic)?;
+        if &magic != CHECKPOINT_MAGIC {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "not a plot checkpoint"));
+        }
+        let mut plot_id = [0u8; 32];
+        reader.read_exact(&mut plot_id)?;
+        let height = reader.read_u8()? as usize;
+        if height > MAX_HEIGHT {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "plot height out of range"));
+        }
+
+        let sizes = level_sizes(height);
+        let total: usize = sizes.iter().sum();
+        let mut hashes = vec![];
+        let mut len = HEADER_SIZE;
+        while let Some(batch) = read_batch(&mut reader, total - hashes.len())? {
+            len += 8 + (batch.len() * HASH_SIZE) as u64 + 32;
+            hashes.extend(batch);
+        }
+        let saved = hashes.len();
+
+        // the units fill the levels from the leaves up
+        let mut levels = Vec::with_capacity(height + 1);
+        let mut hashes = hashes.into_iter();
+        for size in sizes {
+            levels.push(hashes.by_ref().take(size).collect());
+        }
+
+        Ok(PlotBuilder {
+            plot_id,
+            height,
+            levels,
+            checkpoint: checkpoint.as_ref().to_path_buf(),
+            checkpoint_len: Some(len),
+            saved,
+            interval: interval.max(1),
+            since_checkpoint: 0,
+        })
+    }
+
+    pub fn plot_id(&self) -> &PlotId {
+        &self.plot_id
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Units of work done and total units of work.
+    pub fn progress(&self) -> (usize, usize) {
+        let done = self.levels.iter().map(|l| l.len()).sum();
+        let total = level_sizes(self.height).iter().sum();
+        (done, total)
+    }
+
+    pub fn is_done(&self) -> bool {
+        let (done, total) = self.progress();
+        done == total
+    }
+
+    /// Does one unit of work. Returns `false` once the plot is complete.
+    fn next_unit(&mut self) -> bool {
+        let sizes = level_sizes(self.height);
+
+        let leaves = self.levels[0].len();
+        if leaves < sizes[0] {
+            let mut buf = vec![];
+            label(&self.plot_id, leaves as u64).into_repr().write_le(&mut buf).unwrap();
+            self.levels[0].push(buf);
+            return true;
+        }
+
+        // hash the lowest level that still has a pair of children ready
+        for j in 0..self.height {
+            let idx = self.levels[j + 1].len();
+            if idx < sizes[j + 1] {
+                let hash = DIGEST.hash_nodes(j as isize, &self.levels[j][2 * idx], &self.levels[j][2 * idx + 1]);
+                self.levels[j + 1].push(hash.value);
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Runs at most `units` units of work, writing checkpoints on the way.
+    /// Returns whether the plot is complete.
+    pub fn run(&mut self, units: usize) -> io::Result<bool> {
+        for _ in 0..units {
+            if !self.next_unit() {
+                return Ok(true);
+            }
+            self.since_checkpoint += 1;
+            if self.since_checkpoint >= self.interval {
+                self.write_checkpoint()?;
+            }
+        }
+        Ok(self.is_done())
+    }
+
+    /// Appends the units completed since the last checkpoint to the
+    /// checkpoint file. The file is created atomically, and a crash while
+    /// appending only loses the batch being appended.
+    pub fn write_checkpoint(&mut self) -> io::Result<()> {
+        let len = match self.checkpoint_len {
+            Some(len) => len,
+            None => {
+                let tmp = self.checkpoint.with_extension("tmp");
+                {
+                    let mut writer = BufWriter::new(File::create(&tmp)?);
+                    writer.write_all(CHECKPOINT_MAGIC)?;
+                    writer.write_all(&self.plot_id)?;
+                    writer.write_u8(self.height as u8)?;
+                    writer.flush()?;
+                    writer.get_ref().sync_all()?;
+                }
+                fs::rename(&tmp, &self.checkpoint)?;
+                self.checkpoint_len = Some(HEADER_SIZE);
+                HEADER_SIZE
+            }
+        };
+
+        let done = self.progress().0;
+        if done > self.saved {
+            let hashes: Vec<&[u8]> = self.levels.iter()
+                .flat_map(|level| level.iter().map(|h| &h[..]))
+                .skip(self.saved)
+                .collect();
+            let mut count = vec![];
+            count.write_u64::<LittleEndian>(hashes.len() as u64)?;
+
+            let mut file = OpenOptions::new().write(true).open(&self.checkpoint)?;
+            // drops what a failed append left behind
+            file.set_len(len)?;
+            file.seek(SeekFrom::Start(len))?;
+            let mut writer = BufWriter::new(file);
+            writer.write_all(&count)?;
+            for hash in &hashes {
+                writer.write_all(hash)?;
+            }
+            writer.write_all(&batch_digest(&count, &hashes))?;
+            writer.flush()?;
+            writer.get_ref().sync_all()?;
+
+            self.checkpoint_len = Some(len + 8 + (hashes.len() * HASH_SIZE) as u64 + 32);
+            self.saved = done;
+        }
+        self.since_checkpoint = 0;
+        Ok(())
+    }
+
+    /// Completes the plot. The checkpoint file is kept, so the plot is not
+    /// lost if saving it fails; `finish_to` saves it and then removes the
+    /// checkpoint.
+    pub fn finish(mut self) -> io::Result<MerklePlot> {
+        while !self.run(self.interval)? {}
+
+        let mut leaves = Vec::with_capacity(self.levels[0].len());
+        for hash in &self.levels[0] {
+            leaves.push(Node::newFromVec(hash.clone()).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?);
+        }
+        let tree = MerkleTree::from_levels(DIGEST, leaves, self.levels)
+            .ok_or(io::Error::new(io::ErrorKind::InvalidData, "malformed tree levels"))?;
+
+        Ok(MerklePlot::from_tree(self.plot_id, tree))
+    }
+
+    /// Completes the plot and writes it to `path`, replacing it atomically,
+    /// and only then removes the checkpoint file.
+    pub fn finish_to<P: AsRef<Path>>(self, path: P) -> io::Result<MerklePlot> {
+        let checkpoint = self.checkpoint.clone();
+        let plot = self.finish()?;
+
+        let path = path.as_ref();
+        let tmp = path.with_extension("tmp");
+        {
+            let mut writer = BufWriter::new(File::create(&tmp)?);
+            plot.write(&mut writer)?;
+            writer.flush()?;
+            writer.get_ref().sync_all()?;
+        }
+        fs::rename(&tmp, path)?;
+
+        if checkpoint.exists() {
+            fs::remove_file(&checkpoint)?;
+        }
+        Ok(plot)
+    }
+}
+
+#[test]
+fn test_resume_is_byte_identical() {
+    use std::env;
+
+    let dir = env::temp_dir().join(format!("spaceproof-builder-{}", ::std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let checkpoint = dir.join("plot.ckpt");
+
+    let plot_id = [5u8; 32];
+    let mut expected = vec![];
+    MerklePlot::create(plot_id, 3).write(&mut expected).unwrap();
+
+    // 8 labels and 7 hashes, interrupted in the middle of the hashing
+    let mut builder = PlotBuilder::new(plot_id, 3, &checkpoint, 4).unwrap();
+    assert!(!builder.run(10).unwrap());
+    assert_eq!(builder.progress(), (10, 15));
+    // the two units done since the last checkpoint are lost with the builder
+    drop(builder);
+    // each checkpoint appended only its own four units
+    let batch = 8 + 4 * HASH_SIZE as u64 + 32;
+    assert_eq!(fs::metadata(&checkpoint).unwrap().len(), HEADER_SIZE + 2 * batch);
+
+    // a batch torn by a crash is dropped
+    let mut file = OpenOptions::new().append(true).open(&checkpoint).unwrap();
+    file.write_all(&[4, 0, 0, 0, 0, 0, 0, 0, 1, 2, 3]).unwrap();
+    drop(file);
+    let mut builder = PlotBuilder::resume(&checkpoint, 4).unwrap();
+    assert_eq!(builder.progress(), (8, 15));
+    assert!(!builder.run(4).unwrap());
+    assert_eq!(fs::metadata(&checkpoint).unwrap().len(), HEADER_SIZE + 3 * batch);
+
+    let builder = PlotBuilder::resume(&checkpoint, 4).unwrap();
+    assert_eq!(builder.progress(), (12, 15));
+    let plot = builder.finish().unwrap();
+    // kept until the plot is saved
+    assert!(checkpoint.exists());
+
+    let mut actual = vec![];
+    plot.write(&mut actual).unwrap();
+    assert_eq!(actual, expected);
+
+    let out = dir.join("plot.plot");
+    let plot = PlotBuilder::resume(&checkpoint, 4).unwrap().finish_to(&out).unwrap();
+    assert!(!checkpoint.exists());
+    assert!(!out.with_extension("tmp").exists());
+    assert_eq!(fs::read(&out).unwrap(), expected);
+    assert_eq!(plot.root(), MerklePlot::create(plot_id, 3).root());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,376 @@
+//! Bookkeeping for Merkle plots spread over several directories.
+//!
+//! Plot files are named `<hex plot id>.plot`. Plots that fail an integrity
+//! check are moved into a `quarantine` directory next to them, so they are
+//! neither farmed nor listed again until an operator looks at them. Errors
+//! reading a file, as opposed to its contents being wrong, are returned
+//! instead: the plot may be fine once the disk is.
+
+use std::fs::{self, File};
+use std::io::{self, Read, Seek, SeekFrom, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use byteorder::{LittleEndian, ReadBytesExt};
+use ff::{PrimeField, PrimeFieldRepr};
+use rand::{ChaChaRng, SeedableRng, Rng};
+use pairing::bn256::Fr;
+
+use merkle::digest::{Algorithm, PEDERSEN_BN256};
+use merkle::hashutils::HashUtils;
+use spaceproof_merkle_tree::Node;
+
+use super::PlotId;
+use super::merkle::{self, MerklePlot, PlotHeader};
+
+static DIGEST: &Algorithm = &PEDERSEN_BN256;
+
+pub const PLOT_EXTENSION: &'static str = "plot";
+pub const QUARANTINE_DIR: &'static str = "quarantine";
+
+/// A plot file known to the manager.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlotInfo {
+    pub path: PathBuf,
+    pub plot_id: PlotId,
+    pub root: Fr,
+    pub height: usize,
+    pub count: usize,
+    /// Size of the plot file in bytes
+    pub size: u64,
+}
+
+/// Outcome of spot-checking a plot.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CheckResult {
+    Ok,
+    /// The file could be read, but not parsed as a plot
+    Unreadable(String),
+    /// The stored leaf does not match the label derived from the plot id
+    BadLeaf(u64),
+    /// The stored path of the leaf does not lead to the stored root
+    BadPath(u64),
+}
+
+impl CheckResult {
+    pub fn is_ok(&self) -> bool {
+        *self == CheckResult::Ok
+    }
+}
+
+pub struct PlotManager {
+    dirs: Vec<PathBuf>,
+    plots: Vec<PlotInfo>,
+}
+
+pub fn plot_file_name(plot_id: &PlotId) -> String {
+    format!("{}.{}", hex::encode(&plot_id[..]), PLOT_EXTENSION)
+}
+
+fn read_info(path: &Path) -> io::Result<PlotInfo> {
+    let header = PlotHeader::read(BufReader::new(File::open(path)?))?;
+    Ok(PlotInfo {
+        path: path.to_path_buf(),
+        plot_id: header.plot_id,
+        root: header.root,
+        height: header.height,
+        count: header.count,
+        size: fs::metadata(path)?.len(),
+    })
+}
+
+/// Opens a plot and checks that its levels have the sizes its header
+/// implies, returning the offset of the first hash of every level.
+fn open_levels(path: &Path) -> io::Result<(File, PlotHeader, Vec<u64>)> {
+    let mut file = File::open(path)?;
+    let header = PlotHeader::read(BufReader::new(&mut file))?;
+    let offsets = header.level_offsets();
+    if header.count == 0 || fs::metadata(path)?.len() != offsets[offsets.len() - 1] {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "plot file does not have the size of its header"));
+    }
+
+    let mut hashes = vec![];
+    for (size, offset) in header.level_sizes().into_iter().zip(offsets) {
+        file.seek(SeekFrom::Start(offset))?;
+        if file.read_u64::<LittleEndian>()? != size as u64 {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "level does not have the size of its header"));
+        }
+        hashes.push(offset + 8);
+    }
+
+    Ok((file, header, hashes))
+}
+
+fn read_hash(file: &mut File, level: u64, index: u64) -> io::Result<Vec<u8>> {
+    let mut hash = vec![0u8; 32];
+    file.seek(SeekFrom::Start(level + 32 * index))?;
+    file.read_exact(&mut hash)?;
+    Ok(hash)
+}
+
+/// Whether `e` says the file is not a valid plot, rather than that it could
+/// not be read.
+fn is_corrupt(e: &io::Error) -> bool {
+    match e.kind() {
+        io::ErrorKind::InvalidData | io::ErrorKind::UnexpectedEof => true,
+        _ => false,
+    }
+}
+
+impl PlotManager {
+    pub fn new() -> PlotManager {
+        PlotManager {
+            dirs: vec![],
+            plots: vec![],
+        }
+    }
+
+    /// Adds a plot directory, creating it if needed, and returns the number
+    /// of plots found in it.
+    pub fn add_directory<P: AsRef<Path>>(&mut self, dir: P) -> io::Result<usize> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+        if !self.dirs.contains(&dir) {
+            self.dirs.push(dir.clone());
+        }
+        let before = self.plots.len();
+        self.scan(&dir)?;
+        Ok(self.plots.len() - before)
+    }
+
+    pub fn directories(&self) -> &[PathBuf] {
+        &self.dirs
+    }
+
+    fn scan(&mut self, dir: &Path) -> io::Result<()> {
+        self.plots.retain(|p| p.path.parent() != Some(dir));
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_file() && path.extension().map(|e| e == PLOT_EXTENSION).unwrap_or(false) {
+                match read_info(&path) {
+                    Ok(info) => self.plots.push(info),
+                    // a plot whose header cannot be parsed is as broken as it gets
+                    Err(ref e) if is_corrupt(e) => { quarantine_file(&path)?; }
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+        self.plots.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(())
+    }
+
+    /// Rescans every directory.
+    pub fn refresh(&mut self) -> io::Result<()> {
+        for dir in self.dirs.clone() {
+            self.scan(&dir)?;
+        }
+        Ok(())
+    }
+
+    pub fn plots(&self) -> &[PlotInfo] {
+        &self.plots
+    }
+
+    pub fn find(&self, plot_id: &PlotId) -> Option<&PlotInfo> {
+        self.plots.iter().find(|p| p.plot_id == *plot_id)
+    }
+
+    /// Writes `plot` into the directory holding the fewest plots. The plot
+    /// only appears under its final name once it is completely on disk, so
+    /// a crash cannot leave a truncated plot to be scanned.
+    pub fn store(&mut self, plot: &MerklePlot) -> io::Result<PathBuf> {
+        if self.find(&plot.plot_id).is_some() {
+            return Err(io::Error::new(io::ErrorKind::AlreadyExists, "plot is already stored"));
+        }
+        let dir = self.dirs.iter()
+            .min_by_key(|d| self.plots.iter().filter(|p| p.path.parent() == Some(d.as_path())).count())
+            .ok_or(io::Error::new(io::ErrorKind::NotFound, "no plot directory"))?
+            .clone();
+
+        let path = dir.join(plot_file_name(&plot.plot_id));
+        let tmp = path.with_extension("tmp");
+        {
+            let mut writer = BufWriter::new(File::create(&tmp)?);
+            plot.write(&mut writer)?;
+            writer.flush()?;
+            writer.get_ref().sync_all()?;
+        }
+        fs::rename(&tmp, &path)?;
+        self.plots.push(read_info(&path)?);
+        Ok(path)
+    }
+
+    pub fn load(&self, plot_id: &PlotId) -> io::Result<MerklePlot> {
+        let info = self.find(plot_id)
+            .ok_or(io::Error::new(io::ErrorKind::NotFound, "unknown plot"))?;
+        MerklePlot::read(BufReader::new(File::open(&info.path)?))
+    }
+
+    /// Spot-checks `samples` random leaves of a plot: each leaf must match
+    /// its label, and hashing it up with its stored siblings must give the
+    /// stored nodes above it and the root of the header. Only the sampled
+    /// paths are read from the file.
+    pub fn check(&self, plot_id: &PlotId, samples: usize, seed_slice: &[u32]) -> io::Result<CheckResult> {
+        let info = self.find(plot_id)
+            .ok_or(io::Error::new(io::ErrorKind::NotFound, "unknown plot"))?;
+        let (mut file, header, levels) = match open_levels(&info.path) {
+            Ok(opened) => opened,
+            Err(ref e) if is_corrupt(e) => return Ok(CheckResult::Unreadable(e.to_string())),
+            Err(e) => return Err(e),
+        };
+        let sizes = header.level_sizes();
+        let mut root = vec![];
+        header.root.into_repr().write_le(&mut root)?;
+
+        let rng = &mut ChaChaRng::from_seed(seed_slice);
+        for _ in 0..samples {
+            let index = rng.gen_range(0, header.count as u64);
+            let mut node = read_hash(&mut file, levels[0], index)?;
+            match Node::newFromVec(node.clone()) {
+                Ok(ref leaf) if leaf.hash == merkle::label(&header.plot_id, index) => {}
+                _ => return Ok(CheckResult::BadLeaf(index)),
+            }
+
+            let mut i = index;
+            for j in 0..header.height {
+                let sibling = i ^ 1;
+                // a node without a sibling is carried up unchanged
+                if sibling < sizes[j] as u64 {
+                    let sibling = read_hash(&mut file, levels[j], sibling)?;
+                    node = if i % 2 == 0 {
+                        DIGEST.hash_nodes(j as isize, &node, &sibling).value
+                    } else {
+                        DIGEST.hash_nodes(j as isize, &sibling, &node).value
+                    };
+                }
+                i /= 2;
+                if read_hash(&mut file, levels[j + 1], i)? != node {
+                    return Ok(CheckResult::BadPath(index));
+                }
+            }
+            if node != root {
+                return Ok(CheckResult::BadPath(index));
+            }
+        }
+
+        Ok(CheckResult::Ok)
+    }
+
+    /// Moves a plot into the quarantine directory next to it and stops
+    /// listing it.
+    pub fn quarantine(&mut self, plot_id: &PlotId) -> io::Result<PathBuf> {
+        let pos = self.plots.iter().position(|p| p.plot_id == *plot_id)
+            .ok_or(io::Error::new(io::ErrorKind::NotFound, "unknown plot"))?;
+        let target = quarantine_file(&self.plots[pos].path)?;
+        self.plots.remove(pos);
+        Ok(target)
+    }
+
+    /// Checks every plot and quarantines the broken ones.
+    pub fn check_all(&mut self, samples: usize, seed_slice: &[u32]) -> io::Result<Vec<(PlotId, CheckResult)>> {
+        let mut results = vec![];
+        for plot_id in self.plots.iter().map(|p| p.plot_id).collect::<Vec<_>>() {
+            let result = self.check(&plot_id, samples, seed_slice)?;
+            if !result.is_ok() {
+                self.quarantine(&plot_id)?;
+            }
+            results.push((plot_id, result));
+        }
+        Ok(results)
+    }
+}
+
+fn quarantine_file(path: &Path) -> io::Result<PathBuf> {
+    let dir = path.parent().unwrap_or(Path::new(".")).join(QUARANTINE_DIR);
+    fs::create_dir_all(&dir)?;
+    let target = dir.join(path.file_name().unwrap_or_default());
+    fs::rename(path, &target)?;
+    Ok(target)
+}
+
+#[test]
+fn test_plot_manager() {
+    use std::env;
+    use std::io::{Seek, SeekFrom};
+
+    let base = env::temp_dir().join(format!("spaceproof-manager-{}", ::std::process::id()));
+    let _ = fs::remove_dir_all(&base);
+
+    let mut manager = PlotManager::new();
+    assert_eq!(manager.add_directory(base.join("a")).unwrap(), 0);
+    assert_eq!(manager.add_directory(base.join("b")).unwrap(), 0);
+
+    let plots: Vec<_> = (0..4u8).map(|i| MerklePlot::create([i; 32], 2)).collect();
+    for plot in &plots {
+        manager.store(plot).unwrap();
+    }
+    assert!(manager.store(&plots[0]).is_err());
+    // plots are spread over both directories
+    assert_eq!(manager.plots().iter().filter(|p| p.path.starts_with(base.join("a"))).count(), 2);
+
+    let info = manager.find(&plots[1].plot_id).unwrap().clone();
+    assert_eq!(info.root, plots[1].root());
+    assert_eq!(info.count, 4);
+
+    let seed = [1u32, 1u32, 1u32, 1u32];
+    assert_eq!(manager.check(&plots[1].plot_id, 32, &seed).unwrap(), CheckResult::Ok);
+
+    // corrupt the second leaf of the second plot in place
+    {
+        let mut file = fs::OpenOptions::new().write(true).open(&info.path).unwrap();
+        // header is 81 bytes, followed by the length of the leaf level
+        file.seek(SeekFrom::Start(81 + 8 + 32 + 1)).unwrap();
+        file.write_all(&[0xaa]).unwrap();
+    }
+    assert_eq!(manager.check(&plots[1].plot_id, 32, &seed).unwrap(), CheckResult::BadLeaf(1));
+
+    // corrupt the first node above the leaves of the third plot
+    {
+        let path = &manager.find(&plots[2].plot_id).unwrap().path;
+        let mut file = fs::OpenOptions::new().write(true).open(path).unwrap();
+        file.seek(SeekFrom::Start(81 + 8 + 4 * 32 + 8 + 1)).unwrap();
+        file.write_all(&[0xaa]).unwrap();
+    }
+    match manager.check(&plots[2].plot_id, 32, &seed).unwrap() {
+        CheckResult::BadPath(index) => assert!(index < 2),
+        result => panic!("unexpected {:?}", result),
+    }
+
+    // and truncate the fourth
+    {
+        let path = &manager.find(&plots[3].plot_id).unwrap().path;
+        let file = fs::OpenOptions::new().write(true).open(path).unwrap();
+        let len = file.metadata().unwrap().len();
+        file.set_len(len - 1).unwrap();
+    }
+    match manager.check(&plots[3].plot_id, 32, &seed).unwrap() {
+        CheckResult::Unreadable(_) => {}
+        result => panic!("unexpected {:?}", result),
+    }
+
+    let results = manager.check_all(32, &seed).unwrap();
+    assert_eq!(results.len(), 4);
+    assert_eq!(results.iter().filter(|r| r.1.is_ok()).count(), 1);
+    assert!(manager.find(&plots[1].plot_id).is_none());
+    assert!(info.path.parent().unwrap().join(QUARANTINE_DIR).join(plot_file_name(&plots[1].plot_id)).exists());
+
+    // a fresh manager sees the same healthy plots
+    let mut other = PlotManager::new();
+    other.add_directory(base.join("a")).unwrap();
+    other.add_directory(base.join("b")).unwrap();
+    assert_eq!(other.plots(), manager.plots());
+
+    // a file that is not a plot is quarantined on the next scan
+    let junk = base.join("a").join(plot_file_name(&[9; 32]));
+    fs::write(&junk, b"not a plot").unwrap();
+    other.refresh().unwrap();
+    assert_eq!(other.plots(), manager.plots());
+    assert!(!junk.exists());
+    assert!(base.join("a").join(QUARANTINE_DIR).join(plot_file_name(&[9; 32])).exists());
+
+    // while failing to read a file does not make it corrupt
+    let unreadable = base.join("a").join(plot_file_name(&[10; 32]));
+    fs::create_dir(&unreadable).unwrap();
+    assert!(!is_corrupt(&read_info(&unreadable).unwrap_err()));
+
+    fs::remove_dir_all(&base).unwrap();
+}
@@ -0,0 +1,318 @@
+//! Merkle-committed plots.
+//!
+//! Every leaf is a label derived from the plot id and the leaf index, and
+//! the plot commits to its labels with the Pedersen `MerkleTree`. A challenge
+//! selects one leaf, whose inclusion is then proven with the
+//! `SpaceProofMerkleTreeCircuit`.
+//!
+//! ## File format
+//!
+//! All integers are little endian, every hash is a 32 byte field element.
+//!
+//! ```text
+//! magic        8 bytes   "SPMPLOT1"
+//! plot id      32 bytes
+//! height       u8
+//! count        u64       number of leaves
+//! root         32 bytes
+//! height + 1 times, from the leaves up to the root:
+//!   len        u64
+//!   len times:
+//!     hash     32 bytes
+//! ```
+//!
+//! Storing every level lets a plot be loaded without recomputing a single
+//! Pedersen hash; `PlotManager::check` spot-checks the stored nodes instead.
+
+use std::io::{self, Read, Write};
+
+use byteorder::{BigEndian, LittleEndian, ByteOrder, ReadBytesExt, WriteBytesExt};
+use ff::{PrimeField, PrimeFieldRepr};
+use pairing::bn256::{Fr, FrRepr};
+
+use merkle::digest::{Algorithm, PEDERSEN_BN256};
+use merkle::merkletree::MerkleTree;
+use spaceproof_merkle_tree::{Node, merkle_path};
+
+use super::{hash, PlotId, Challenge, Quality};
+
+static DIGEST: &Algorithm = &PEDERSEN_BN256;
+
+pub const MAGIC: &'static [u8; 8] = b"SPMPLOT1";
+
+/// Size in bytes of a serialized field element.
+const HASH_SIZE: usize = 32;
+
+const LABEL_PERSONALIZATION: &'static [u8; 8] = b"SPMrkLb_";
+const QUALITY_PERSONALIZATION: &'static [u8; 8] = b"SPMrkQl_";
+
+/// Size in bytes of a serialized `PlotHeader`.
+pub const HEADER_SIZE: u64 = 8 + 32 + 1 + 8 + 32;
+
+/// A plot made of `2^height` labels committed in a Pedersen Merkle tree.
+#[derive(Clone, Debug)]
+pub struct MerklePlot {
+    pub plot_id: PlotId,
+    tree: MerkleTree<Node>,
+}
+
+/// Fixed-size header of a plot file, readable without loading the tree.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PlotHeader {
+    pub plot_id: PlotId,
+    pub height: usize,
+    pub count: usize,
+    pub root: Fr,
+}
+
+impl PlotHeader {
+    pub fn write<W: Write>(
+        &self,
+        mut writer: W
+    ) -> io::Result<()>
+    {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&self.plot_id)?;
+        writer.write_u8(self.height as u8)?;
+        writer.write_u64::<LittleEndian>(self.count as u64)?;
+        self.root.into_repr().write_le(&mut writer)?;
+
+        Ok(())
+    }
+
+    pub fn read<R: Read>(
+        mut reader: R
+    ) -> io::Result<Self>
+    {
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "not a merkle plot"));
+        }
+
+        let mut plot_id = [0u8; 32];
+        reader.read_exact(&mut plot_id)?;
+        let height = reader.read_u8()? as usize;
+        let count = reader.read_u64::<LittleEndian>()? as usize;
+        let root = read_fr(&mut reader)?;
+
+        Ok(PlotHeader { plot_id, height, count, root })
+    }
+
+    /// Number of nodes on every level, from the leaves up to the root, as
+    /// `MerkleTree::from_vec` lays them out.
+    pub fn level_sizes(&self) -> Vec<usize> {
+        let mut sizes = vec![self.count];
+        for j in 0..self.height {
+            let size = (sizes[j] + 1) / 2;
+            sizes.push(size);
+        }
+        sizes
+    }
+
+    /// Offset in the plot file of every level, where its length is stored
+    /// followed by its hashes. The last offset is the size of the file.
+    pub fn level_offsets(&self) -> Vec<u64> {
+        let mut offsets = vec![HEADER_SIZE];
+        for size in self.level_sizes() {
+            let offset = offsets[offsets.len() - 1]
+                .saturating_add(8)
+                .saturating_add((size as u64).saturating_mul(HASH_SIZE as u64));
+            offsets.push(offset);
+        }
+        offsets
+    }
+}
+
+fn read_fr<R: Read>(reader: &mut R) -> io::Result<Fr> {
+    let mut repr = FrRepr::default();
+    repr.read_le(reader)?;
+    Fr::from_repr(repr).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Label of the `index`-th leaf of the plot `plot_id`.
+pub fn label(plot_id: &PlotId, index: u64) -> Fr {
+    let mut index_bytes = [0u8; 8];
+    LittleEndian::write_u64(&mut index_bytes, index);
+    let digest = hash(LABEL_PERSONALIZATION, &[&plot_id[..], &index_bytes[..]]);
+
+    let mut repr = FrRepr::default();
+    repr.read_le(&digest[..]).unwrap();
+    // clear the top bits so the label is always below the modulus
+    repr.as_mut()[3] &= 0x0fffffffffffffff;
+    Fr::from_repr(repr).unwrap()
+}
+
+/// Leaf position selected by `challenge` in a plot of `count` leaves.
+pub fn challenge_index(challenge: &Challenge, count: usize) -> u64 {
+    BigEndian::read_u64(&challenge[..8]) % count as u64
+}
+
+/// Quality of answering `challenge` with the leaf `leaf`.
+pub fn quality(challenge: &Challenge, leaf: &Fr) -> Quality {
+    let mut leaf_bytes = vec![];
+    leaf.into_repr().write_le(&mut leaf_bytes).unwrap();
+    hash(QUALITY_PERSONALIZATION, &[&challenge[..], &leaf_bytes[..]])
+}
+
+impl MerklePlot {
+    /// Labels and commits a plot of `2^height` leaves.
+    pub fn create(plot_id: PlotId, height: usize) -> Self {
+        let leaves = (0..(1u64 << height))
+            .map(|i| Node::new(label(&plot_id, i)))
+            .collect();
+
+        MerklePlot::from_leaves(plot_id, leaves)
+    }
+
+    /// Commits already computed leaves.
+    pub fn from_leaves(plot_id: PlotId, leaves: Vec<Node>) -> Self {
+        MerklePlot::from_tree(plot_id, MerkleTree::from_vec(DIGEST, leaves))
+    }
+
+    /// Wraps an already built tree.
+    pub fn from_tree(plot_id: PlotId, tree: MerkleTree<Node>) -> Self {
+        MerklePlot { plot_id, tree }
+    }
+
+    /// Root of the tree, the public commitment to the plot.
+    pub fn root(&self) -> Fr {
+        Node::newFromVec(self.tree.root_hash().clone()).unwrap().hash
+    }
+
+    pub fn height(&self) -> usize {
+        self.tree.height() as usize
+    }
+
+    pub fn count(&self) -> usize {
+        self.tree.count()
+    }
+
+    pub fn tree(&self) -> &MerkleTree<Node> {
+        &self.tree
+    }
+
+    /// Value of the `index`-th leaf.
+    pub fn leaf(&self, index: u64) -> Option<Fr> {
+        self.tree.gen_nth_proof(index as usize).map(|p| p.value.hash)
+    }
+
+    /// Siblings of the `index`-th leaf from the leaf up to the root, in the
+    /// form expected by `SpaceProofMerkleTreeCircuit::proof`.
+    pub fn path(&self, index: u64) -> Option<Vec<Option<Fr>>> {
+        merkle_path(&self.tree, index).ok()
+    }
+
+    pub fn header(&self) -> PlotHeader {
+        PlotHeader {
+            plot_id: self.plot_id,
+            height: self.height(),
+            count: self.count(),
+            root: self.root(),
+        }
+    }
+
+    pub fn write<W: Write>(
+        &self,
+        mut writer: W
+    ) -> io::Result<()>
+    {
+        self.header().write(&mut writer)?;
+        for level in self.tree.levels() {
+            writer.write_u64::<LittleEndian>(level.len() as u64)?;
+            for hash in level {
+                writer.write_all(&hash)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn read<R: Read>(
+        mut reader: R
+    ) -> io::Result<Self>
+    {
+        let header = PlotHeader::read(&mut reader)?;
+
+        let mut levels = Vec::with_capacity(header.height + 1);
+        for _ in 0..(header.height + 1) {
+            let len = reader.read_u64::<LittleEndian>()? as usize;
+            if len > header.count {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, "level is larger than the leaf count"));
+            }
+            // `len` is only bounded by the header, which comes from the
+            // file as well, so the level grows as its hashes are read
+            let mut level = vec![];
+            for _ in 0..len {
+                let mut hash = vec![0u8; HASH_SIZE];
+                reader.read_exact(&mut hash)?;
+                level.push(hash);
+            }
+            levels.push(level);
+        }
+
+        let mut leaves = vec![];
+        for hash in levels.first().map(|l| &l[..]).unwrap_or(&[]) {
+            leaves.push(Node::newFromVec(hash.clone()).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?);
+        }
+
+        let tree = MerkleTree::from_levels(DIGEST, leaves, levels)
+            .ok_or(io::Error::new(io::ErrorKind::InvalidData, "malformed tree levels"))?;
+        let plot = MerklePlot { plot_id: header.plot_id, tree };
+        if plot.header() != header {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "header does not match the stored tree"));
+        }
+
+        Ok(plot)
+    }
+
+    /// Position and quality of the leaf selected by `challenge`.
+    pub fn quality(&self, challenge: &Challenge) -> (u64, Quality) {
+        let index = challenge_index(challenge, self.count());
+        let leaf = self.leaf(index).expect("challenge index is below the leaf count");
+        (index, quality(challenge, &leaf))
+    }
+}
+
+#[test]
+fn test_merkle_plot_serialization() {
+    let plot = MerklePlot::create([2u8; 32], 2);
+    let mut v = vec![];
+    plot.write(&mut v).unwrap();
+    assert_eq!(PlotHeader::read(&v[..]).unwrap(), plot.header());
+    assert_eq!(plot.header().level_offsets().last(), Some(&(v.len() as u64)));
+
+    let de_plot = MerklePlot::read(&v[..]).unwrap();
+    assert_eq!(de_plot.root(), plot.root());
+    assert_eq!(de_plot.path(3), plot.path(3));
+
+    // the root stored in the header must match the tree
+    v[41 + 8] ^= 1;
+    assert!(MerklePlot::read(&v[..]).is_err());
+}
+
+#[test]
+fn test_merkle_plot_path() {
+    use merkle::hashutils::HashUtils;
+
+    let plot = MerklePlot::create([1u8; 32], 3);
+    assert_eq!(plot.count(), 8);
+    assert_eq!(plot.height(), 3);
+
+    let index = 5;
+    let path = plot.path(index).unwrap();
+    assert_eq!(path.len(), plot.height());
+
+    // fold the path back into the root, leaf level first
+    let mut node = Node::new(plot.leaf(index).unwrap());
+    for (level, sibling) in path.iter().enumerate() {
+        let sibling = Node::new(sibling.unwrap());
+        let digest = if (index >> level) & 1 == 1 {
+            DIGEST.hash_nodes(level as isize, &sibling, &node)
+        } else {
+            DIGEST.hash_nodes(level as isize, &node, &sibling)
+        };
+        node = Node::newFromVec(digest.value).unwrap();
+    }
+    assert_eq!(node.hash, plot.root());
+}
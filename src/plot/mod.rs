@@ -0,0 +1,33 @@
+//! Plot formats for proofs of space.
+//!
+//! A plot commits to a large amount of data derived from a `PlotId`. When a
+//! `Challenge` is issued, the plot is looked up for a proof and the proof is
+//! scored by its `Quality`; the lower the quality digest, the better the proof.
+
+use blake2_rfc::blake2s::Blake2s;
+
+pub mod builder;
+pub mod manager;
+pub mod merkle;
+pub mod table;
+
+/// Identifier of a plot, mixed into every value derived from it.
+pub type PlotId = [u8; 32];
+
+/// Challenge issued to every plot at once.
+pub type Challenge = [u8; 32];
+
+/// Score of a proof of space; compared lexicographically, lower is better.
+pub type Quality = [u8; 32];
+
+/// Blake2s over the concatenation of `parts`, prefixed with `personalization`.
+pub(crate) fn hash(personalization: &[u8; 8], parts: &[&[u8]]) -> [u8; 32] {
+    let mut h = Blake2s::new(32);
+    h.update(personalization);
+    for part in parts {
+        h.update(part);
+    }
+    let mut out = [0u8; 32];
+    out.copy_from_slice(h.finalize().as_bytes());
+    out
+}
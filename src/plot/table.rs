@@ -0,0 +1,398 @@
+//! Table-based plots, following the matched-pair construction used by Chia.
+//!
+//! Table 1 holds `f1(x)` for every `x` in `0..2^k`. Each following table is
+//! built by matching entries of the previous table that fall in adjacent
+//! buckets and hashing the pair into a new `k + PARAM_EXT` bit output. A
+//! proof of space is the set of 64 `x` values below an entry of table 7
+//! whose output matches the challenge; the verifier only recomputes those
+//! 64 leaves and the 63 matches above them.
+//!
+//! Entries in the last bucket of a table have no partner, so very small
+//! plots lose a noticeable share of every table; use `k >= 16` for real
+//! plots, smaller sizes are only useful for tests.
+//!
+//! ## File format
+//!
+//! All integers are little endian.
+//!
+//! ```text
+//! magic        8 bytes   "SPTPLOT1"
+//! k            u8
+//! plot id      32 bytes
+//! 7 times:
+//!   count      u64       2^k for table 1
+//!   count times:
+//!     y        u64       table output
+//!     pos_l    u64       x for table 1, position in the previous table otherwise
+//!     pos_r    u64       0 for table 1, position in the previous table otherwise
+//! ```
+
+use std::cmp;
+use std::io::{self, Read, Write};
+
+use byteorder::{LittleEndian, BigEndian, ByteOrder, ReadBytesExt, WriteBytesExt};
+
+use super::{hash, PlotId, Challenge, Quality};
+
+pub const MAGIC: &'static [u8; 8] = b"SPTPLOT1";
+
+/// Smallest supported plot size.
+pub const K_MIN: u8 = 8;
+/// Largest supported plot size.
+pub const K_MAX: u8 = 40;
+
+/// Number of tables in a plot.
+pub const NUM_TABLES: usize = 7;
+/// Number of `x` values in a proof.
+pub const PROOF_SIZE: usize = 1 << (NUM_TABLES - 1);
+
+/// Most entries `read` reserves room for before reading them.
+const READ_RESERVE: usize = 1 << 16;
+
+/// Extra output bits of every `f` function, keeps table sizes close to `2^k`.
+const PARAM_EXT: u8 = 6;
+/// Number of target `b` groups a left entry can match.
+const PARAM_M: u64 = 64;
+const PARAM_B: u64 = 119;
+const PARAM_C: u64 = 127;
+const PARAM_BC: u64 = PARAM_B * PARAM_C;
+
+const F1_PERSONALIZATION: &'static [u8; 8] = b"SPTabF1_";
+const FX_PERSONALIZATION: &'static [u8; 8] = b"SPTabFx_";
+const META_PERSONALIZATION: &'static [u8; 8] = b"SPTabMt_";
+const QUALITY_PERSONALIZATION: &'static [u8; 8] = b"SPTabQl_";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Entry {
+    y: u64,
+    pos_l: u64,
+    pos_r: u64,
+}
+
+/// Collated data of the `x` values below an entry, fed into the next `f`.
+type Meta = [u8; 16];
+
+/// A proof of space found in a `TablePlot`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TableProof {
+    /// The 64 leaves of the proof, in tree order.
+    pub xs: Vec<u64>,
+    pub quality: Quality,
+}
+
+/// A plot made of seven matched-pair tables.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TablePlot {
+    pub k: u8,
+    pub plot_id: PlotId,
+    tables: Vec<Vec<Entry>>,
+}
+
+fn truncate(digest: &[u8; 32], k: u8) -> u64 {
+    BigEndian::read_u64(&digest[..8]) >> (64 - (k + PARAM_EXT) as u32)
+}
+
+fn f1(plot_id: &PlotId, k: u8, x: u64) -> u64 {
+    let mut x_bytes = [0u8; 8];
+    LittleEndian::write_u64(&mut x_bytes, x);
+    truncate(&hash(F1_PERSONALIZATION, &[&plot_id[..], &x_bytes[..]]), k)
+}
+
+fn meta1(x: u64) -> Meta {
+    let mut meta = [0u8; 16];
+    LittleEndian::write_u64(&mut meta[..8], x);
+    meta
+}
+
+fn fx(plot_id: &PlotId, k: u8, table: u8, y_l: u64, meta_l: &Meta, meta_r: &Meta) -> (u64, Meta) {
+    let mut y_bytes = [0u8; 8];
+    LittleEndian::write_u64(&mut y_bytes, y_l);
+    let y = truncate(&hash(FX_PERSONALIZATION, &[&plot_id[..], &[table], &y_bytes[..], &meta_l[..], &meta_r[..]]), k);
+
+    let mut meta = [0u8; 16];
+    meta.copy_from_slice(&hash(META_PERSONALIZATION, &[&meta_l[..], &meta_r[..]])[..16]);
+    (y, meta)
+}
+
+/// Whether `y_l` and `y_r` form a matching pair.
+///
+/// Both outputs must lie in adjacent buckets of size `PARAM_BC`, and their
+/// offsets inside the bucket must differ by one of `PARAM_M` targets that
+/// depend on the parity of the left bucket.
+fn matches(y_l: u64, y_r: u64) -> bool {
+    let bucket_l = y_l / PARAM_BC;
+    if bucket_l + 1 != y_r / PARAM_BC {
+        return false;
+    }
+    let (b_l, c_l) = ((y_l % PARAM_BC) / PARAM_C, (y_l % PARAM_BC) % PARAM_C);
+    let (b_r, c_r) = ((y_r % PARAM_BC) / PARAM_C, (y_r % PARAM_BC) % PARAM_C);
+
+    let m = (b_r + PARAM_B - b_l) % PARAM_B;
+    if m >= PARAM_M {
+        return false;
+    }
+    let parity = bucket_l % 2;
+    let c_target = ((2 * m + parity) * (2 * m + parity)) % PARAM_C;
+    (c_r + PARAM_C - c_l) % PARAM_C == c_target
+}
+
+/// The top `k` bits of the challenge, compared against the top `k` bits of
+/// table 7 outputs.
+fn challenge_target(challenge: &Challenge, k: u8) -> u64 {
+    BigEndian::read_u64(&challenge[..8]) >> (64 - k as u32)
+}
+
+fn quality(challenge: &Challenge, xs: &[u64]) -> Quality {
+    // the last 5 bits of the challenge select one of the 32 leaf pairs
+    let idx = (challenge[31] & 0x1f) as usize;
+    let mut pair = [0u8; 16];
+    LittleEndian::write_u64(&mut pair[..8], xs[2 * idx]);
+    LittleEndian::write_u64(&mut pair[8..], xs[2 * idx + 1]);
+    hash(QUALITY_PERSONALIZATION, &[&challenge[..], &pair[..]])
+}
+
+impl TablePlot {
+    /// Builds all seven tables of a plot of size `2^k`.
+    pub fn create(plot_id: PlotId, k: u8) -> io::Result<Self> {
+        if k < K_MIN || k > K_MAX {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "plot size out of range"));
+        }
+
+        let mut tables = Vec::with_capacity(NUM_TABLES);
+
+        let mut current: Vec<(Entry, Meta)> = (0..(1u64 << k))
+            .map(|x| (Entry { y: f1(&plot_id, k, x), pos_l: x, pos_r: 0 }, meta1(x)))
+            .collect();
+        current.sort();
+
+        for table in 2..(NUM_TABLES as u8 + 1) {
+            let mut next = vec![];
+            // `current` is sorted, so every bucket is a contiguous range
+            let mut start = 0;
+            while start < current.len() {
+                let bucket = current[start].0.y / PARAM_BC;
+                let mut mid = start;
+                while mid < current.len() && current[mid].0.y / PARAM_BC == bucket {
+                    mid += 1;
+                }
+                let mut end = mid;
+                while end < current.len() && current[end].0.y / PARAM_BC == bucket + 1 {
+                    end += 1;
+                }
+
+                for l in start..mid {
+                    for r in mid..end {
+                        let (ref left, ref meta_l) = current[l];
+                        let (ref right, ref meta_r) = current[r];
+                        if matches(left.y, right.y) {
+                            let (y, meta) = fx(&plot_id, k, table, left.y, meta_l, meta_r);
+                            next.push((Entry { y, pos_l: l as u64, pos_r: r as u64 }, meta));
+                        }
+                    }
+                }
+                start = mid;
+            }
+            next.sort();
+
+            tables.push(current.into_iter().map(|(e, _)| e).collect());
+            current = next;
+        }
+        tables.push(current.into_iter().map(|(e, _)| e).collect());
+
+        Ok(TablePlot { k, plot_id, tables })
+    }
+
+    /// Number of entries in each table.
+    pub fn table_sizes(&self) -> Vec<usize> {
+        self.tables.iter().map(|t| t.len()).collect()
+    }
+
+    /// Collects the `x` values below the `pos`-th entry of `table` (0-based).
+    fn collect_xs(&self, table: usize, pos: usize, xs: &mut Vec<u64>) {
+        let entry = &self.tables[table][pos];
+        if table == 0 {
+            xs.push(entry.pos_l);
+        } else {
+            self.collect_xs(table - 1, entry.pos_l as usize, xs);
+            self.collect_xs(table - 1, entry.pos_r as usize, xs);
+        }
+    }
+
+    /// All proofs of space in this plot for `challenge`.
+    pub fn find_proofs(&self, challenge: &Challenge) -> Vec<TableProof> {
+        let target = challenge_target(challenge, self.k);
+        let last = &self.tables[NUM_TABLES - 1];
+        // the last table is sorted by `y`
+        let first = last.partition_point(|e| e.y >> PARAM_EXT < target);
+
+        (first..last.len())
+            .take_while(|&pos| last[pos].y >> PARAM_EXT == target)
+            .map(|pos| {
+                let mut xs = Vec::with_capacity(PROOF_SIZE);
+                self.collect_xs(NUM_TABLES - 1, pos, &mut xs);
+                let quality = quality(challenge, &xs);
+                TableProof { xs, quality }
+            })
+            .collect()
+    }
+
+    /// The proof with the lowest quality digest for `challenge`, if any.
+    pub fn best_proof(&self, challenge: &Challenge) -> Option<TableProof> {
+        self.find_proofs(challenge)
+            .into_iter()
+            .min_by(|a, b| a.quality.cmp(&b.quality))
+    }
+
+    pub fn write<W: Write>(
+        &self,
+        mut writer: W
+    ) -> io::Result<()>
+    {
+        writer.write_all(MAGIC)?;
+        writer.write_u8(self.k)?;
+        writer.write_all(&self.plot_id)?;
+        for table in &self.tables {
+            writer.write_u64::<LittleEndian>(table.len() as u64)?;
+            for e in table {
+                writer.write_u64::<LittleEndian>(e.y)?;
+                writer.write_u64::<LittleEndian>(e.pos_l)?;
+                writer.write_u64::<LittleEndian>(e.pos_r)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn read<R: Read>(
+        mut reader: R
+    ) -> io::Result<Self>
+    {
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "not a table plot"));
+        }
+
+        let k = reader.read_u8()?;
+        if k < K_MIN || k > K_MAX {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "plot size out of range"));
+        }
+        let mut plot_id = [0u8; 32];
+        reader.read_exact(&mut plot_id)?;
+
+        let mut tables = Vec::with_capacity(NUM_TABLES);
+        for t in 0..NUM_TABLES {
+            let len = reader.read_u64::<LittleEndian>()?;
+            if t == 0 && len != 1 << k {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, "the first table does not hold every x"));
+            }
+            let len = len as usize;
+            let prev_len = if t == 0 { 0 } else { tables.last().map(|p: &Vec<Entry>| p.len()).unwrap_or(0) };
+            // `len` comes from the file, so a short file must fail on its
+            // missing entries before the table is fully reserved
+            let mut table = Vec::with_capacity(cmp::min(len, READ_RESERVE));
+            for _ in 0..len {
+                let e = Entry {
+                    y: reader.read_u64::<LittleEndian>()?,
+                    pos_l: reader.read_u64::<LittleEndian>()?,
+                    pos_r: reader.read_u64::<LittleEndian>()?,
+                };
+                if t > 0 && (e.pos_l as usize >= prev_len || e.pos_r as usize >= prev_len) {
+                    return Err(io::Error::new(io::ErrorKind::InvalidData, "table entry points outside the previous table"));
+                }
+                table.push(e);
+            }
+            // `find_proofs` searches the last table by `y`
+            if table.windows(2).any(|w| w[0].y > w[1].y) {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, "table is not sorted"));
+            }
+            tables.push(table);
+        }
+
+        Ok(TablePlot { k, plot_id, tables })
+    }
+}
+
+/// Verifies a table proof of space natively and returns its quality.
+///
+/// Returns `None` if the leaves do not form matching pairs at every level,
+/// or if the resulting table 7 output does not match the challenge.
+pub fn verify(plot_id: &PlotId, k: u8, challenge: &Challenge, xs: &[u64]) -> Option<Quality> {
+    if k < K_MIN || k > K_MAX || xs.len() != PROOF_SIZE || xs.iter().any(|&x| x >> k != 0) {
+        return None;
+    }
+
+    let mut level: Vec<(u64, Meta)> = xs.iter().map(|&x| (f1(plot_id, k, x), meta1(x))).collect();
+    for table in 2..(NUM_TABLES as u8 + 1) {
+        let mut next = Vec::with_capacity(level.len() / 2);
+        for pair in level.chunks(2) {
+            let (y_l, ref meta_l) = pair[0];
+            let (y_r, ref meta_r) = pair[1];
+            if !matches(y_l, y_r) {
+                return None;
+            }
+            next.push(fx(plot_id, k, table, y_l, meta_l, meta_r));
+        }
+        level = next;
+    }
+
+    if level[0].0 >> PARAM_EXT != challenge_target(challenge, k) {
+        return None;
+    }
+
+    Some(quality(challenge, xs))
+}
+
+#[cfg(test)]
+fn challenge_for(plot: &TablePlot, pos: usize) -> Challenge {
+    let target = plot.tables[NUM_TABLES - 1][pos].y >> PARAM_EXT;
+    let mut challenge = [7u8; 32];
+    BigEndian::write_u64(&mut challenge[..8], target << (64 - plot.k as u32));
+    challenge
+}
+
+#[test]
+fn test_table_plot_proof() {
+    let plot = TablePlot::create([3u8; 32], 12).unwrap();
+    let sizes = plot.table_sizes();
+    assert_eq!(sizes[0], 1 << 12);
+    assert!(sizes[NUM_TABLES - 1] > 0);
+
+    let challenge = challenge_for(&plot, sizes[NUM_TABLES - 1] / 2);
+    let proof = plot.best_proof(&challenge).unwrap();
+    assert_eq!(proof.xs.len(), PROOF_SIZE);
+    assert_eq!(verify(&plot.plot_id, plot.k, &challenge, &proof.xs), Some(proof.quality));
+
+    // a different plot id, or any changed leaf, breaks the proof
+    assert_eq!(verify(&[4u8; 32], plot.k, &challenge, &proof.xs), None);
+    let mut xs = proof.xs.clone();
+    xs[5] ^= 1;
+    assert_eq!(verify(&plot.plot_id, plot.k, &challenge, &xs), None);
+}
+
+#[test]
+fn test_table_plot_serialization() {
+    let plot = TablePlot::create([9u8; 32], 10).unwrap();
+    let mut v = vec![];
+    plot.write(&mut v).unwrap();
+    let de_plot = TablePlot::read(&v[..]).unwrap();
+    assert_eq!(plot, de_plot);
+
+    // table lengths are not trusted
+    let first = MAGIC.len() + 1 + 32;
+    let mut w = v.clone();
+    LittleEndian::write_u64(&mut w[first..first + 8], 1 << 11);
+    assert!(TablePlot::read(&w[..]).is_err());
+    let second = first + 8 + 24 * plot.table_sizes()[0];
+    LittleEndian::write_u64(&mut w[first..first + 8], 1 << 10);
+    LittleEndian::write_u64(&mut w[second..second + 8], u64::max_value());
+    assert!(TablePlot::read(&w[..]).is_err());
+
+    // nor is the order of the entries
+    let mut w = v.clone();
+    LittleEndian::write_u64(&mut w[first + 8..first + 16], u64::max_value());
+    assert!(TablePlot::read(&w[..]).is_err());
+
+    v[0] = b'X';
+    assert!(TablePlot::read(&v[..]).is_err());
+}
@@ -0,0 +1,514 @@
+//! Powers-of-tau ceremony (phase 1).
+//!
+//! Parameters of any circuit are built from powers of a secret `tau`, times
+//! secrets `alpha` and `beta`. An `Accumulator` holds these powers for
+//! circuits of up to `2^exp` constraints; every participant multiplies
+//! `tau`, `alpha` and `beta` by secrets of their own with `contribute` and
+//! publishes the new accumulator with the returned `PublicKey`. Nobody knows
+//! the final secrets as long as one participant forgot theirs.
+//!
+//! `verify_contribution` checks one contribution with pairings. The
+//! ceremony ends with `apply_beacon`, a contribution derived from a public
+//! random value nobody could predict, e.g. a block hash, so that the last
+//! participant cannot steer the result. `lagrange` then evaluates the
+//! powers in the Lagrange basis of a circuit's domain for
+//! `generate_parameters_from_phase1` and `MPCParameters::from_phase1`.
+//!
+//! ## File format
+//!
+//! Big endian, points uncompressed:
+//!
+//! ```text
+//! n                     u32, a power of two, at least 2
+//! tau_powers_g1         2n - 1 G1, tau^i
+//! tau_powers_g2         n G2, tau^i
+//! alpha_tau_powers_g1   n G1, alpha * tau^i
+//! beta_tau_powers_g1    n G1, beta * tau^i
+//! beta_g2               G2
+//! ```
+//!
+//! A `PublicKey` is written as `tau_g1`, `alpha_g1` and `beta_g1`, two G1
+//! each, followed by `tau_g2`, `alpha_g2` and `beta_g2`.
+
+use std::io::{self, Read, Write};
+
+use bellman::domain::{EvaluationDomain, Point};
+use bellman::groth16::Phase1;
+use bellman::multicore::Worker;
+use blake2_rfc::blake2b::Blake2b;
+use byteorder::{BigEndian, ByteOrder, ReadBytesExt, WriteBytesExt};
+use ff::{Field, PrimeField};
+use pairing::{CurveAffine, CurveProjective, Engine};
+use rand::{ChaChaRng, Rng, SeedableRng};
+
+use error::{Error, Result};
+use mpc::{HashWriter, hash_to_g2, merge_pairs, read_point, same_ratio, write_point};
+
+/// Powers of the secrets of a phase 1 ceremony.
+#[derive(Clone)]
+pub struct Accumulator<E: Engine> {
+    /// `tau^i` for `i` below `2n - 1`
+    pub tau_powers_g1: Vec<E::G1Affine>,
+    /// `tau^i` for `i` below `n`
+    pub tau_powers_g2: Vec<E::G2Affine>,
+    /// `alpha * tau^i` for `i` below `n`
+    pub alpha_tau_powers_g1: Vec<E::G1Affine>,
+    /// `beta * tau^i` for `i` below `n`
+    pub beta_tau_powers_g1: Vec<E::G1Affine>,
+    pub beta_g2: E::G2Affine,
+}
+
+/// Proof that a contribution multiplied the secrets by known values.
+///
+/// For each secret `x`, a random `s` and `s * x` in G1, and `r * x` in G2,
+/// where `r` is derived from the accumulator before, `s` and `s * x`.
+#[derive(Clone)]
+pub struct PublicKey<E: Engine> {
+    tau_g1: (E::G1Affine, E::G1Affine),
+    alpha_g1: (E::G1Affine, E::G1Affine),
+    beta_g1: (E::G1Affine, E::G1Affine),
+    tau_g2: E::G2Affine,
+    alpha_g2: E::G2Affine,
+    beta_g2: E::G2Affine,
+}
+
+impl<E: Engine> PartialEq for Accumulator<E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.tau_powers_g1 == other.tau_powers_g1 &&
+        self.tau_powers_g2 == other.tau_powers_g2 &&
+        self.alpha_tau_powers_g1 == other.alpha_tau_powers_g1 &&
+        self.beta_tau_powers_g1 == other.beta_tau_powers_g1 &&
+        self.beta_g2 == other.beta_g2
+    }
+}
+
+impl<E: Engine> PartialEq for PublicKey<E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.tau_g1 == other.tau_g1 &&
+        self.alpha_g1 == other.alpha_g1 &&
+        self.beta_g1 == other.beta_g1 &&
+        self.tau_g2 == other.tau_g2 &&
+        self.alpha_g2 == other.alpha_g2 &&
+        self.beta_g2 == other.beta_g2
+    }
+}
+
+impl<E: Engine> PublicKey<E> {
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        for &(s, s_x) in &[self.tau_g1, self.alpha_g1, self.beta_g1] {
+            write_point(&mut writer, &s)?;
+            write_point(&mut writer, &s_x)?;
+        }
+        write_point(&mut writer, &self.tau_g2)?;
+        write_point(&mut writer, &self.alpha_g2)?;
+        write_point(&mut writer, &self.beta_g2)
+    }
+
+    pub fn read<R: Read>(mut reader: R) -> io::Result<PublicKey<E>> {
+        let tau_g1 = (read_point(&mut reader)?, read_point(&mut reader)?);
+        let alpha_g1 = (read_point(&mut reader)?, read_point(&mut reader)?);
+        let beta_g1 = (read_point(&mut reader)?, read_point(&mut reader)?);
+
+        Ok(PublicKey {
+            tau_g1,
+            alpha_g1,
+            beta_g1,
+            tau_g2: read_point(&mut reader)?,
+            alpha_g2: read_point(&mut reader)?,
+            beta_g2: read_point(&mut reader)?,
+        })
+    }
+}
+
+fn invalid(message: &str) -> Error {
+    Error::InvalidInput(message.to_string())
+}
+
+/// The `r` a proof of knowledge of `x` in `s_x = s * x` is checked against.
+fn compute_g2_s<E: Engine>(digest: &[u8; 64], s: &E::G1Affine, s_x: &E::G1Affine, personalization: u8) -> E::G2Affine {
+    let mut sink = HashWriter::new();
+    sink.write_all(digest).expect("hashing does not fail");
+    sink.write_all(&[personalization]).expect("hashing does not fail");
+    write_point(&mut sink, s).expect("hashing does not fail");
+    write_point(&mut sink, s_x).expect("hashing does not fail");
+
+    hash_to_g2::<E>(&sink.into_hash()).into_affine()
+}
+
+/// Random linear combinations of consecutive elements of `v`, which have the
+/// ratio of `v[1] / v[0]` if `v` holds powers.
+fn power_pairs<G: CurveAffine, R: Rng>(v: &[G], rng: &mut R) -> (G, G) {
+    merge_pairs(&v[0..v.len() - 1], &v[1..], rng)
+}
+
+/// Multiplies `points[i]` by `powers[i]`, times `coeff` if given.
+fn mul_powers<G: CurveAffine>(points: &mut [G], powers: &[G::Scalar], coeff: Option<G::Scalar>) {
+    let mut projective: Vec<G::Projective> = points.iter().zip(powers.iter()).map(|(p, power)| {
+        let mut exp = *power;
+        if let Some(ref coeff) = coeff {
+            exp.mul_assign(coeff);
+        }
+        p.mul(exp)
+    }).collect();
+    G::Projective::batch_normalization(&mut projective);
+    for (p, q) in points.iter_mut().zip(projective.iter()) {
+        *p = q.into_affine();
+    }
+}
+
+/// The coefficients of the polynomial whose evaluations at the domain are
+/// `powers`, i.e. `L_i(tau)` if `powers` are `tau^i`.
+fn lagrange_coeffs<E, G>(powers: &[G::Affine], worker: &Worker) -> Result<Vec<G::Affine>>
+    where E: Engine, G: CurveProjective<Engine = E>
+{
+    let points = powers.iter().map(|p| Point(p.into_projective())).collect();
+    let mut domain = EvaluationDomain::<E, Point<G>>::from_coeffs(points)?;
+    domain.ifft(worker);
+    let mut points: Vec<G> = domain.into_coeffs().into_iter().map(|p| p.0).collect();
+    G::batch_normalization(&mut points);
+
+    Ok(points.into_iter().map(|p| p.into_affine()).collect())
+}
+
+impl<E: Engine> Accumulator<E> {
+    /// The accumulator before any contribution, for circuits of up to
+    /// `2^exp` constraints. All secrets are one.
+    ///
+    /// `exp` is at least 1, since the checks compare consecutive powers, and
+    /// at most the two-adicity of the scalar field, the largest domain.
+    pub fn new(exp: u32) -> Result<Accumulator<E>> {
+        if exp == 0 || exp > E::Fr::S {
+            return Err(Error::InvalidInput(format!("exp must be between 1 and {}, not {}", E::Fr::S, exp)));
+        }
+        let n = 1usize << exp;
+
+        Ok(Accumulator {
+            tau_powers_g1: vec![E::G1Affine::one(); 2 * n - 1],
+            tau_powers_g2: vec![E::G2Affine::one(); n],
+            alpha_tau_powers_g1: vec![E::G1Affine::one(); n],
+            beta_tau_powers_g1: vec![E::G1Affine::one(); n],
+            beta_g2: E::G2Affine::one(),
+        })
+    }
+
+    /// Number of powers in G2, the largest domain the accumulator serves.
+    pub fn size(&self) -> usize {
+        self.tau_powers_g2.len()
+    }
+
+    /// Hash of the accumulator, which contributions on top of it commit to.
+    pub fn hash(&self) -> [u8; 64] {
+        let mut sink = HashWriter::new();
+        self.write(&mut sink).expect("hashing does not fail");
+        sink.into_hash()
+    }
+
+    /// Multiplies the secrets by secrets drawn from `rng`, which are dropped
+    /// before returning.
+    pub fn contribute<R: Rng>(&mut self, rng: &mut R) -> PublicKey<E> {
+        let digest = self.hash();
+        let tau: E::Fr = rng.gen();
+        let alpha: E::Fr = rng.gen();
+        let beta: E::Fr = rng.gen();
+
+        let mut prove = |x: E::Fr, personalization: u8| {
+            let s = rng.gen::<E::G1>().into_affine();
+            let s_x = s.mul(x).into_affine();
+            let r = compute_g2_s::<E>(&digest, &s, &s_x, personalization);
+            ((s, s_x), r.mul(x).into_affine())
+        };
+        let (tau_g1, tau_g2) = prove(tau, 0);
+        let (alpha_g1, alpha_g2) = prove(alpha, 1);
+        let (beta_g1, beta_g2) = prove(beta, 2);
+
+        let mut powers = Vec::with_capacity(self.tau_powers_g1.len());
+        let mut power = E::Fr::one();
+        for _ in 0..self.tau_powers_g1.len() {
+            powers.push(power);
+            power.mul_assign(&tau);
+        }
+        mul_powers(&mut self.tau_powers_g1, &powers, None);
+        mul_powers(&mut self.tau_powers_g2, &powers, None);
+        mul_powers(&mut self.alpha_tau_powers_g1, &powers, Some(alpha));
+        mul_powers(&mut self.beta_tau_powers_g1, &powers, Some(beta));
+        self.beta_g2 = self.beta_g2.mul(beta).into_affine();
+
+        PublicKey { tau_g1, alpha_g1, beta_g1, tau_g2, alpha_g2, beta_g2 }
+    }
+
+    /// Contributes secrets derived from `beacon` by hashing it
+    /// `2^iterations_exp` times, so that everyone can recompute them but
+    /// nobody could choose them. `iterations_exp` is below 64.
+    pub fn apply_beacon(&mut self, beacon: &[u8], iterations_exp: u32) -> Result<PublicKey<E>> {
+        if iterations_exp >= 64 {
+            return Err(Error::InvalidInput(format!("{} iterations are too many", iterations_exp)));
+        }
+
+        let mut hasher = Blake2b::new(64);
+        hasher.update(beacon);
+        let mut hash = hasher.finalize();
+        for _ in 0..(1u64 << iterations_exp) {
+            let mut hasher = Blake2b::new(64);
+            hasher.update(hash.as_ref());
+            hash = hasher.finalize();
+        }
+
+        let seed: Vec<u32> = hash.as_ref()[..32].chunks(4).map(BigEndian::read_u32).collect();
+        Ok(self.contribute(&mut ChaChaRng::from_seed(&seed)))
+    }
+
+    /// Checks that `after` is `before` with the secrets multiplied by the
+    /// ones `key` proves knowledge of.
+    pub fn verify_contribution(before: &Accumulator<E>, after: &Accumulator<E>, key: &PublicKey<E>) -> Result<()> {
+        if before.size() != after.size() || before.tau_powers_g1.len() != after.tau_powers_g1.len() {
+            return Err(invalid("the accumulators differ in size"));
+        }
+
+        let digest = before.hash();
+        let tau_r = compute_g2_s::<E>(&digest, &key.tau_g1.0, &key.tau_g1.1, 0);
+        let alpha_r = compute_g2_s::<E>(&digest, &key.alpha_g1.0, &key.alpha_g1.1, 1);
+        let beta_r = compute_g2_s::<E>(&digest, &key.beta_g1.0, &key.beta_g1.1, 2);
+
+        let checks = [
+            // the participant knows their secrets
+            (same_ratio::<E>(key.tau_g1, (tau_r, key.tau_g2)), "no proof of knowledge of tau"),
+            (same_ratio::<E>(key.alpha_g1, (alpha_r, key.alpha_g2)), "no proof of knowledge of alpha"),
+            (same_ratio::<E>(key.beta_g1, (beta_r, key.beta_g2)), "no proof of knowledge of beta"),
+            // the generators did not change
+            (after.tau_powers_g1[0] == E::G1Affine::one(), "tau^0 in G1 is not the generator"),
+            (after.tau_powers_g2[0] == E::G2Affine::one(), "tau^0 in G2 is not the generator"),
+            // the secrets were multiplied by the ones of the key
+            (
+                same_ratio::<E>((before.tau_powers_g1[1], after.tau_powers_g1[1]), (tau_r, key.tau_g2)),
+                "tau was not multiplied by the contribution",
+            ),
+            (
+                same_ratio::<E>((before.alpha_tau_powers_g1[0], after.alpha_tau_powers_g1[0]), (alpha_r, key.alpha_g2)),
+                "alpha was not multiplied by the contribution",
+            ),
+            (
+                same_ratio::<E>((before.beta_tau_powers_g1[0], after.beta_tau_powers_g1[0]), (beta_r, key.beta_g2)),
+                "beta was not multiplied by the contribution",
+            ),
+            (
+                same_ratio::<E>((before.beta_tau_powers_g1[0], after.beta_tau_powers_g1[0]), (before.beta_g2, after.beta_g2)),
+                "beta differs in G1 and G2",
+            ),
+        ];
+        for &(ok, message) in &checks {
+            if !ok {
+                return Err(invalid(message));
+            }
+        }
+
+        after.verify_powers()
+    }
+
+    /// Checks that `after` is `before` with the contribution of `beacon`.
+    pub fn verify_beacon(before: &Accumulator<E>, after: &Accumulator<E>, beacon: &[u8], iterations_exp: u32) -> Result<()> {
+        let mut expected = before.clone();
+        expected.apply_beacon(beacon, iterations_exp)?;
+        if &expected != after {
+            return Err(invalid("the accumulator is not the one of the beacon"));
+        }
+
+        Ok(())
+    }
+
+    /// Checks that all vectors hold consecutive powers of the same `tau`.
+    fn verify_powers(&self) -> Result<()> {
+        let rng = &mut ::rand::OsRng::new()?;
+        let g1 = (self.tau_powers_g1[0], self.tau_powers_g1[1]);
+        let g2 = (self.tau_powers_g2[0], self.tau_powers_g2[1]);
+
+        if !same_ratio::<E>(power_pairs(&self.tau_powers_g1, rng), g2) {
+            return Err(invalid("the G1 powers are not powers of tau"));
+        }
+        if !same_ratio::<E>(g1, power_pairs(&self.tau_powers_g2, rng)) {
+            return Err(invalid("the G2 powers are not powers of tau"));
+        }
+        if !same_ratio::<E>(power_pairs(&self.alpha_tau_powers_g1, rng), g2) {
+            return Err(invalid("the alpha powers are not powers of tau"));
+        }
+        if !same_ratio::<E>(power_pairs(&self.beta_tau_powers_g1, rng), g2) {
+            return Err(invalid("the beta powers are not powers of tau"));
+        }
+
+        Ok(())
+    }
+
+    /// The powers in the Lagrange basis of the domain of `2^exp` points, for
+    /// circuits whose `domain_exp` is `exp`.
+    pub fn lagrange(&self, exp: u32) -> Result<Phase1<E>> {
+        let m = 1usize << exp;
+        if m > self.size() {
+            return Err(Error::InvalidInput(format!("the accumulator has {} powers, not {}", self.size(), m)));
+        }
+
+        let worker = Worker::new();
+        let coeffs_g1 = lagrange_coeffs::<E, E::G1>(&self.tau_powers_g1[..m], &worker)?;
+        let coeffs_g2 = lagrange_coeffs::<E, E::G2>(&self.tau_powers_g2[..m], &worker)?;
+        let alpha_coeffs_g1 = lagrange_coeffs::<E, E::G1>(&self.alpha_tau_powers_g1[..m], &worker)?;
+        let beta_coeffs_g1 = lagrange_coeffs::<E, E::G1>(&self.beta_tau_powers_g1[..m], &worker)?;
+
+        // tau^i * t(tau) = tau^(i + m) - tau^i
+        let mut h: Vec<E::G1> = (0..m - 1).map(|i| {
+            let mut p = self.tau_powers_g1[i + m].into_projective();
+            p.sub_assign(&self.tau_powers_g1[i].into_projective());
+            p
+        }).collect();
+        E::G1::batch_normalization(&mut h);
+
+        Ok(Phase1 {
+            coeffs_g1,
+            coeffs_g2,
+            alpha_coeffs_g1,
+            beta_coeffs_g1,
+            h: h.into_iter().map(|p| p.into_affine()).collect(),
+            alpha_g1: self.alpha_tau_powers_g1[0],
+            beta_g1: self.beta_tau_powers_g1[0],
+            beta_g2: self.beta_g2,
+        })
+    }
+
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_u32::<BigEndian>(self.size() as u32)?;
+        for p in &self.tau_powers_g1 {
+            write_point(&mut writer, p)?;
+        }
+        for p in &self.tau_powers_g2 {
+            write_point(&mut writer, p)?;
+        }
+        for p in &self.alpha_tau_powers_g1 {
+            write_point(&mut writer, p)?;
+        }
+        for p in &self.beta_tau_powers_g1 {
+            write_point(&mut writer, p)?;
+        }
+        write_point(&mut writer, &self.beta_g2)
+    }
+
+    pub fn read<R: Read>(mut reader: R) -> io::Result<Accumulator<E>> {
+        let n = reader.read_u32::<BigEndian>()? as usize;
+        if n < 2 || !n.is_power_of_two() || n > 1 << E::Fr::S {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "size is not a power of two the accumulator supports"));
+        }
+
+        // `count` comes from the file, so the vectors grow as the points
+        // are actually read rather than being reserved up front.
+        fn points<R: Read, G: CurveAffine>(mut reader: R, count: usize) -> io::Result<Vec<G>> {
+            let mut points = vec![];
+            for _ in 0..count {
+                points.push(read_point(&mut reader)?);
+            }
+            Ok(points)
+        }
+
+        Ok(Accumulator {
+            tau_powers_g1: points(&mut reader, 2 * n - 1)?,
+            tau_powers_g2: points(&mut reader, n)?,
+            alpha_tau_powers_g1: points(&mut reader, n)?,
+            beta_tau_powers_g1: points(&mut reader, n)?,
+            beta_g2: read_point(&mut reader)?,
+        })
+    }
+}
+
+#[test]
+fn test_powers_of_tau() {
+    use bellman::{Circuit, ConstraintSystem, SynthesisError};
+    use bellman::groth16::{create_random_proof, domain_exp, prepare_verifying_key, verify_proof};
+    use pairing::bn256::{Bn256, Fr};
+    use rand::XorShiftRng;
+    use mpc::MPCParameters;
+
+    /// Knowledge of a cube root of the input.
+    struct CubeRoot {
+        x: Option<Fr>,
+    }
+
+    impl Circuit<Bn256> for CubeRoot {
+        fn synthesize<CS: ConstraintSystem<Bn256>>(self, cs: &mut CS) -> ::std::result::Result<(), SynthesisError> {
+            let x_value = self.x;
+            let x2_value = x_value.map(|mut x| { x.square(); x });
+            let x3_value = x_value.and_then(|x| x2_value.map(|mut x2| { x2.mul_assign(&x); x2 }));
+            let x = cs.alloc(|| "x", || x_value.ok_or(SynthesisError::AssignmentMissing))?;
+            let x2 = cs.alloc(|| "x2", || x2_value.ok_or(SynthesisError::AssignmentMissing))?;
+            let x3 = cs.alloc_input(|| "x3", || x3_value.ok_or(SynthesisError::AssignmentMissing))?;
+            cs.enforce(|| "x2", |lc| lc + x, |lc| lc + x, |lc| lc + x2);
+            cs.enforce(|| "x3", |lc| lc + x2, |lc| lc + x, |lc| lc + x3);
+            Ok(())
+        }
+    }
+
+    let rng = &mut XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+    let exp = domain_exp(CubeRoot { x: None }).unwrap();
+    let initial = Accumulator::<Bn256>::new(exp + 1).unwrap();
+
+    let mut first = initial.clone();
+    let key = first.contribute(rng);
+    Accumulator::verify_contribution(&initial, &first, &key).unwrap();
+    let mut v = vec![];
+    key.write(&mut v).unwrap();
+    assert!(PublicKey::<Bn256>::read(&v[..]).unwrap() == key);
+
+    let mut second = first.clone();
+    let key = second.contribute(rng);
+    Accumulator::verify_contribution(&first, &second, &key).unwrap();
+    // a key only vouches for the contribution it was made for
+    assert!(Accumulator::verify_contribution(&initial, &second, &key).is_err());
+
+    let mut last = second.clone();
+    last.apply_beacon(b"block 1000000", 4).unwrap();
+    Accumulator::verify_beacon(&second, &last, b"block 1000000", 4).unwrap();
+    assert!(Accumulator::verify_beacon(&second, &last, b"block 1000001", 4).is_err());
+    assert!(last.clone().apply_beacon(b"block 1000000", 64).is_err());
+
+    let mut v = vec![];
+    last.write(&mut v).unwrap();
+    let last = Accumulator::<Bn256>::read(&v[..]).unwrap();
+
+    // the powers serve a phase 2 ceremony
+    let phase1 = last.lagrange(exp).unwrap();
+    let initial = MPCParameters::from_phase1(CubeRoot { x: None }, &phase1).unwrap();
+    assert!(initial == MPCParameters::from_phase1(CubeRoot { x: None }, &phase1).unwrap());
+    let mut params = initial.clone();
+    params.contribute(rng);
+    params.verify(CubeRoot { x: None }, &initial).unwrap();
+    params.verify_from_phase1(CubeRoot { x: None }, &phase1).unwrap();
+    assert!(params.verify_from_phase1(CubeRoot { x: None }, &second.lagrange(exp).unwrap()).is_err());
+
+    let params = params.into_params();
+    let mut x = Fr::one();
+    x.double();
+    let mut x3 = x;
+    x3.square();
+    x3.mul_assign(&x);
+    let proof = create_random_proof(CubeRoot { x: Some(x) }, &params, rng).unwrap();
+    let pvk = prepare_verifying_key(&params.vk);
+    assert!(verify_proof(&pvk, &proof, &[x3]).unwrap());
+    assert!(!verify_proof(&pvk, &proof, &[x]).unwrap());
+
+    // another domain needs other powers
+    assert!(MPCParameters::from_phase1(CubeRoot { x: None }, &last.lagrange(exp + 1).unwrap()).is_err());
+    assert!(last.lagrange(exp + 2).is_err());
+}
+
+#[test]
+fn test_accumulator_size() {
+    use pairing::bn256::Bn256;
+
+    // a single power has no ratio to check
+    assert!(Accumulator::<Bn256>::new(0).is_err());
+    assert!(Accumulator::<Bn256>::new(29).is_err());
+
+    let mut v = vec![];
+    Accumulator::<Bn256>::new(1).unwrap().write(&mut v).unwrap();
+    assert!(Accumulator::<Bn256>::read(&v[..]).unwrap().size() == 2);
+    BigEndian::write_u32(&mut v[..4], 1);
+    assert!(Accumulator::<Bn256>::read(&v[..]).is_err());
+
+    // a huge size fails on the missing points, not by reserving memory
+    BigEndian::write_u32(&mut v[..4], 1 << 28);
+    assert!(Accumulator::<Bn256>::read(&v[..]).is_err());
+}
@@ -0,0 +1,701 @@
+// This file is generated by rust-protobuf 2.28.0. Do not edit
+// @generated
+
+// https://github.com/rust-lang/rust-clippy/issues/702
+#![allow(unknown_lints)]
+#![allow(clippy::all)]
+
+#![allow(unused_attributes)]
+#![cfg_attr(rustfmt, rustfmt::skip)]
+
+#![allow(box_pointers)]
+#![allow(dead_code)]
+#![allow(missing_docs)]
+#![allow(non_camel_case_types)]
+#![allow(non_snake_case)]
+#![allow(non_upper_case_globals)]
+#![allow(trivial_casts)]
+#![allow(unused_imports)]
+#![allow(unused_results)]
+//! Generated file from `groth16.proto`
+
+/// Generated files are compatible only with the same version
+/// of protobuf runtime.
+// const _PROTOBUF_VERSION_CHECK: () = ::protobuf::VERSION_2_28_0;
+
+#[derive(PartialEq,Clone,Default)]
+pub struct Groth16ProofProto {
+    // message fields
+    pub a: ::std::vec::Vec<u8>,
+    pub b: ::std::vec::Vec<u8>,
+    pub c: ::std::vec::Vec<u8>,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a Groth16ProofProto {
+    fn default() -> &'a Groth16ProofProto {
+        <Groth16ProofProto as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl Groth16ProofProto {
+    pub fn new() -> Groth16ProofProto {
+        ::std::default::Default::default()
+    }
+
+    // bytes a = 1;
+
+
+    pub fn get_a(&self) -> &[u8] {
+        &self.a
+    }
+    pub fn clear_a(&mut self) {
+        self.a.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_a(&mut self, v: ::std::vec::Vec<u8>) {
+        self.a = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_a(&mut self) -> &mut ::std::vec::Vec<u8> {
+        &mut self.a
+    }
+
+    // Take field
+    pub fn take_a(&mut self) -> ::std::vec::Vec<u8> {
+        ::std::mem::replace(&mut self.a, ::std::vec::Vec::new())
+    }
+
+    // bytes b = 2;
+
+
+    pub fn get_b(&self) -> &[u8] {
+        &self.b
+    }
+    pub fn clear_b(&mut self) {
+        self.b.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_b(&mut self, v: ::std::vec::Vec<u8>) {
+        self.b = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_b(&mut self) -> &mut ::std::vec::Vec<u8> {
+        &mut self.b
+    }
+
+    // Take field
+    pub fn take_b(&mut self) -> ::std::vec::Vec<u8> {
+        ::std::mem::replace(&mut self.b, ::std::vec::Vec::new())
+    }
+
+    // bytes c = 3;
+
+
+    pub fn get_c(&self) -> &[u8] {
+        &self.c
+    }
+    pub fn clear_c(&mut self) {
+        self.c.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_c(&mut self, v: ::std::vec::Vec<u8>) {
+        self.c = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_c(&mut self) -> &mut ::std::vec::Vec<u8> {
+        &mut self.c
+    }
+
+    // Take field
+    pub fn take_c(&mut self) -> ::std::vec::Vec<u8> {
+        ::std::mem::replace(&mut self.c, ::std::vec::Vec::new())
+    }
+}
+
+impl ::protobuf::Message for Groth16ProofProto {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_proto3_bytes_into(wire_type, is, &mut self.a)?;
+                },
+                2 => {
+                    ::protobuf::rt::read_singular_proto3_bytes_into(wire_type, is, &mut self.b)?;
+                },
+                3 => {
+                    ::protobuf::rt::read_singular_proto3_bytes_into(wire_type, is, &mut self.c)?;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if !self.a.is_empty() {
+            my_size += ::protobuf::rt::bytes_size(1, &self.a);
+        }
+        if !self.b.is_empty() {
+            my_size += ::protobuf::rt::bytes_size(2, &self.b);
+        }
+        if !self.c.is_empty() {
+            my_size += ::protobuf::rt::bytes_size(3, &self.c);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if !self.a.is_empty() {
+            os.write_bytes(1, &self.a)?;
+        }
+        if !self.b.is_empty() {
+            os.write_bytes(2, &self.b)?;
+        }
+        if !self.c.is_empty() {
+            os.write_bytes(3, &self.c)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: ::std::boxed::Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> Groth16ProofProto {
+        Groth16ProofProto::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            let mut fields = ::std::vec::Vec::new();
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBytes>(
+                "a",
+                |m: &Groth16ProofProto| { &m.a },
+                |m: &mut Groth16ProofProto| { &mut m.a },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBytes>(
+                "b",
+                |m: &Groth16ProofProto| { &m.b },
+                |m: &mut Groth16ProofProto| { &mut m.b },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBytes>(
+                "c",
+                |m: &Groth16ProofProto| { &m.c },
+                |m: &mut Groth16ProofProto| { &mut m.c },
+            ));
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<Groth16ProofProto>(
+                "Groth16ProofProto",
+                fields,
+                file_descriptor_proto()
+            )
+        })
+    }
+
+    fn default_instance() -> &'static Groth16ProofProto {
+        static instance: ::protobuf::rt::LazyV2<Groth16ProofProto> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(Groth16ProofProto::new)
+    }
+}
+
+impl ::protobuf::Clear for Groth16ProofProto {
+    fn clear(&mut self) {
+        self.a.clear();
+        self.b.clear();
+        self.c.clear();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for Groth16ProofProto {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for Groth16ProofProto {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct VerifyingKeyProto {
+    // message fields
+    pub alpha_g1: ::std::vec::Vec<u8>,
+    pub beta_g1: ::std::vec::Vec<u8>,
+    pub beta_g2: ::std::vec::Vec<u8>,
+    pub gamma_g2: ::std::vec::Vec<u8>,
+    pub delta_g1: ::std::vec::Vec<u8>,
+    pub delta_g2: ::std::vec::Vec<u8>,
+    pub ic: ::protobuf::RepeatedField<::std::vec::Vec<u8>>,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a VerifyingKeyProto {
+    fn default() -> &'a VerifyingKeyProto {
+        <VerifyingKeyProto as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl VerifyingKeyProto {
+    pub fn new() -> VerifyingKeyProto {
+        ::std::default::Default::default()
+    }
+
+    // bytes alpha_g1 = 1;
+
+
+    pub fn get_alpha_g1(&self) -> &[u8] {
+        &self.alpha_g1
+    }
+    pub fn clear_alpha_g1(&mut self) {
+        self.alpha_g1.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_alpha_g1(&mut self, v: ::std::vec::Vec<u8>) {
+        self.alpha_g1 = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_alpha_g1(&mut self) -> &mut ::std::vec::Vec<u8> {
+        &mut self.alpha_g1
+    }
+
+    // Take field
+    pub fn take_alpha_g1(&mut self) -> ::std::vec::Vec<u8> {
+        ::std::mem::replace(&mut self.alpha_g1, ::std::vec::Vec::new())
+    }
+
+    // bytes beta_g1 = 2;
+
+
+    pub fn get_beta_g1(&self) -> &[u8] {
+        &self.beta_g1
+    }
+    pub fn clear_beta_g1(&mut self) {
+        self.beta_g1.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_beta_g1(&mut self, v: ::std::vec::Vec<u8>) {
+        self.beta_g1 = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_beta_g1(&mut self) -> &mut ::std::vec::Vec<u8> {
+        &mut self.beta_g1
+    }
+
+    // Take field
+    pub fn take_beta_g1(&mut self) -> ::std::vec::Vec<u8> {
+        ::std::mem::replace(&mut self.beta_g1, ::std::vec::Vec::new())
+    }
+
+    // bytes beta_g2 = 3;
+
+
+    pub fn get_beta_g2(&self) -> &[u8] {
+        &self.beta_g2
+    }
+    pub fn clear_beta_g2(&mut self) {
+        self.beta_g2.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_beta_g2(&mut self, v: ::std::vec::Vec<u8>) {
+        self.beta_g2 = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_beta_g2(&mut self) -> &mut ::std::vec::Vec<u8> {
+        &mut self.beta_g2
+    }
+
+    // Take field
+    pub fn take_beta_g2(&mut self) -> ::std::vec::Vec<u8> {
+        ::std::mem::replace(&mut self.beta_g2, ::std::vec::Vec::new())
+    }
+
+    // bytes gamma_g2 = 4;
+
+
+    pub fn get_gamma_g2(&self) -> &[u8] {
+        &self.gamma_g2
+    }
+    pub fn clear_gamma_g2(&mut self) {
+        self.gamma_g2.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_gamma_g2(&mut self, v: ::std::vec::Vec<u8>) {
+        self.gamma_g2 = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_gamma_g2(&mut self) -> &mut ::std::vec::Vec<u8> {
+        &mut self.gamma_g2
+    }
+
+    // Take field
+    pub fn take_gamma_g2(&mut self) -> ::std::vec::Vec<u8> {
+        ::std::mem::replace(&mut self.gamma_g2, ::std::vec::Vec::new())
+    }
+
+    // bytes delta_g1 = 5;
+
+
+    pub fn get_delta_g1(&self) -> &[u8] {
+        &self.delta_g1
+    }
+    pub fn clear_delta_g1(&mut self) {
+        self.delta_g1.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_delta_g1(&mut self, v: ::std::vec::Vec<u8>) {
+        self.delta_g1 = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_delta_g1(&mut self) -> &mut ::std::vec::Vec<u8> {
+        &mut self.delta_g1
+    }
+
+    // Take field
+    pub fn take_delta_g1(&mut self) -> ::std::vec::Vec<u8> {
+        ::std::mem::replace(&mut self.delta_g1, ::std::vec::Vec::new())
+    }
+
+    // bytes delta_g2 = 6;
+
+
+    pub fn get_delta_g2(&self) -> &[u8] {
+        &self.delta_g2
+    }
+    pub fn clear_delta_g2(&mut self) {
+        self.delta_g2.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_delta_g2(&mut self, v: ::std::vec::Vec<u8>) {
+        self.delta_g2 = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_delta_g2(&mut self) -> &mut ::std::vec::Vec<u8> {
+        &mut self.delta_g2
+    }
+
+    // Take field
+    pub fn take_delta_g2(&mut self) -> ::std::vec::Vec<u8> {
+        ::std::mem::replace(&mut self.delta_g2, ::std::vec::Vec::new())
+    }
+
+    // repeated bytes ic = 7;
+
+
+    pub fn get_ic(&self) -> &[::std::vec::Vec<u8>] {
+        &self.ic
+    }
+    pub fn clear_ic(&mut self) {
+        self.ic.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_ic(&mut self, v: ::protobuf::RepeatedField<::std::vec::Vec<u8>>) {
+        self.ic = v;
+    }
+
+    // Mutable pointer to the field.
+    pub fn mut_ic(&mut self) -> &mut ::protobuf::RepeatedField<::std::vec::Vec<u8>> {
+        &mut self.ic
+    }
+
+    // Take field
+    pub fn take_ic(&mut self) -> ::protobuf::RepeatedField<::std::vec::Vec<u8>> {
+        ::std::mem::replace(&mut self.ic, ::protobuf::RepeatedField::new())
+    }
+}
+
+impl ::protobuf::Message for VerifyingKeyProto {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_proto3_bytes_into(wire_type, is, &mut self.alpha_g1)?;
+                },
+                2 => {
+                    ::protobuf::rt::read_singular_proto3_bytes_into(wire_type, is, &mut self.beta_g1)?;
+                },
+                3 => {
+                    ::protobuf::rt::read_singular_proto3_bytes_into(wire_type, is, &mut self.beta_g2)?;
+                },
+                4 => {
+                    ::protobuf::rt::read_singular_proto3_bytes_into(wire_type, is, &mut self.gamma_g2)?;
+                },
+                5 => {
+                    ::protobuf::rt::read_singular_proto3_bytes_into(wire_type, is, &mut self.delta_g1)?;
+                },
+                6 => {
+                    ::protobuf::rt::read_singular_proto3_bytes_into(wire_type, is, &mut self.delta_g2)?;
+                },
+                7 => {
+                    ::protobuf::rt::read_repeated_bytes_into(wire_type, is, &mut self.ic)?;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if !self.alpha_g1.is_empty() {
+            my_size += ::protobuf::rt::bytes_size(1, &self.alpha_g1);
+        }
+        if !self.beta_g1.is_empty() {
+            my_size += ::protobuf::rt::bytes_size(2, &self.beta_g1);
+        }
+        if !self.beta_g2.is_empty() {
+            my_size += ::protobuf::rt::bytes_size(3, &self.beta_g2);
+        }
+        if !self.gamma_g2.is_empty() {
+            my_size += ::protobuf::rt::bytes_size(4, &self.gamma_g2);
+        }
+        if !self.delta_g1.is_empty() {
+            my_size += ::protobuf::rt::bytes_size(5, &self.delta_g1);
+        }
+        if !self.delta_g2.is_empty() {
+            my_size += ::protobuf::rt::bytes_size(6, &self.delta_g2);
+        }
+        for value in &self.ic {
+            my_size += ::protobuf::rt::bytes_size(7, &value);
+        };
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if !self.alpha_g1.is_empty() {
+            os.write_bytes(1, &self.alpha_g1)?;
+        }
+        if !self.beta_g1.is_empty() {
+            os.write_bytes(2, &self.beta_g1)?;
+        }
+        if !self.beta_g2.is_empty() {
+            os.write_bytes(3, &self.beta_g2)?;
+        }
+        if !self.gamma_g2.is_empty() {
+            os.write_bytes(4, &self.gamma_g2)?;
+        }
+        if !self.delta_g1.is_empty() {
+            os.write_bytes(5, &self.delta_g1)?;
+        }
+        if !self.delta_g2.is_empty() {
+            os.write_bytes(6, &self.delta_g2)?;
+        }
+        for v in &self.ic {
+            os.write_bytes(7, &v)?;
+        };
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: ::std::boxed::Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> VerifyingKeyProto {
+        VerifyingKeyProto::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static descriptor: ::protobuf::rt::LazyV2<::protobuf::reflect::MessageDescriptor> = ::protobuf::rt::LazyV2::INIT;
+        descriptor.get(|| {
+            let mut fields = ::std::vec::Vec::new();
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBytes>(
+                "alpha_g1",
+                |m: &VerifyingKeyProto| { &m.alpha_g1 },
+                |m: &mut VerifyingKeyProto| { &mut m.alpha_g1 },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBytes>(
+                "beta_g1",
+                |m: &VerifyingKeyProto| { &m.beta_g1 },
+                |m: &mut VerifyingKeyProto| { &mut m.beta_g1 },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBytes>(
+                "beta_g2",
+                |m: &VerifyingKeyProto| { &m.beta_g2 },
+                |m: &mut VerifyingKeyProto| { &mut m.beta_g2 },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBytes>(
+                "gamma_g2",
+                |m: &VerifyingKeyProto| { &m.gamma_g2 },
+                |m: &mut VerifyingKeyProto| { &mut m.gamma_g2 },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBytes>(
+                "delta_g1",
+                |m: &VerifyingKeyProto| { &m.delta_g1 },
+                |m: &mut VerifyingKeyProto| { &mut m.delta_g1 },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBytes>(
+                "delta_g2",
+                |m: &VerifyingKeyProto| { &m.delta_g2 },
+                |m: &mut VerifyingKeyProto| { &mut m.delta_g2 },
+            ));
+            fields.push(::protobuf::reflect::accessor::make_repeated_field_accessor::<_, ::protobuf::types::ProtobufTypeBytes>(
+                "ic",
+                |m: &VerifyingKeyProto| { &m.ic },
+                |m: &mut VerifyingKeyProto| { &mut m.ic },
+            ));
+            ::protobuf::reflect::MessageDescriptor::new_pb_name::<VerifyingKeyProto>(
+                "VerifyingKeyProto",
+                fields,
+                file_descriptor_proto()
+            )
+        })
+    }
+
+    fn default_instance() -> &'static VerifyingKeyProto {
+        static instance: ::protobuf::rt::LazyV2<VerifyingKeyProto> = ::protobuf::rt::LazyV2::INIT;
+        instance.get(VerifyingKeyProto::new)
+    }
+}
+
+impl ::protobuf::Clear for VerifyingKeyProto {
+    fn clear(&mut self) {
+        self.alpha_g1.clear();
+        self.beta_g1.clear();
+        self.beta_g2.clear();
+        self.gamma_g2.clear();
+        self.delta_g1.clear();
+        self.delta_g2.clear();
+        self.ic.clear();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for VerifyingKeyProto {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for VerifyingKeyProto {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
+    }
+}
+
+static file_descriptor_proto_data: &'static [u8] = b"\
+    \n\rgroth16.proto\x12\nspaceproof\"E\n\x11Groth16ProofProto\x12\x0e\n\
+    \x01a\x18\x01\x20\x01(\x0cR\x01aB\0\x12\x0e\n\x01b\x18\x02\x20\x01(\x0cR\
+    \x01bB\0\x12\x0e\n\x01c\x18\x03\x20\x01(\x0cR\x01cB\0:\0\"\xd1\x01\n\x11\
+    VerifyingKeyProto\x12\x1b\n\x08alpha_g1\x18\x01\x20\x01(\x0cR\x07alphaG1\
+    B\0\x12\x19\n\x07beta_g1\x18\x02\x20\x01(\x0cR\x06betaG1B\0\x12\x19\n\
+    \x07beta_g2\x18\x03\x20\x01(\x0cR\x06betaG2B\0\x12\x1b\n\x08gamma_g2\x18\
+    \x04\x20\x01(\x0cR\x07gammaG2B\0\x12\x1b\n\x08delta_g1\x18\x05\x20\x01(\
+    \x0cR\x07deltaG1B\0\x12\x1b\n\x08delta_g2\x18\x06\x20\x01(\x0cR\x07delta\
+    G2B\0\x12\x10\n\x02ic\x18\x07\x20\x03(\x0cR\x02icB\0:\0B\0b\x06proto3\
+";
+
+static file_descriptor_proto_lazy: ::protobuf::rt::LazyV2<::protobuf::descriptor::FileDescriptorProto> = ::protobuf::rt::LazyV2::INIT;
+
+fn parse_descriptor_proto() -> ::protobuf::descriptor::FileDescriptorProto {
+    ::protobuf::Message::parse_from_bytes(file_descriptor_proto_data).unwrap()
+}
+
+pub fn file_descriptor_proto() -> &'static ::protobuf::descriptor::FileDescriptorProto {
+    file_descriptor_proto_lazy.get(|| {
+        parse_descriptor_proto()
+    })
+}
@@ -0,0 +1,133 @@
+//! Protocol Buffers messages for Groth16 proofs and verifying keys, defined
+//! in `protobuf/groth16.proto`, along with the inclusion proof messages of
+//! `protobuf/spaceproof.proto` from the Merkle tree code.
+//!
+//! Points are encoded as in `Proof::write` and `VerifyingKey::write`, one
+//! point per field, so any protobuf implementation can pass them around and
+//! only the final reader needs to know the curve.
+
+// generated code, written for older compilers
+#[allow(unknown_lints, renamed_and_removed_lints, mismatched_lifetime_syntaxes)]
+mod groth16;
+
+use std::error::Error;
+use std::io;
+
+use bellman::groth16::{Proof, VerifyingKey};
+use pairing::{CurveAffine, EncodedPoint, Engine};
+use protobuf::RepeatedField;
+
+pub use merkle::proto::{ProofProto, LemmaProto, PositionedProto, PositionedProto_oneof_value};
+pub use self::groth16::{Groth16ProofProto, VerifyingKeyProto};
+
+fn invalid_data<E: Into<Box<dyn Error + Send + Sync>>>(e: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e)
+}
+
+fn read_point<G, P>(bytes: &[u8], field: &str) -> io::Result<G>
+where
+    G: CurveAffine,
+    P: EncodedPoint<Affine = G>,
+{
+    let mut repr = P::empty();
+    if bytes.len() != repr.as_ref().len() {
+        return Err(invalid_data(format!("{} must be {} bytes, not {}", field, repr.as_ref().len(), bytes.len())));
+    }
+    repr.as_mut().copy_from_slice(bytes);
+
+    repr.into_affine().map_err(invalid_data)
+}
+
+fn read_compressed<G: CurveAffine>(bytes: &[u8], field: &str) -> io::Result<G> {
+    read_point::<G, G::Compressed>(bytes, field)
+}
+
+fn read_uncompressed<G: CurveAffine>(bytes: &[u8], field: &str) -> io::Result<G> {
+    read_point::<G, G::Uncompressed>(bytes, field)
+}
+
+fn not_zero<G: CurveAffine>(point: G) -> io::Result<G> {
+    if point.is_zero() {
+        Err(invalid_data("point at infinity"))
+    } else {
+        Ok(point)
+    }
+}
+
+impl Groth16ProofProto {
+    pub fn from_proof<E: Engine>(proof: &Proof<E>) -> Self {
+        let mut proto = Self::new();
+        proto.set_a(proof.a.into_compressed().as_ref().to_vec());
+        proto.set_b(proof.b.into_compressed().as_ref().to_vec());
+        proto.set_c(proof.c.into_compressed().as_ref().to_vec());
+
+        proto
+    }
+
+    /// Checks the points like `Proof::read`.
+    pub fn to_proof<E: Engine>(&self) -> io::Result<Proof<E>> {
+        Ok(Proof {
+            a: not_zero(read_compressed(self.get_a(), "a")?)?,
+            b: not_zero(read_compressed(self.get_b(), "b")?)?,
+            c: not_zero(read_compressed(self.get_c(), "c")?)?,
+        })
+    }
+}
+
+impl VerifyingKeyProto {
+    pub fn from_verifying_key<E: Engine>(vk: &VerifyingKey<E>) -> Self {
+        let mut proto = Self::new();
+        proto.set_alpha_g1(vk.alpha_g1.into_uncompressed().as_ref().to_vec());
+        proto.set_beta_g1(vk.beta_g1.into_uncompressed().as_ref().to_vec());
+        proto.set_beta_g2(vk.beta_g2.into_uncompressed().as_ref().to_vec());
+        proto.set_gamma_g2(vk.gamma_g2.into_uncompressed().as_ref().to_vec());
+        proto.set_delta_g1(vk.delta_g1.into_uncompressed().as_ref().to_vec());
+        proto.set_delta_g2(vk.delta_g2.into_uncompressed().as_ref().to_vec());
+        let ic = vk.ic.iter().map(|ic| ic.into_uncompressed().as_ref().to_vec()).collect();
+        proto.set_ic(RepeatedField::from_vec(ic));
+
+        proto
+    }
+
+    /// Checks the points like `VerifyingKey::read`.
+    pub fn to_verifying_key<E: Engine>(&self) -> io::Result<VerifyingKey<E>> {
+        let mut ic = Vec::with_capacity(self.get_ic().len());
+        for point in self.get_ic() {
+            ic.push(not_zero(read_uncompressed(point, "ic")?)?);
+        }
+
+        Ok(VerifyingKey {
+            alpha_g1: read_uncompressed(self.get_alpha_g1(), "alpha_g1")?,
+            beta_g1: read_uncompressed(self.get_beta_g1(), "beta_g1")?,
+            beta_g2: read_uncompressed(self.get_beta_g2(), "beta_g2")?,
+            gamma_g2: read_uncompressed(self.get_gamma_g2(), "gamma_g2")?,
+            delta_g1: read_uncompressed(self.get_delta_g1(), "delta_g1")?,
+            delta_g2: read_uncompressed(self.get_delta_g2(), "delta_g2")?,
+            ic,
+        })
+    }
+}
+
+#[test]
+fn test_serialize_groth16_with_protobuf() {
+    use pairing::bn256::Bn256;
+    use protobuf::Message;
+    use ::{build_tree, generate, prove, Node, position_to_fr};
+
+    let seed = [1u32, 2, 3, 4];
+    let params = generate(&seed, 2).unwrap();
+    let tree = build_tree((1..5u64).map(|i| Node::new(position_to_fr(i))).collect());
+    let proof = prove(&seed, &params, &tree, 1).unwrap();
+
+    let bytes = Groth16ProofProto::from_proof(&proof).write_to_bytes().unwrap();
+    let parsed: Proof<Bn256> = Groth16ProofProto::parse_from_bytes(&bytes).unwrap().to_proof().unwrap();
+    assert!(parsed == proof);
+
+    let bytes = VerifyingKeyProto::from_verifying_key(&params.vk).write_to_bytes().unwrap();
+    let parsed: VerifyingKey<Bn256> = VerifyingKeyProto::parse_from_bytes(&bytes).unwrap().to_verifying_key().unwrap();
+    assert!(parsed == params.vk);
+
+    let mut truncated = Groth16ProofProto::from_proof(&proof);
+    truncated.mut_b().pop();
+    assert!(truncated.to_proof::<Bn256>().is_err());
+}
@@ -0,0 +1,102 @@
+//! Client of the proving service.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+#[cfg(unix)]
+use std::path::Path;
+
+use serde_json::{self, Value};
+
+use error::{Error, Result};
+
+use super::{ProveParams, ProveResult, VerifyParams, VerifyResult, RootParams, RootResult, Status, Request, Response};
+
+/// A connection to a proving service. Calls are sent one at a time and wait
+/// for their response.
+pub struct Client {
+    reader: BufReader<Box<dyn Read + Send>>,
+    writer: Box<dyn Write + Send>,
+    next_id: u64,
+}
+
+impl Client {
+    pub fn new<R, W>(reader: R, writer: W) -> Client
+        where R: Read + Send + 'static, W: Write + Send + 'static
+    {
+        Client {
+            reader: BufReader::new(Box::new(reader)),
+            writer: Box::new(writer),
+            next_id: 0,
+        }
+    }
+
+    pub fn connect<A: ToSocketAddrs>(addr: A) -> Result<Client> {
+        let stream = TcpStream::connect(addr)?;
+        Ok(Client::new(stream.try_clone()?, stream))
+    }
+
+    #[cfg(unix)]
+    pub fn connect_unix<P: AsRef<Path>>(path: P) -> Result<Client> {
+        let stream = UnixStream::connect(path)?;
+        Ok(Client::new(stream.try_clone()?, stream))
+    }
+
+    /// Calls `method` and returns its result; error responses become
+    /// `Error::Rpc`.
+    pub fn call(&mut self, method: &str, params: Value) -> Result<Value> {
+        self.next_id += 1;
+        let request = Request {
+            jsonrpc: "2.0".to_string(),
+            id: Value::from(self.next_id),
+            method: method.to_string(),
+            params,
+        };
+        let mut line = serde_json::to_string(&request).map_err(invalid_params)?;
+        line.push('\n');
+        self.writer.write_all(line.as_bytes())?;
+        self.writer.flush()?;
+
+        let mut line = String::new();
+        if self.reader.read_line(&mut line)? == 0 {
+            return Err(Error::Rpc(0, "the server closed the connection".to_string()));
+        }
+        let response: Response = serde_json::from_str(&line)
+            .map_err(|e| Error::Rpc(0, format!("malformed response: {}", e)))?;
+        if let Some(error) = response.error {
+            return Err(Error::Rpc(error.code, error.message));
+        }
+        response.result.ok_or(Error::Rpc(0, "response without result".to_string()))
+    }
+
+    pub fn prove(&mut self, params: &ProveParams) -> Result<ProveResult> {
+        let params = serde_json::to_value(params).map_err(invalid_params)?;
+        serde_json::from_value(self.call("prove", params)?).map_err(malformed_result)
+    }
+
+    pub fn verify(&mut self, params: &VerifyParams) -> Result<bool> {
+        let params = serde_json::to_value(params).map_err(invalid_params)?;
+        let result: VerifyResult = serde_json::from_value(self.call("verify", params)?).map_err(malformed_result)?;
+        Ok(result.valid)
+    }
+
+    /// Root of the tree, big endian hex.
+    pub fn root(&mut self, params: &RootParams) -> Result<String> {
+        let params = serde_json::to_value(params).map_err(invalid_params)?;
+        let result: RootResult = serde_json::from_value(self.call("root", params)?).map_err(malformed_result)?;
+        Ok(result.root)
+    }
+
+    pub fn status(&mut self) -> Result<Status> {
+        serde_json::from_value(self.call("status", Value::Null)?).map_err(malformed_result)
+    }
+}
+
+fn invalid_params(e: serde_json::Error) -> Error {
+    Error::InvalidInput(e.to_string())
+}
+
+fn malformed_result(e: serde_json::Error) -> Error {
+    Error::Rpc(0, format!("malformed result: {}", e))
+}
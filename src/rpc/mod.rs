@@ -0,0 +1,119 @@
+//! JSON-RPC 2.0 protocol of the proving service.
+//!
+//! Requests and responses are single JSON objects, one per line, over TCP or
+//! a Unix socket. Field elements are big endian hex like `Fr::to_hex`,
+//! proofs are hex of `Proof::write`.
+//!
+//! | method   | params                                    | result                   |
+//! |----------|-------------------------------------------|--------------------------|
+//! | `prove`  | `{leaves | plot, position, seed?}`        | `{proof, leaf, root}`    |
+//! | `verify` | `{proof, leaf, position, root}`           | `{valid}`                |
+//! | `root`   | `{leaves | plot}`                         | `{root}`                 |
+//! | `status` | none                                      | `Status`                 |
+//!
+//! `plot` names a Merkle plot in the server's plot directory, and `leaves`
+//! are at most `server::MAX_LEAVES`. The server is in `server` (feature
+//! `server`), the client in `client`.
+
+use serde_json::Value;
+
+pub mod client;
+#[cfg(feature = "server")]
+pub mod server;
+
+pub use self::client::Client;
+
+/// Invalid JSON was received.
+pub const PARSE_ERROR: i64 = -32700;
+/// The JSON is not a valid request object.
+pub const INVALID_REQUEST: i64 = -32600;
+/// The method does not exist.
+pub const METHOD_NOT_FOUND: i64 = -32601;
+/// Invalid method parameters.
+pub const INVALID_PARAMS: i64 = -32602;
+/// Proving or verifying failed.
+pub const SERVER_ERROR: i64 = -32000;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Request {
+    pub jsonrpc: String,
+    #[serde(default)]
+    pub id: Value,
+    pub method: String,
+    #[serde(default)]
+    pub params: Value,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Response {
+    pub jsonrpc: String,
+    pub id: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<RpcError>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+/// Either `leaves` or `plot` selects the tree.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct ProveParams {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub leaves: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plot: Option<String>,
+    pub position: u64,
+    /// Seed of the proof randomness; drawn from the system if missing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<Vec<u32>>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ProveResult {
+    pub proof: String,
+    pub leaf: String,
+    pub root: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct VerifyParams {
+    pub proof: String,
+    pub leaf: String,
+    pub position: u64,
+    pub root: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct VerifyResult {
+    pub valid: bool,
+}
+
+/// Either `leaves` or `plot` selects the tree.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct RootParams {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub leaves: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plot: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RootResult {
+    pub root: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Status {
+    /// Threads building trees and creating proofs
+    pub workers: usize,
+    /// Proofs queued or in progress
+    pub pending: usize,
+    pub proved: u64,
+    pub verified: u64,
+    pub failed: u64,
+}
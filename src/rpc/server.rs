@@ -0,0 +1,506 @@
+//! Proving service holding one set of parameters.
+//!
+//! Parameters are read once, for trees of one height, and the constraints of
+//! the circuit are recorded once for that height, see `CircuitShape`; trees
+//! of other heights are refused before they reach a worker. Proofs are
+//! created by a fixed number of worker threads taking jobs from a queue, so
+//! concurrent `prove` calls wait for a free worker instead of competing for
+//! memory and cores. The workers also build the trees of `prove` and
+//! `root`, which are limited to `MAX_LEAVES` leaves. `verify` and `status`
+//! are answered on the connection's own thread.
+//!
+//! `plot` names a file in the directory given to `with_plot_dir`; without
+//! one, the server reads no plots.
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+#[cfg(unix)]
+use std::os::unix::net::UnixListener;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use bellman::groth16::{CircuitShape, Proof, Parameters, PreparedVerifyingKey, prepare_verifying_key};
+use pairing::bn256::{Bn256, Fr};
+use rand::{OsRng, Rng};
+use serde_json::{self, Value};
+
+use error::{Error, Result};
+use merkle::merkletree::MerkleTree;
+use plot::merkle::{MerklePlot, PlotHeader};
+use spaceproof_merkle_tree::{self, Node};
+
+use super::{
+    ProveParams, ProveResult, VerifyParams, VerifyResult, RootParams, RootResult, Status,
+    Request, Response, RpcError,
+    PARSE_ERROR, INVALID_REQUEST, METHOD_NOT_FOUND, INVALID_PARAMS, SERVER_ERROR,
+};
+
+/// Most leaves a request may give.
+pub const MAX_LEAVES: usize = 1 << 20;
+
+/// The tree of a request, built by a worker.
+enum TreeSource {
+    Leaves(Vec<Fr>),
+    /// A plot inside the plot directory
+    Plot(PathBuf),
+}
+
+/// A proof with the leaf and root of its tree.
+struct Proved {
+    proof: Proof<Bn256>,
+    leaf: Fr,
+    root: Fr,
+}
+
+enum Job {
+    Prove {
+        tree: TreeSource,
+        seed: Vec<u32>,
+        position: u64,
+        reply: Sender<Result<Proved>>,
+    },
+    Root {
+        tree: TreeSource,
+        reply: Sender<Result<Fr>>,
+    },
+}
+
+#[derive(Default)]
+struct Counters {
+    pending: AtomicUsize,
+    proved: AtomicUsize,
+    verified: AtomicUsize,
+    failed: AtomicUsize,
+}
+
+pub struct Server {
+    pvk: PreparedVerifyingKey<Bn256>,
+    /// Height of the trees the parameters prove paths in
+    depth: usize,
+    jobs: Mutex<Sender<Job>>,
+    workers: usize,
+    counters: Counters,
+    /// Canonical path of the directory `plot` names files in
+    plot_dir: Option<PathBuf>,
+}
+
+/// Height of the tree `MerkleTree::from_vec` builds over `leaves` leaves.
+fn tree_height(leaves: usize) -> usize {
+    let mut height = 0;
+    let mut width = leaves;
+    while width > 1 {
+        width = (width + 1) / 2;
+        height += 1;
+    }
+    height
+}
+
+fn check_height(height: usize, depth: usize) -> Result<()> {
+    if height != depth {
+        return Err(Error::InvalidInput(format!("a tree of height {} does not fit parameters for height {}", height, depth)));
+    }
+    Ok(())
+}
+
+fn build_tree(source: TreeSource) -> Result<MerkleTree<Node>> {
+    match source {
+        TreeSource::Leaves(leaves) => Ok(spaceproof_merkle_tree::build_tree(leaves.into_iter().map(Node::new).collect())),
+        TreeSource::Plot(path) => {
+            let file = File::open(&path).map_err(|e| Error::InvalidInput(format!("could not open {}: {}", path.display(), e)))?;
+            Ok(MerklePlot::read(BufReader::new(file))?.tree().clone())
+        }
+    }
+}
+
+fn prove(params: &Parameters<Bn256>, shape: &CircuitShape<Bn256>, depth: usize, tree: TreeSource, seed: &[u32], position: u64) -> Result<Proved> {
+    let tree = build_tree(tree)?;
+    // a plot whose tree disagrees with its header
+    check_height(tree.height() as usize, depth)?;
+    let leaf = tree.gen_nth_proof(position as usize)
+        .ok_or(Error::InvalidInput(format!("position {} is outside the tree", position)))?
+        .value.hash;
+    let root = spaceproof_merkle_tree::tree_root(&tree)?;
+    let proof = spaceproof_merkle_tree::prove_with_shape(seed, params, shape, &tree, position)?;
+
+    Ok(Proved { proof, leaf, root })
+}
+
+fn work(params: Arc<Parameters<Bn256>>, shape: Arc<CircuitShape<Bn256>>, depth: usize, jobs: Arc<Mutex<Receiver<Job>>>) {
+    loop {
+        // the lock is released before proving
+        let job = match jobs.lock().expect("a worker panicked").recv() {
+            Ok(job) => job,
+            // the server is gone
+            Err(_) => return,
+        };
+        match job {
+            Job::Prove { tree, seed, position, reply } => {
+                let _ = reply.send(prove(&params, &shape, depth, tree, &seed, position));
+            }
+            Job::Root { tree, reply } => {
+                let _ = reply.send(build_tree(tree).and_then(|tree| spaceproof_merkle_tree::tree_root(&tree)));
+            }
+        }
+    }
+}
+
+fn rpc_error(e: Error) -> RpcError {
+    let code = match e {
+        Error::Hex(_) | Error::InvalidInput(_) => INVALID_PARAMS,
+        _ => SERVER_ERROR,
+    };
+    RpcError { code, message: e.to_string() }
+}
+
+fn fr_from_hex(value: &str) -> Result<Fr> {
+    Fr::from_hex(value).map_err(Error::InvalidInput)
+}
+
+impl Server {
+    /// Starts `workers` proving threads, at least one, sharing `params`,
+    /// which must be for trees of height `depth`.
+    pub fn new(params: Parameters<Bn256>, depth: usize, workers: usize) -> Result<Server> {
+        let shape = Arc::new(spaceproof_merkle_tree::circuit_shape(depth)?);
+        if params.l.len() != shape.num_aux() {
+            return Err(Error::InvalidInput(format!("the parameters are not for trees of height {}", depth)));
+        }
+        let workers = if workers == 0 { 1 } else { workers };
+        let pvk = prepare_verifying_key(&params.vk);
+        let params = Arc::new(params);
+        let (sender, receiver) = mpsc::channel();
+        let receiver = Arc::new(Mutex::new(receiver));
+        for _ in 0..workers {
+            let params = params.clone();
+            let shape = shape.clone();
+            let receiver = receiver.clone();
+            thread::spawn(move || work(params, shape, depth, receiver));
+        }
+
+        Ok(Server {
+            pvk,
+            depth,
+            jobs: Mutex::new(sender),
+            workers,
+            counters: Counters::default(),
+            plot_dir: None,
+        })
+    }
+
+    /// Lets requests name plots in `dir`, and nowhere else.
+    pub fn with_plot_dir<P: AsRef<Path>>(mut self, dir: P) -> io::Result<Server> {
+        self.plot_dir = Some(dir.as_ref().canonicalize()?);
+        Ok(self)
+    }
+
+    /// The plot `name` resolves to, if it is inside the plot directory.
+    fn plot_path(&self, name: &str) -> Result<PathBuf> {
+        let dir = self.plot_dir.as_ref().ok_or(Error::InvalidInput("the server reads no plots".to_string()))?;
+        // the same error whether the file is missing or outside, so that
+        // requests cannot probe the file system
+        let not_found = || Error::InvalidInput(format!("no plot {} in the plot directory", name));
+        let path = dir.join(name).canonicalize().map_err(|_| not_found())?;
+        if !path.starts_with(dir) {
+            return Err(not_found());
+        }
+
+        Ok(path)
+    }
+
+    /// The tree given either by its leaves or by a plot file. With `proving`,
+    /// the tree must also have the height of the parameters.
+    fn select_tree(&self, leaves: Option<Vec<String>>, plot: Option<String>, proving: bool) -> Result<TreeSource> {
+        match (leaves, plot) {
+            (Some(leaves), None) => {
+                if leaves.len() > MAX_LEAVES {
+                    return Err(Error::InvalidInput(format!("{} leaves are more than {}", leaves.len(), MAX_LEAVES)));
+                }
+                if proving {
+                    check_height(tree_height(leaves.len()), self.depth)?;
+                }
+                let mut nodes = Vec::with_capacity(leaves.len());
+                for leaf in leaves {
+                    nodes.push(fr_from_hex(&leaf)?);
+                }
+                Ok(TreeSource::Leaves(nodes))
+            }
+            (None, Some(plot)) => {
+                let path = self.plot_path(&plot)?;
+                if proving {
+                    let file = File::open(&path).map_err(|e| Error::InvalidInput(format!("could not open {}: {}", plot, e)))?;
+                    let header = PlotHeader::read(BufReader::new(file))
+                        .map_err(|e| Error::InvalidInput(format!("could not read {}: {}", plot, e)))?;
+                    check_height(header.height, self.depth)?;
+                }
+                Ok(TreeSource::Plot(path))
+            }
+            _ => Err(Error::InvalidInput("exactly one of leaves and plot is required".to_string())),
+        }
+    }
+
+    /// Runs the job `job` makes with its reply channel on a worker.
+    fn run<T, F: FnOnce(Sender<Result<T>>) -> Job>(&self, job: F) -> Result<T> {
+        let (reply, result) = mpsc::channel();
+        let sent = self.jobs.lock().expect("a connection panicked").send(job(reply));
+        match sent {
+            Ok(()) => result.recv().unwrap_or(Err(Error::InvalidInput("the prover stopped".to_string()))),
+            Err(_) => Err(Error::InvalidInput("the prover stopped".to_string())),
+        }
+    }
+
+    fn prove(&self, params: ProveParams) -> Result<ProveResult> {
+        let tree = self.select_tree(params.leaves, params.plot, true)?;
+        let seed = match params.seed {
+            Some(seed) => seed,
+            None => {
+                let mut rng = OsRng::new()?;
+                (0..8).map(|_| rng.gen()).collect()
+            }
+        };
+
+        let position = params.position;
+        self.counters.pending.fetch_add(1, Ordering::SeqCst);
+        let proved = self.run(|reply| Job::Prove { tree, seed, position, reply });
+        self.counters.pending.fetch_sub(1, Ordering::SeqCst);
+
+        let proved = match proved {
+            Ok(proved) => {
+                self.counters.proved.fetch_add(1, Ordering::SeqCst);
+                proved
+            }
+            Err(e) => {
+                self.counters.failed.fetch_add(1, Ordering::SeqCst);
+                return Err(e);
+            }
+        };
+        let mut v = vec![];
+        proved.proof.write(&mut v)?;
+
+        Ok(ProveResult {
+            proof: hex::encode(&v[..]),
+            leaf: proved.leaf.to_hex(),
+            root: proved.root.to_hex(),
+        })
+    }
+
+    fn verify(&self, params: VerifyParams) -> Result<VerifyResult> {
+        let proof = Proof::<Bn256>::read(&hex::decode(&params.proof)?[..])?;
+        let leaf = fr_from_hex(&params.leaf)?;
+        let root = fr_from_hex(&params.root)?;
+        let valid = spaceproof_merkle_tree::verify(&self.pvk, &proof, leaf, params.position, root)?;
+        self.counters.verified.fetch_add(1, Ordering::SeqCst);
+
+        Ok(VerifyResult { valid })
+    }
+
+    fn root(&self, params: RootParams) -> Result<RootResult> {
+        let tree = self.select_tree(params.leaves, params.plot, false)?;
+        let root = self.run(|reply| Job::Root { tree, reply })?;
+
+        Ok(RootResult {
+            root: root.to_hex()
+        })
+    }
+
+    pub fn status(&self) -> Status {
+        Status {
+            workers: self.workers,
+            pending: self.counters.pending.load(Ordering::SeqCst),
+            proved: self.counters.proved.load(Ordering::SeqCst) as u64,
+            verified: self.counters.verified.load(Ordering::SeqCst) as u64,
+            failed: self.counters.failed.load(Ordering::SeqCst) as u64,
+        }
+    }
+
+    fn dispatch(&self, method: &str, params: Value) -> ::std::result::Result<Value, RpcError> {
+        let invalid = |e: serde_json::Error| RpcError { code: INVALID_PARAMS, message: e.to_string() };
+        let result = match method {
+            "prove" => serde_json::to_value(self.prove(serde_json::from_value(params).map_err(invalid)?).map_err(rpc_error)?),
+            "verify" => serde_json::to_value(self.verify(serde_json::from_value(params).map_err(invalid)?).map_err(rpc_error)?),
+            "root" => serde_json::to_value(self.root(serde_json::from_value(params).map_err(invalid)?).map_err(rpc_error)?),
+            "status" => serde_json::to_value(self.status()),
+            _ => return Err(RpcError { code: METHOD_NOT_FOUND, message: format!("unknown method {}", method) }),
+        };
+
+        result.map_err(|e| RpcError { code: SERVER_ERROR, message: e.to_string() })
+    }
+
+    /// Answers one request line with one response line, without the newline.
+    pub fn handle(&self, line: &str) -> String {
+        let (id, outcome) = match serde_json::from_str::<Request>(line) {
+            Err(e) => (Value::Null, Err(RpcError { code: PARSE_ERROR, message: e.to_string() })),
+            Ok(ref request) if request.jsonrpc != "2.0" => {
+                (request.id.clone(), Err(RpcError { code: INVALID_REQUEST, message: "jsonrpc must be \"2.0\"".to_string() }))
+            }
+            Ok(request) => {
+                let outcome = self.dispatch(&request.method, request.params);
+                (request.id, outcome)
+            }
+        };
+
+        let (result, error) = match outcome {
+            Ok(result) => (Some(result), None),
+            Err(error) => (None, Some(error)),
+        };
+        let response = Response { jsonrpc: "2.0".to_string(), id, result, error };
+        serde_json::to_string(&response).expect("responses serialize")
+    }
+
+    fn connection<R: Read, W: Write>(&self, reader: R, mut writer: W) -> io::Result<()> {
+        for line in BufReader::new(reader).lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let mut response = self.handle(&line);
+            response.push('\n');
+            writer.write_all(response.as_bytes())?;
+            writer.flush()?;
+        }
+
+        Ok(())
+    }
+
+    /// Serves every connection on its own thread until `listener` fails.
+    pub fn serve(self: Arc<Self>, listener: TcpListener) -> io::Result<()> {
+        for stream in listener.incoming() {
+            let stream = stream?;
+            let server = self.clone();
+            thread::spawn(move || {
+                if let Ok(reader) = stream.try_clone() {
+                    let _ = server.connection(reader, stream);
+                }
+            });
+        }
+
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    pub fn serve_unix(self: Arc<Self>, listener: UnixListener) -> io::Result<()> {
+        for stream in listener.incoming() {
+            let stream = stream?;
+            let server = self.clone();
+            thread::spawn(move || {
+                if let Ok(reader) = stream.try_clone() {
+                    let _ = server.connection(reader, stream);
+                }
+            });
+        }
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_server_client() {
+    use super::Client;
+
+    let seed = vec![1u32, 1, 1, 1];
+    let params = spaceproof_merkle_tree::generate(&seed, 2).unwrap();
+    let server = Arc::new(Server::new(params, 2, 2).unwrap());
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    {
+        let server = server.clone();
+        thread::spawn(move || server.serve(listener));
+    }
+
+    let leaves: Vec<String> = (1..5u64).map(|i| spaceproof_merkle_tree::position_to_fr(i).to_hex()).collect();
+    let mut client = Client::connect(addr).unwrap();
+    let root = client.root(&RootParams { leaves: Some(leaves.clone()), plot: None }).unwrap();
+
+    // two clients prove at once
+    let handles: Vec<_> = (0..2u64).map(|position| {
+        let leaves = leaves.clone();
+        let seed = seed.clone();
+        thread::spawn(move || {
+            let mut client = Client::connect(addr).unwrap();
+            client.prove(&ProveParams { leaves: Some(leaves), plot: None, position, seed: Some(seed) }).unwrap()
+        })
+    }).collect();
+    for (position, handle) in handles.into_iter().enumerate() {
+        let proved = handle.join().unwrap();
+        assert_eq!(proved.root, root);
+        assert_eq!(proved.leaf, leaves[position]);
+
+        let mut verify = VerifyParams { proof: proved.proof, leaf: proved.leaf, position: position as u64, root: root.clone() };
+        assert!(client.verify(&verify).unwrap());
+        verify.position += 1;
+        assert!(!client.verify(&verify).unwrap());
+    }
+
+    match client.prove(&ProveParams { leaves: Some(leaves.clone()), plot: None, position: 4, seed: None }) {
+        Err(Error::Rpc(INVALID_PARAMS, _)) => {}
+        r => panic!("expected invalid params, got {:?}", r.map(|p| p.proof)),
+    }
+    // trees of another height are refused before they are proven
+    for count in &[2u64, 8] {
+        let leaves = (0..*count).map(|i| spaceproof_merkle_tree::position_to_fr(i).to_hex()).collect();
+        match client.prove(&ProveParams { leaves: Some(leaves), plot: None, position: 0, seed: None }) {
+            Err(Error::Rpc(INVALID_PARAMS, _)) => {}
+            r => panic!("expected invalid params, got {:?}", r.map(|p| p.proof)),
+        }
+    }
+    match client.call("mine", Value::Null) {
+        Err(Error::Rpc(METHOD_NOT_FOUND, _)) => {}
+        r => panic!("expected unknown method, got {:?}", r),
+    }
+
+    let status = client.status().unwrap();
+    assert_eq!(status, Status { workers: 2, pending: 0, proved: 2, verified: 4, failed: 1 });
+    assert!(server.handle("{").contains(&PARSE_ERROR.to_string()));
+}
+
+#[test]
+fn test_server_trees() {
+    use std::env;
+    use std::fs;
+
+    let seed = vec![1u32, 1, 1, 1];
+    let params = spaceproof_merkle_tree::generate(&seed, 2).unwrap();
+    let base = env::temp_dir().join(format!("spaceproof-server-{}", ::std::process::id()));
+    let dir = base.join("plots");
+    fs::create_dir_all(&dir).unwrap();
+    let plot = MerklePlot::create([7; 32], 2);
+    plot.write(File::create(dir.join("a.plot")).unwrap()).unwrap();
+    plot.write(File::create(base.join("outside.plot")).unwrap()).unwrap();
+    MerklePlot::create([8; 32], 3).write(File::create(dir.join("deep.plot")).unwrap()).unwrap();
+    let root = plot.root().to_hex();
+
+    let call = |server: &Server, params: RootParams| {
+        let value = serde_json::to_value(params).unwrap();
+        server.dispatch("root", value).map(|v| serde_json::from_value::<RootResult>(v).unwrap().root)
+    };
+    let plot = |name: &str| RootParams { leaves: None, plot: Some(name.to_string()) };
+
+    // without a plot directory no plot is read
+    let server = Server::new(params.clone(), 2, 1).unwrap();
+    assert_eq!(call(&server, plot("a.plot")).unwrap_err().code, INVALID_PARAMS);
+
+    let server = server.with_plot_dir(&dir).unwrap();
+    assert_eq!(call(&server, plot("a.plot")).unwrap(), root);
+    assert_eq!(call(&server, plot(dir.join("a.plot").to_str().unwrap())).unwrap(), root);
+    for name in &["../outside.plot", base.join("outside.plot").to_str().unwrap(), "missing.plot"] {
+        assert_eq!(call(&server, plot(name)).unwrap_err().code, INVALID_PARAMS);
+    }
+
+    // plots of another height are not proven
+    let prove = |name: &str| {
+        let params = ProveParams { leaves: None, plot: Some(name.to_string()), position: 0, seed: Some(seed.clone()) };
+        server.dispatch("prove", serde_json::to_value(params).unwrap())
+    };
+    assert!(prove("a.plot").is_ok());
+    assert_eq!(prove("deep.plot").unwrap_err().code, INVALID_PARAMS);
+
+    // nor do parameters serve trees of another height
+    assert!(Server::new(params.clone(), 3, 1).is_err());
+
+    let leaves = vec!["00".to_string(); MAX_LEAVES + 1];
+    assert_eq!(call(&server, RootParams { leaves: Some(leaves), plot: None }).unwrap_err().code, INVALID_PARAMS);
+
+    fs::remove_dir_all(&base).unwrap();
+}
@@ -12,7 +12,7 @@ use sapling_crypto::{
     circuit::{
         num::{AllocatedNum},
         baby_pedersen_hash,
-        boolean::{Boolean, AllocatedBit}
+        boolean::Boolean
     }
 };
 use pairing::{bn256::{Bn256, Fr}};
@@ -48,19 +48,23 @@ impl<'a, E: JubjubEngine> Circuit<E> for SpaceProofMerkleTreeCircuit<'a, E> {
             })
         )?;
         position.inputize(cs.namespace(|| "public input position"))?;
+        // the path follows the bits of the public position, which must be
+        // inside the tree
         let size_vec = position.into_bits_le_strict(cs.namespace(|| "position into bits"))?;
+        for (i, bit) in size_vec.iter().enumerate().skip(self.proof.len()) {
+            Boolean::enforce_equal(cs.namespace(|| format!("position bit {} is zero", i)),
+                bit,
+                &Boolean::constant(false)
+            )?;
+        }
         for i in 0..self.proof.len() {
             if let Some(ref element) = self.proof[i] {
                 let elt = AllocatedNum::alloc(cs.namespace(|| format!("elt {}",i)),
                     || Ok(*element))?;
-                let right_side = Boolean::from(AllocatedBit::alloc(
-                    cs.namespace(|| format!("position bit {}", i)),
-                    size_vec[i].get_value()).unwrap()
-                );
                 let (xl, xr) = AllocatedNum::conditionally_reverse(cs.namespace(|| format!("conditional reversal of preimage {}", i)),
                     &hash, 
                     &elt,
-                    &right_side
+                    &size_vec[i]
                 )?;
                 let mut preimage = vec![];
                 preimage.extend(xl.into_bits_le_strict(cs.namespace(|| format!("xl into bits {}",i)))?);
@@ -76,7 +80,6 @@ impl<'a, E: JubjubEngine> Circuit<E> for SpaceProofMerkleTreeCircuit<'a, E> {
         }
         //root node 
         hash.inputize(cs)?;
-        println!("Root hash {:?}", hash.get_value());
         Ok(())
     }
 }
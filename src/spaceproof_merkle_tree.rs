@@ -1,44 +1,40 @@
-use std::error::Error;
-use rand::{ChaChaRng, SeedableRng};
-use bellman::groth16::{Proof, Parameters, verify_proof, create_random_proof, prepare_verifying_key, generate_random_parameters};
+use rand::{ChaChaRng, OsRng, SeedableRng};
+use bellman::groth16::{Assignment, Proof, Parameters, ParameterSource, VerifyingKey, PreparedVerifyingKey, prepare_verifying_key, create_random_proof, create_random_proof_from_assignment, create_random_proof_with_shape, domain_exp, CircuitShape, generate_random_parameters, synthesize_assignment};
+#[cfg(test)]
 use num_bigint::BigInt;
+#[cfg(test)]
 use num_traits::Num;
-use bellman::{
-    Circuit,
-    SynthesisError
-    // SynthesisError,
-    // ConstraintSystem,
-};
-// use ff::{Field, PrimeField};
-use sapling_crypto::{
-    babyjubjub::{
-        JubjubEngine,
-        JubjubBn256
-    },
-    circuit::{
-        // num::{AllocatedNum},
-        // baby_pedersen_hash,
-        // boolean::{Boolean, AllocatedBit},
-        test::TestConstraintSystem
-    }
-};
+#[cfg(test)]
+use bellman::Circuit;
+#[cfg(test)]
+use bellman::groth16::verify_proof;
+use sapling_crypto::babyjubjub::JubjubBn256;
+#[cfg(test)]
+use sapling_crypto::circuit::test::TestConstraintSystem;
 use ff::{Field, PrimeField, PrimeFieldRepr};
-use pairing::{bn256::{Bn256, Fr, FrRepr},Engine};
+use pairing::bn256::{Bn256, Fr, FrRepr};
 
+#[cfg(test)]
 use rand::Rand;
-use rand::Rng;
+#[cfg(test)]
 use time::PreciseTime;
 
+use wasm_bindgen::prelude::*;
+
 use spaceproof_circuit::SpaceProofMerkleTreeCircuit;
 
-use merkle::digest::{Algorithm, Digest};
-use merkle::hashutils::{HashUtils, Hashable, BitIterator};
+use error::{Error, Result};
+use mpc::MPCParameters;
+use powersoftau::Accumulator;
+use merkle::digest::{Algorithm, PEDERSEN_BN256};
 use merkle::merkletree::MerkleTree;
-static DIGEST: &Algorithm = &Algorithm{};
+static DIGEST: &Algorithm = &PEDERSEN_BN256;
 
 #[derive(Serialize)]
 pub struct Generate{
-    pub params: String
+    pub params: String,
+    /// The verifying key alone, all a verifier needs
+    pub vk: String
 }
 
 #[derive(Serialize)]
@@ -46,24 +42,289 @@ pub struct SProof{
     pub proof: String
 }
 
-pub fn generate(seed_slice: &[u32], depth: usize) ->  Result<Parameters<Bn256>, SynthesisError> {
+#[derive(Serialize)]
+pub struct SRoot{
+    pub root: String
+}
+
+#[derive(Serialize)]
+pub struct SVerify{
+    pub result: bool
+}
+
+/// Generates Groth16 parameters for proving membership in trees of height
+/// `depth`.
+pub fn generate(seed_slice: &[u32], depth: usize) -> Result<Parameters<Bn256>> {
     let rng = &mut ChaChaRng::from_seed(seed_slice);
     let j_params = &JubjubBn256::new();
+    let params = generate_random_parameters::<Bn256, _, _>(blank_circuit(j_params, depth), rng)?;
+
+    Ok(params)
+}
+
+/// Starts a parameter ceremony for trees of height `depth`, see `mpc`.
+pub fn new_ceremony(depth: usize) -> Result<MPCParameters<Bn256>> {
+    let rng = &mut OsRng::new()?;
+    let j_params = &JubjubBn256::new();
+
+    MPCParameters::new(blank_circuit(j_params, depth), rng)
+}
+
+/// Starts a parameter ceremony for trees of height `depth` from the powers
+/// of a finished phase 1 ceremony, see `powersoftau`.
+pub fn ceremony_from_phase1(depth: usize, accumulator: &Accumulator<Bn256>) -> Result<MPCParameters<Bn256>> {
+    let j_params = &JubjubBn256::new();
+    let phase1 = accumulator.lagrange(domain_exp(blank_circuit(j_params, depth))?)?;
+
+    MPCParameters::from_phase1(blank_circuit(j_params, depth), &phase1)
+}
+
+/// Checks a ceremony started by `new_ceremony` for trees of height `depth`
+/// and returns the hashes of its contributions, see `MPCParameters::verify`.
+pub fn verify_ceremony(params: &MPCParameters<Bn256>, initial: &MPCParameters<Bn256>, depth: usize) -> Result<Vec<[u8; 64]>> {
+    let j_params = &JubjubBn256::new();
+
+    params.verify(blank_circuit(j_params, depth), initial)
+}
+
+/// Checks a ceremony started by `ceremony_from_phase1`, recomputing its
+/// initial parameters from the phase 1 powers.
+pub fn verify_ceremony_from_phase1(params: &MPCParameters<Bn256>, depth: usize, accumulator: &Accumulator<Bn256>) -> Result<Vec<[u8; 64]>> {
+    let j_params = &JubjubBn256::new();
+    let phase1 = accumulator.lagrange(domain_exp(blank_circuit(j_params, depth))?)?;
+
+    params.verify_from_phase1(blank_circuit(j_params, depth), &phase1)
+}
+
+/// The circuit without witness, for parameter generation.
+fn blank_circuit(j_params: &JubjubBn256, depth: usize) -> SpaceProofMerkleTreeCircuit<Bn256> {
     let mut proof_elts = vec![];
     for _ in 0..depth {
         proof_elts.push(Some(
-            pairing::bn256::Fr::zero(),
+            Fr::zero(),
         ));
     }
-     generate_random_parameters::<Bn256, _, _>(
-        SpaceProofMerkleTreeCircuit {
-            params: j_params,
-            position: None,
-            node: None,
-            proof: proof_elts,
-        },
-        rng,
-    )
+
+    SpaceProofMerkleTreeCircuit {
+        params: j_params,
+        position: None,
+        node: None,
+        proof: proof_elts,
+    }
+}
+
+/// Builds the Pedersen Merkle tree committing to `leaves`.
+pub fn build_tree(leaves: Vec<Node>) -> MerkleTree<Node> {
+    MerkleTree::from_vec(DIGEST, leaves)
+}
+
+/// Root of `tree` as a field element, a public input of the proof.
+pub fn tree_root(tree: &MerkleTree<Node>) -> Result<Fr> {
+    Node::newFromVec(tree.root_hash().clone()).map(|n| n.hash)
+}
+
+pub use spaceproof_verifier::position_to_fr;
+
+/// Siblings of the leaf at `position` from the leaf up to the root, in the
+/// form expected by `SpaceProofMerkleTreeCircuit::proof`.
+pub fn merkle_path(tree: &MerkleTree<Node>, position: u64) -> Result<Vec<Option<Fr>>> {
+    let proof = tree.gen_nth_proof(position as usize)
+        .ok_or(Error::InvalidInput(format!("position {} is outside the tree", position)))?;
+    let mut path = vec![];
+    for sibling in proof.get_sibling_vec() {
+        match sibling {
+            Some(s) => path.push(Some(Node::newFromVec(s)?.hash)),
+            None => path.push(None),
+        }
+    }
+
+    Ok(path)
+}
+
+/// Proves that the leaf at `position` is in `tree`. The public inputs are the
+/// leaf, the position and the root. `params` are `&Parameters` or, to keep
+/// the proving key on disk, `&MappedParameters`.
+pub fn prove<P: ParameterSource<Bn256>>(
+    seed_slice: &[u32],
+    params: P,
+    tree: &MerkleTree<Node>,
+    position: u64,
+) -> Result<Proof<Bn256>> {
+    let rng = &mut ChaChaRng::from_seed(seed_slice);
+    let proof = create_random_proof(circuit(&JubjubBn256::new(), tree, position)?, params, rng)?;
+
+    Ok(proof)
+}
+
+/// Computes the assignment of the circuit proving that the leaf at
+/// `position` is in `tree`, so the proof can be created from it by
+/// `prove_assignment`, possibly on another machine without the tree.
+pub fn witness(tree: &MerkleTree<Node>, position: u64) -> Result<Assignment<Bn256>> {
+    Ok(synthesize_assignment(circuit(&JubjubBn256::new(), tree, position)?)?)
+}
+
+/// Like `prove`, from the assignment computed by `witness` for a tree of
+/// height `depth`.
+pub fn prove_assignment<P: ParameterSource<Bn256>>(
+    seed_slice: &[u32],
+    params: P,
+    depth: usize,
+    assignment: &Assignment<Bn256>,
+) -> Result<Proof<Bn256>> {
+    let rng = &mut ChaChaRng::from_seed(seed_slice);
+    let j_params = &JubjubBn256::new();
+    let proof = create_random_proof_from_assignment(blank_circuit(j_params, depth), params, assignment, rng)?;
+
+    Ok(proof)
+}
+
+/// Records the constraints of the circuit for trees of height `depth` once,
+/// for `prove_with_shape`.
+pub fn circuit_shape(depth: usize) -> Result<CircuitShape<Bn256>> {
+    Ok(CircuitShape::new(blank_circuit(&JubjubBn256::new(), depth))?)
+}
+
+/// Like `prove`, with the constraints taken from `shape`, which must have
+/// been built by `circuit_shape` for the height of `tree`. Proving many times
+/// under the same parameters then skips synthesizing the constraints.
+pub fn prove_with_shape<P: ParameterSource<Bn256>>(
+    seed_slice: &[u32],
+    params: P,
+    shape: &CircuitShape<Bn256>,
+    tree: &MerkleTree<Node>,
+    position: u64,
+) -> Result<Proof<Bn256>> {
+    let rng = &mut ChaChaRng::from_seed(seed_slice);
+    let proof = create_random_proof_with_shape(shape, params, &witness(tree, position)?, rng)?;
+
+    Ok(proof)
+}
+
+/// The circuit with the witness for the leaf at `position`.
+fn circuit<'a>(j_params: &'a JubjubBn256, tree: &MerkleTree<Node>, position: u64) -> Result<SpaceProofMerkleTreeCircuit<'a, Bn256>> {
+    let leaf = tree.gen_nth_proof(position as usize)
+        .ok_or(Error::InvalidInput(format!("position {} is outside the tree", position)))?
+        .value;
+
+    Ok(SpaceProofMerkleTreeCircuit {
+        params: j_params,
+        position: Some(position_to_fr(position)),
+        node: Some(leaf.hash),
+        proof: merkle_path(tree, position)?,
+    })
+}
+
+/// Checks a proof that `leaf` sits at `position` in the tree with `root`.
+pub fn verify(
+    pvk: &PreparedVerifyingKey<Bn256>,
+    proof: &Proof<Bn256>,
+    leaf: Fr,
+    position: u64,
+    root: Fr,
+) -> Result<bool> {
+    Ok(spaceproof_verifier::verify(pvk, proof, leaf, position, root)?)
+}
+
+/// Builds a tree from leaves given as big endian hex field elements.
+fn tree_from_hex(leaves: &[String]) -> Result<MerkleTree<Node>> {
+    let mut nodes = Vec::with_capacity(leaves.len());
+    for leaf in leaves {
+        nodes.push(Node::new(Fr::from_hex(leaf).map_err(Error::InvalidInput)?));
+    }
+
+    Ok(build_tree(nodes))
+}
+
+fn generate_hex(seed_slice: &[u32], depth: u32) -> Result<Generate> {
+    let params = generate(seed_slice, depth as usize)?;
+    let mut v = vec![];
+    params.write(&mut v)?;
+    let mut vk = vec![];
+    params.vk.write(&mut vk)?;
+
+    Ok(Generate {
+        params: hex::encode(&v[..]),
+        vk: hex::encode(&vk[..])
+    })
+}
+
+fn root_hex(leaves: &[String]) -> Result<SRoot> {
+    let tree = tree_from_hex(leaves)?;
+
+    Ok(SRoot {
+        root: tree_root(&tree)?.to_hex()
+    })
+}
+
+fn prove_hex(seed_slice: &[u32], params: &str, leaves: &[String], position: u32) -> Result<SProof> {
+    let params = Parameters::<Bn256>::read(&hex::decode(params)?[..], true)?;
+    let tree = tree_from_hex(leaves)?;
+    let proof = prove(seed_slice, &params, &tree, position as u64)?;
+    let mut v = vec![];
+    proof.write(&mut v)?;
+
+    Ok(SProof {
+        proof: hex::encode(&v[..])
+    })
+}
+
+fn verify_hex(vk: &str, proof: &str, leaf: &str, position: u32, root: &str) -> Result<SVerify> {
+    // the verifying key is the first part of the parameters, so both work
+    let vk = VerifyingKey::<Bn256>::read(&hex::decode(vk)?[..])?;
+    let proof = Proof::<Bn256>::read(&hex::decode(proof)?[..])?;
+    let leaf = Fr::from_hex(leaf).map_err(Error::InvalidInput)?;
+    let root = Fr::from_hex(root).map_err(Error::InvalidInput)?;
+
+    Ok(SVerify {
+        result: verify(&prepare_verifying_key(&vk), &proof, leaf, position as u64, root)?
+    })
+}
+
+fn leaves_from_js(leaves: &JsValue) -> ::std::result::Result<Vec<String>, JsValue> {
+    leaves.into_serde().map_err(|e| JsValue::from_str(&format!("leaves must be an array of hex strings: {}", e)))
+}
+
+/// Generates parameters for trees of height `depth`, returned as
+/// `{ params: hex, vk: hex }`. Provers need `params`, verifiers only `vk`.
+#[wasm_bindgen(catch)]
+pub fn generate_spaceproof(seed_slice: &[u32], depth: u32) -> ::std::result::Result<JsValue, JsValue> {
+    let res = generate_hex(seed_slice, depth).map_err(|e| JsValue::from_str(&e.to_string()))?;
+    JsValue::from_serde(&res).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+/// Root of the tree built from `leaves`, an array of hex field elements,
+/// returned as `{ root: hex }`.
+#[wasm_bindgen(catch)]
+pub fn spaceproof_root(leaves: JsValue) -> ::std::result::Result<JsValue, JsValue> {
+    let res = root_hex(&leaves_from_js(&leaves)?).map_err(|e| JsValue::from_str(&e.to_string()))?;
+    JsValue::from_serde(&res).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+/// Proves that the leaf at `position` is in the tree built from `leaves`,
+/// returned as `{ proof: hex }`.
+#[wasm_bindgen(catch)]
+pub fn prove_spaceproof(
+    seed_slice: &[u32],
+    params: &str,
+    leaves: JsValue,
+    position: u32,
+) -> ::std::result::Result<JsValue, JsValue> {
+    let res = prove_hex(seed_slice, params, &leaves_from_js(&leaves)?, position).map_err(|e| JsValue::from_str(&e.to_string()))?;
+    JsValue::from_serde(&res).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+/// Checks a proof that `leaf` sits at `position` under `root` against the
+/// verifying key `vk`, returned as `{ result: bool }`.
+#[wasm_bindgen(catch)]
+pub fn verify_spaceproof(
+    vk: &str,
+    proof: &str,
+    leaf: &str,
+    position: u32,
+    root: &str,
+) -> ::std::result::Result<JsValue, JsValue> {
+    let res = verify_hex(vk, proof, leaf, position, root).map_err(|e| JsValue::from_str(&e.to_string()))?;
+    JsValue::from_serde(&res).map_err(|e| JsValue::from_str(&e.to_string()))
 }
 
 #[test]
@@ -94,14 +355,14 @@ fn test_spaceproof_merkle_circuit() {
 }
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Node{
-    hash: Fr,
+    pub hash: Fr,
     encoded: Vec<u8>,
 }
 
 impl Node{
-    fn new(hash : Fr) -> Node{
+    pub fn new(hash : Fr) -> Node{
         let mut buf = vec![];
-        hash.into_repr().write_le(&mut buf).unwrap();
+        hash.into_repr().write_le(&mut buf).expect("writing to a Vec cannot fail");
         let node = Node {
             hash: hash,
             encoded: buf,
@@ -109,16 +370,17 @@ impl Node{
         node
     }
 
-    fn newFromVec(s: Vec<u8>) ->  Result<Node, String> {
+    pub fn newFromVec(s: Vec<u8>) -> Result<Node> {
         let mut repr = FrRepr::default();
-        repr.read_le(&s[..]).map_err(|e| format!("could not read {}", &e))?;
-        let fr = Fr::from_repr(repr).map_err(|e| format!("could not convert into prime field: {}", &e))?;
+        repr.read_le(&s[..]).map_err(|e| Error::InvalidInput(format!("could not read {}", &e)))?;
+        let fr = Fr::from_repr(repr).map_err(|e| Error::InvalidInput(format!("could not convert into prime field: {}", &e)))?;
         
         Ok(
             Node::new(fr)
         )
     }
     
+    #[cfg(test)]
     fn newFromStr(s: &str) -> Node {
         Node::new(Fr::from_str(s).unwrap())
     }
@@ -242,4 +504,148 @@ fn test_space_generate_params() {
         ]).unwrap();
     println!("verify in {} ms", start.to(PreciseTime::now()).num_milliseconds() as f64);
     assert!(result);
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_prove_verify() {
+    let seed_slice = &[1u32, 1u32, 1u32, 1u32];
+    let leaves: Vec<_> = (1..9).map(|i| Node::newFromStr(&i.to_string())).collect();
+    let tree = build_tree(leaves.clone());
+    let root = tree_root(&tree).unwrap();
+
+    let params = generate(seed_slice, tree.height() as usize).unwrap();
+    let pvk = prepare_verifying_key(&params.vk);
+
+    let proof = prove(seed_slice, &params, &tree, 5).unwrap();
+    assert!(verify(&pvk, &proof, leaves[5].hash, 5, root).unwrap());
+    assert!(!verify(&pvk, &proof, leaves[4].hash, 4, root).unwrap());
+
+    match prove(seed_slice, &params, &tree, 8) {
+        Err(Error::InvalidInput(_)) => {}
+        _ => panic!("proving outside the tree must fail"),
+    }
+}
+
+#[test]
+fn test_proof_bound_to_position() {
+    let seed_slice = &[1u32, 1u32, 1u32, 1u32];
+    let leaves: Vec<_> = (1..9).map(|i| Node::newFromStr(&i.to_string())).collect();
+    let tree = build_tree(leaves.clone());
+    let root = tree_root(&tree).unwrap();
+
+    let params = generate(seed_slice, tree.height() as usize).unwrap();
+    let pvk = prepare_verifying_key(&params.vk);
+
+    let j_params = &JubjubBn256::new();
+    let circuit = |position| SpaceProofMerkleTreeCircuit {
+        params: j_params,
+        position: Some(position_to_fr(position)),
+        node: Some(leaves[4].hash),
+        proof: merkle_path(&tree, 4).unwrap(),
+    };
+    let mut cs = TestConstraintSystem::<Bn256>::new();
+    circuit(4).synthesize(&mut cs).unwrap();
+    assert!(cs.is_satisfied());
+    assert!(cs.verify(&[leaves[4].hash, position_to_fr(4), root]));
+
+    // claiming position 5 for the path of leaf 4 flips the lowest position
+    // bit, which the path follows
+    let five = position_to_fr(5);
+    cs.set("position/num", five);
+    cs.set("public input position/input variable", five);
+    cs.set("position into bits/bit 253/boolean", Fr::one());
+    assert!(!cs.is_satisfied());
+
+    // and so does a position outside the tree
+    let mut cs = TestConstraintSystem::<Bn256>::new();
+    circuit(4 + 8).synthesize(&mut cs).unwrap();
+    assert!(!cs.is_satisfied());
+
+    let rng = &mut ChaChaRng::from_seed(seed_slice);
+    let proof = create_random_proof(circuit(5), &params, rng).unwrap();
+    assert!(!verify(&pvk, &proof, leaves[4].hash, 5, root).unwrap());
+}
+
+#[test]
+fn test_prove_from_witness() {
+    let seed_slice = &[1u32, 1u32, 1u32, 1u32];
+    let leaves: Vec<_> = (1..9).map(|i| Node::newFromStr(&i.to_string())).collect();
+    let tree = build_tree(leaves.clone());
+    let root = tree_root(&tree).unwrap();
+    let depth = tree.height() as usize;
+
+    let params = generate(seed_slice, depth).unwrap();
+    let pvk = prepare_verifying_key(&params.vk);
+
+    let assignment = witness(&tree, 6).unwrap();
+    assert!(assignment.inputs == vec![leaves[6].hash, position_to_fr(6), root]);
+
+    let mut v = vec![];
+    assignment.write(&mut v).unwrap();
+    let assignment = Assignment::<Bn256>::read(&v[..]).unwrap();
+
+    let proof = prove_assignment(seed_slice, &params, depth, &assignment).unwrap();
+    assert!(proof == prove(seed_slice, &params, &tree, 6).unwrap());
+    assert!(verify(&pvk, &proof, leaves[6].hash, 6, root).unwrap());
+
+    assert!(prove_assignment(seed_slice, &params, depth + 1, &assignment).is_err());
+    assert!(witness(&tree, 8).is_err());
+}
+
+#[test]
+fn test_prove_with_shape() {
+    let seed_slice = &[1u32, 1u32, 1u32, 1u32];
+    let leaves: Vec<_> = (1..9).map(|i| Node::newFromStr(&i.to_string())).collect();
+    let tree = build_tree(leaves.clone());
+    let root = tree_root(&tree).unwrap();
+    let depth = tree.height() as usize;
+
+    let params = generate(seed_slice, depth).unwrap();
+    let pvk = prepare_verifying_key(&params.vk);
+    let shape = circuit_shape(depth).unwrap();
+
+    for &position in &[2, 7] {
+        let proof = prove_with_shape(seed_slice, &params, &shape, &tree, position).unwrap();
+        assert!(proof == prove(seed_slice, &params, &tree, position).unwrap());
+        assert!(verify(&pvk, &proof, leaves[position as usize].hash, position, root).unwrap());
+    }
+
+    let other = circuit_shape(depth + 1).unwrap();
+    assert!(prove_with_shape(seed_slice, &params, &other, &tree, 2).is_err());
+}
+
+#[test]
+fn test_verify_with_verifying_key_only() {
+    let seed_slice = &[1u32, 1u32, 1u32, 1u32];
+    let leaves: Vec<_> = (1..5).map(|i| Node::newFromStr(&i.to_string())).collect();
+    let tree = build_tree(leaves.clone());
+    let root = tree_root(&tree).unwrap();
+
+    let params = generate(seed_slice, tree.height() as usize).unwrap();
+    let proof = prove(seed_slice, &params, &tree, 3).unwrap();
+
+    let mut vk = vec![];
+    params.vk.write(&mut vk).unwrap();
+    let mut proof_bytes = vec![];
+    proof.write(&mut proof_bytes).unwrap();
+
+    assert!(spaceproof_verifier::verify_encoded(&vk, &proof_bytes, leaves[3].hash, 3, root).unwrap());
+    assert!(!spaceproof_verifier::verify_encoded(&vk, &proof_bytes, leaves[3].hash, 2, root).unwrap());
+    assert!(spaceproof_verifier::verify_encoded(&vk[..vk.len() - 1], &proof_bytes, leaves[3].hash, 3, root).is_err());
+}
+
+#[test]
+fn test_hex_prove_verify() {
+    let seed_slice = &[1u32, 1u32, 1u32, 1u32];
+    let leaves: Vec<String> = (1..5).map(|i| Node::newFromStr(&i.to_string()).hash.to_hex()).collect();
+
+    let generated = generate_hex(seed_slice, 2).unwrap();
+    let root = root_hex(&leaves).unwrap().root;
+    let proof = prove_hex(seed_slice, &generated.params, &leaves, 2).unwrap().proof;
+
+    assert!(verify_hex(&generated.vk, &proof, &leaves[2], 2, &root).unwrap().result);
+    assert!(!verify_hex(&generated.vk, &proof, &leaves[1], 2, &root).unwrap().result);
+    // the parameters start with the verifying key
+    assert!(verify_hex(&generated.params, &proof, &leaves[2], 2, &root).unwrap().result);
+    assert!(root_hex(&["zz".to_string()]).is_err());
+}
@@ -0,0 +1,261 @@
+use rand::{ChaChaRng, SeedableRng, Rand};
+use bellman::groth16::{Proof, Parameters, PreparedVerifyingKey, verify_proof, create_random_proof, generate_random_parameters};
+use bellman::{
+    Circuit,
+    SynthesisError,
+    ConstraintSystem,
+};
+use blake2_rfc::blake2s::Blake2s;
+use ff::{Field, PrimeField, PrimeFieldRepr};
+use pairing::{bn256::{Bn256, Fr, FrRepr}, Engine};
+
+use error::Error;
+
+/// Seed of the round constants, so that every node derives the same permutation.
+const VDF_CONSTANTS_SEED: &'static [u32] = &[0x76646600, 0x6d696d63, 0x626e3235, 0x36000000];
+
+/// Personalization prepended when hashing a challenge into the field.
+const VDF_CHALLENGE_PERSONALIZATION: &'static [u8; 8] = b"SPVdfCh_";
+
+/// One round of the MiMC-style permutation `x -> (x + c)^5`.
+///
+/// The exponent 5 is used because `gcd(5, r - 1) = 1` for the bn256 scalar
+/// field, so the round is a permutation; `x^3` is not over this field.
+fn mimc5_round<E: Engine>(x: E::Fr, constant: &E::Fr) -> E::Fr {
+    let mut t = x;
+    t.add_assign(constant);
+    let mut t4 = t;
+    t4.square();
+    t4.square();
+    t4.mul_assign(&t);
+    t4
+}
+
+/// Sequentially applies `rounds` MiMC rounds to `x`.
+///
+/// ```text
+/// function Eval(x ⦂ Fr) {
+///     for i from 0 up to rounds - 1 {
+///         x := (x + Ci)^5
+///     }
+///     return x
+/// }
+/// ```
+pub fn mimc5<E: Engine>(mut x: E::Fr, constants: &[E::Fr]) -> E::Fr {
+    for c in constants {
+        x = mimc5_round::<E>(x, c);
+    }
+    x
+}
+
+/// Circuit proving that `output` is the result of evaluating the delay
+/// function on `input`. Both values are public inputs.
+pub struct VdfCircuit<'a, E: Engine> {
+    // challenge fed into the delay function, public
+    pub input: Option<E::Fr>,
+    // round constants, one per sequential step
+    pub constants: &'a [E::Fr],
+}
+
+impl<'a, E: Engine> Circuit<E> for VdfCircuit<'a, E> {
+    fn synthesize<CS: ConstraintSystem<E>>(self, cs: &mut CS) -> Result<(), SynthesisError> {
+        let mut x_value = self.input;
+        let mut x = cs.alloc_input(|| "input", || {
+            x_value.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+
+        for (i, c) in self.constants.iter().enumerate() {
+            let cs = &mut cs.namespace(|| format!("round {}", i));
+            let last = i == self.constants.len() - 1;
+
+            // t2 = (x + Ci)^2
+            let t2_value = x_value.map(|mut e| {
+                e.add_assign(c);
+                e.square();
+                e
+            });
+            let t2 = cs.alloc(|| "t2", || {
+                t2_value.ok_or(SynthesisError::AssignmentMissing)
+            })?;
+            cs.enforce(
+                || "t2 = (x + Ci)^2",
+                |lc| lc + x + (*c, CS::one()),
+                |lc| lc + x + (*c, CS::one()),
+                |lc| lc + t2
+            );
+
+            // t4 = t2^2
+            let t4_value = t2_value.map(|mut e| {
+                e.square();
+                e
+            });
+            let t4 = cs.alloc(|| "t4", || {
+                t4_value.ok_or(SynthesisError::AssignmentMissing)
+            })?;
+            cs.enforce(
+                || "t4 = t2^2",
+                |lc| lc + t2,
+                |lc| lc + t2,
+                |lc| lc + t4
+            );
+
+            // new_x = t4 * (x + Ci)
+            let new_x_value = x_value.map(|e| mimc5_round::<E>(e, c));
+            let new_x = if last {
+                // The image of the last round is the delay function output.
+                cs.alloc_input(|| "output", || {
+                    new_x_value.ok_or(SynthesisError::AssignmentMissing)
+                })?
+            } else {
+                cs.alloc(|| "new_x", || {
+                    new_x_value.ok_or(SynthesisError::AssignmentMissing)
+                })?
+            };
+            cs.enforce(
+                || "new_x = t4 * (x + Ci)",
+                |lc| lc + t4,
+                |lc| lc + x + (*c, CS::one()),
+                |lc| lc + new_x
+            );
+
+            x = new_x;
+            x_value = new_x_value;
+        }
+
+        Ok(())
+    }
+}
+
+/// A verifiable delay function over `bn256::Fr`.
+///
+/// `eval` runs the sequential MiMC chain natively, `prove` produces a Groth16
+/// proof that the output was computed correctly and `verify` checks it
+/// without repeating the sequential work.
+pub struct Vdf {
+    constants: Vec<Fr>,
+}
+
+impl Vdf {
+    /// Creates a delay function with the given number of sequential rounds.
+    pub fn new(rounds: usize) -> Vdf {
+        assert!(rounds > 0, "a delay function needs at least one round");
+        let rng = &mut ChaChaRng::from_seed(VDF_CONSTANTS_SEED);
+        Vdf {
+            constants: (0..rounds).map(|_| Fr::rand(rng)).collect(),
+        }
+    }
+
+    /// Number of sequential rounds, i.e. the delay parameter.
+    pub fn rounds(&self) -> usize {
+        self.constants.len()
+    }
+
+    /// Round constants of the underlying permutation.
+    pub fn constants(&self) -> &[Fr] {
+        &self.constants
+    }
+
+    /// Maps an arbitrary challenge (e.g. a space-proof challenge) into the field.
+    pub fn input_from_challenge(challenge: &[u8]) -> Fr {
+        let mut h = Blake2s::new(32);
+        h.update(VDF_CHALLENGE_PERSONALIZATION);
+        h.update(challenge);
+        let digest = h.finalize();
+
+        let mut repr = FrRepr::default();
+        repr.read_le(digest.as_bytes()).unwrap();
+        // clear the top bits so the value is always below the modulus
+        repr.as_mut()[3] &= 0x0fffffffffffffff;
+        Fr::from_repr(repr).unwrap()
+    }
+
+    /// Evaluates the delay function natively.
+    pub fn eval(&self, input: Fr) -> Fr {
+        mimc5::<Bn256>(input, &self.constants)
+    }
+
+    /// Generates Groth16 parameters for the delay circuit.
+    pub fn generate(&self, seed_slice: &[u32]) -> Result<Parameters<Bn256>, Error> {
+        let rng = &mut ChaChaRng::from_seed(seed_slice);
+        let params = generate_random_parameters::<Bn256, _, _>(
+            VdfCircuit {
+                input: None,
+                constants: &self.constants,
+            },
+            rng,
+        )?;
+
+        Ok(params)
+    }
+
+    /// Evaluates the delay function and proves the evaluation.
+    pub fn prove(
+        &self,
+        seed_slice: &[u32],
+        params: &Parameters<Bn256>,
+        input: Fr,
+    ) -> Result<(Fr, Proof<Bn256>), Error> {
+        let rng = &mut ChaChaRng::from_seed(seed_slice);
+        let output = self.eval(input);
+        let proof = create_random_proof(
+            VdfCircuit {
+                input: Some(input),
+                constants: &self.constants,
+            },
+            params,
+            rng
+        )?;
+
+        Ok((output, proof))
+    }
+
+    /// Checks that `output` is the delay function evaluated on `input`.
+    pub fn verify(
+        &self,
+        pvk: &PreparedVerifyingKey<Bn256>,
+        input: Fr,
+        output: Fr,
+        proof: &Proof<Bn256>,
+    ) -> Result<bool, Error> {
+        let result = verify_proof(pvk, proof, &[input, output])?;
+
+        Ok(result)
+    }
+}
+
+#[test]
+fn test_vdf_circuit() {
+    use sapling_crypto::circuit::test::TestConstraintSystem;
+
+    let vdf = Vdf::new(16);
+    let input = Vdf::input_from_challenge(b"challenge");
+    let mut cs = TestConstraintSystem::<Bn256>::new();
+    VdfCircuit {
+        input: Some(input),
+        constants: vdf.constants(),
+    }.synthesize(&mut cs).unwrap();
+
+    assert!(cs.is_satisfied());
+    assert_eq!(cs.num_constraints(), 3 * 16);
+    assert_eq!(cs.num_inputs(), 3);
+    assert_eq!(cs.get_input(2, "round 15/output"), vdf.eval(input));
+}
+
+#[test]
+fn test_vdf_prove_verify() {
+    use bellman::groth16::prepare_verifying_key;
+
+    let seed_slice = &[1u32, 1u32, 1u32, 1u32];
+    let vdf = Vdf::new(32);
+    let params = vdf.generate(seed_slice).unwrap();
+    let pvk = prepare_verifying_key(&params.vk);
+
+    let input = Vdf::input_from_challenge(b"challenge");
+    let (output, proof) = vdf.prove(seed_slice, &params, input).unwrap();
+    assert_eq!(output, vdf.eval(input));
+    assert!(vdf.verify(&pvk, input, output, &proof).unwrap());
+
+    let mut wrong = output;
+    wrong.add_assign(&Fr::one());
+    assert!(!vdf.verify(&pvk, input, wrong, &proof).unwrap());
+}
@@ -0,0 +1,107 @@
+//! Verifier-only build of the Merkle space proofs.
+//!
+//! Checks proofs made by `rust_miximus::prove` against a Groth16
+//! `VerifyingKey` alone. Neither the prover, the full `Parameters` nor the
+//! Jubjub tables are compiled in, and without the default `std` feature the
+//! crate is `no_std` so it can run on embedded devices.
+//!
+//! `verifier/wasm` exports it to JavaScript.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate bellman;
+extern crate ff;
+extern crate pairing;
+
+// Without `std`, `::std` paths resolve to `core`.
+#[cfg(not(feature = "std"))]
+mod std {
+    pub use core::fmt;
+}
+
+use std::fmt;
+
+use bellman::SynthesisError;
+use bellman::groth16::{Proof, VerifyingKey, PreparedVerifyingKey, prepare_verifying_key, verify_proof};
+use ff::io;
+use ff::PrimeField;
+use pairing::bn256::{Bn256, Fr, FrRepr};
+
+/// Errors returned while verifying.
+#[derive(Debug)]
+pub enum Error {
+    /// The verifying key or the proof could not be decoded
+    Io(io::Error),
+    /// The verifying key does not fit the public inputs
+    Synthesis(SynthesisError),
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Error {
+        Error::Io(e)
+    }
+}
+
+impl From<SynthesisError> for Error {
+    fn from(e: SynthesisError) -> Error {
+        Error::Synthesis(e)
+    }
+}
+
+#[cfg(feature = "std")]
+impl ::std::error::Error for Error {
+    fn description(&self) -> &str {
+        match *self {
+            Error::Io(_) => "could not decode the verifying key or the proof",
+            Error::Synthesis(_) => "could not verify the proof",
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::Io(ref e) => write!(f, "could not decode: {}", e),
+            Error::Synthesis(ref e) => write!(f, "could not verify: {}", e),
+        }
+    }
+}
+
+/// The position as the field element used for the public input.
+pub fn position_to_fr(position: u64) -> Fr {
+    Fr::from_repr(FrRepr::from(position)).expect("a u64 is a field element")
+}
+
+/// Checks a proof that `leaf` sits at `position` in the tree with `root`.
+pub fn verify(
+    pvk: &PreparedVerifyingKey<Bn256>,
+    proof: &Proof<Bn256>,
+    leaf: Fr,
+    position: u64,
+    root: Fr,
+) -> Result<bool, SynthesisError> {
+    verify_proof(
+        pvk,
+        proof,
+        &[
+            leaf,
+            position_to_fr(position),
+            root
+        ])
+}
+
+/// Like `verify`, with the verifying key and the proof given as written by
+/// `VerifyingKey::write` and `Proof::write`. As the verifying key is the
+/// first part of the parameters, a parameter file works as well.
+pub fn verify_encoded(
+    vk: &[u8],
+    proof: &[u8],
+    leaf: Fr,
+    position: u64,
+    root: Fr,
+) -> Result<bool, Error> {
+    let vk = VerifyingKey::<Bn256>::read(vk)?;
+    let proof = Proof::<Bn256>::read(proof)?;
+
+    Ok(verify(&prepare_verifying_key(&vk), &proof, leaf, position, root)?)
+}
@@ -0,0 +1,33 @@
+//! `spaceproof-verifier` for JavaScript.
+//!
+//! Exports `verify_spaceproof`; build the package with
+//! `wasm-pack build verifier/wasm`.
+
+extern crate ff;
+extern crate hex;
+extern crate pairing;
+extern crate spaceproof_verifier;
+extern crate wasm_bindgen;
+
+use ff::PrimeField;
+use pairing::bn256::Fr;
+use spaceproof_verifier::verify_encoded;
+use wasm_bindgen::prelude::*;
+
+/// Verifies a proof against a verifying key. The key, the proof, the leaf and
+/// the root are hex, field elements big endian like `Fr::to_hex`.
+#[wasm_bindgen]
+pub fn verify_spaceproof(
+    vk: &str,
+    proof: &str,
+    leaf: &str,
+    position: u32,
+    root: &str,
+) -> Result<bool, JsValue> {
+    let vk = hex::decode(vk).map_err(|e| JsValue::from_str(&format!("invalid verifying key hex: {}", e)))?;
+    let proof = hex::decode(proof).map_err(|e| JsValue::from_str(&format!("invalid proof hex: {}", e)))?;
+    let leaf = Fr::from_hex(leaf).map_err(|e| JsValue::from_str(&format!("invalid leaf: {}", e)))?;
+    let root = Fr::from_hex(root).map_err(|e| JsValue::from_str(&format!("invalid root: {}", e)))?;
+
+    verify_encoded(&vk, &proof, leaf, position as u64, root).map_err(|e| JsValue::from_str(&e.to_string()))
+}